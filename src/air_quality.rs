@@ -0,0 +1,347 @@
+use eframe::egui::Color32;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+
+// EPA breakpoints: (concentration low, concentration high, index low, index high).
+const PM25_BREAKPOINTS: &[(f64, f64, f64, f64)] = &[
+    (0.0, 9.0, 0.0, 50.0),
+    (9.1, 35.4, 51.0, 100.0),
+    (35.5, 55.4, 101.0, 150.0),
+    (55.5, 125.4, 151.0, 200.0),
+    (125.5, 225.4, 201.0, 300.0),
+    (225.5, 325.4, 301.0, 500.0),
+];
+const PM10_BREAKPOINTS: &[(f64, f64, f64, f64)] = &[
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 154.0, 51.0, 100.0),
+    (155.0, 254.0, 101.0, 150.0),
+    (255.0, 354.0, 151.0, 200.0),
+    (355.0, 424.0, 201.0, 300.0),
+    (425.0, 604.0, 301.0, 500.0),
+];
+// Ozone (8-hour) and NO2 (1-hour) are defined in ppb; OWM and Open-Meteo
+// report µg/m³, converted at 25 °C.
+const O3_BREAKPOINTS: &[(f64, f64, f64, f64)] = &[
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 70.0, 51.0, 100.0),
+    (71.0, 85.0, 101.0, 150.0),
+    (86.0, 105.0, 151.0, 200.0),
+    (106.0, 200.0, 201.0, 300.0),
+];
+const NO2_BREAKPOINTS: &[(f64, f64, f64, f64)] = &[
+    (0.0, 53.0, 0.0, 50.0),
+    (54.0, 100.0, 51.0, 100.0),
+    (101.0, 360.0, 101.0, 150.0),
+    (361.0, 649.0, 151.0, 200.0),
+    (650.0, 1249.0, 201.0, 300.0),
+    (1250.0, 2049.0, 301.0, 500.0),
+];
+const O3_UG_PER_PPB: f64 = 1.96;
+const NO2_UG_PER_PPB: f64 = 1.88;
+
+const OPEN_METEO_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+const OPEN_METEO_POLLUTANTS: &str = "pm2_5,pm10,ozone,nitrogen_dioxide";
+const FORECAST_DAYS: u32 = 4;
+
+#[derive(Debug, Deserialize)]
+struct AirPollutionResponse {
+    list: Vec<AirPollutionEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirPollutionEntry {
+    dt: i64,
+    components: Components,
+}
+
+#[derive(Debug, Deserialize)]
+struct Components {
+    pm2_5: f64,
+    pm10: f64,
+    #[serde(default)]
+    o3: f64,
+    #[serde(default)]
+    no2: f64,
+}
+
+// Open-Meteo's concentrations are also µg/m³, but any of them can be null
+// where the model has no value.
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    current: Option<OpenMeteoValues>,
+    hourly: Option<OpenMeteoSeries>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoValues {
+    time: i64,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    nitrogen_dioxide: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoSeries {
+    time: Vec<i64>,
+    pm2_5: Vec<Option<f64>>,
+    pm10: Vec<Option<f64>>,
+    ozone: Vec<Option<f64>>,
+    nitrogen_dioxide: Vec<Option<f64>>,
+}
+
+impl OpenMeteoValues {
+    fn sample(&self) -> Option<AqiSample> {
+        if self.pm2_5.is_none() && self.pm10.is_none() {
+            return None;
+        }
+        let (aqi, dominant) = us_aqi(&Components {
+            pm2_5: self.pm2_5.unwrap_or_default(),
+            pm10: self.pm10.unwrap_or_default(),
+            o3: self.ozone.unwrap_or_default(),
+            no2: self.nitrogen_dioxide.unwrap_or_default(),
+        });
+        Some(AqiSample { dt: self.time, aqi, dominant })
+    }
+}
+
+impl OpenMeteoSeries {
+    fn samples(&self) -> Vec<AqiSample> {
+        let at = |series: &[Option<f64>], i: usize| series.get(i).copied().flatten();
+        self.time
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &time)| {
+                OpenMeteoValues {
+                    time,
+                    pm2_5: at(&self.pm2_5, i),
+                    pm10: at(&self.pm10, i),
+                    ozone: at(&self.ozone, i),
+                    nitrogen_dioxide: at(&self.nitrogen_dioxide, i),
+                }
+                .sample()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct AqiSample {
+    pub dt: i64,
+    pub aqi: f64,
+    /// The pollutant behind the index, e.g. "PM2.5".
+    pub dominant: &'static str,
+}
+
+/// Current US AQI, from the OWM air pollution API with a key and from
+/// Open-Meteo without one.
+pub async fn get_air_quality(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    api_key: Option<&str>,
+) -> Result<AqiSample, WeatherError> {
+    let Some(api_key) = api_key else {
+        let response = get_open_meteo(client, lat, lon, "current").await?;
+        return response
+            .current
+            .as_ref()
+            .and_then(OpenMeteoValues::sample)
+            .ok_or_else(|| WeatherError::Deserialize("no air quality reading".to_string()));
+    };
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/air_pollution?lat={}&lon={}&appid={}",
+        lat, lon, api_key
+    );
+    let res = client.get(&url).send().await?.error_for_status()?;
+    let current: AirPollutionResponse = res.json().await?;
+    let entry = current
+        .list
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::Deserialize("no air quality reading".to_string()))?;
+    let (aqi, dominant) = us_aqi(&entry.components);
+    Ok(AqiSample { dt: entry.dt, aqi, dominant })
+}
+
+/// Hourly US AQI forecast for roughly the next four days, from the same
+/// source as [`get_air_quality`].
+pub async fn get_air_quality_forecast(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    api_key: Option<&str>,
+) -> Result<Vec<AqiSample>, WeatherError> {
+    let Some(api_key) = api_key else {
+        let response = get_open_meteo(client, lat, lon, "hourly").await?;
+        return Ok(response.hourly.map(|hourly| hourly.samples()).unwrap_or_default());
+    };
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/air_pollution/forecast?lat={}&lon={}&appid={}",
+        lat, lon, api_key
+    );
+    let res = client.get(&url).send().await?.error_for_status()?;
+    let forecast: AirPollutionResponse = res.json().await?;
+
+    let samples = forecast
+        .list
+        .into_iter()
+        .map(|entry| {
+            let (aqi, dominant) = us_aqi(&entry.components);
+            AqiSample { dt: entry.dt, aqi, dominant }
+        })
+        .collect();
+    Ok(samples)
+}
+
+/// `section` is "current" or "hourly".
+async fn get_open_meteo(client: &Client, lat: f64, lon: f64, section: &str) -> Result<OpenMeteoResponse, WeatherError> {
+    let res = client
+        .get(OPEN_METEO_URL)
+        .query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            (section, OPEN_METEO_POLLUTANTS.to_string()),
+            ("forecast_days", FORECAST_DAYS.to_string()),
+            ("timeformat", "unixtime".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(res.json().await?)
+}
+
+// OWM's own `aqi` is a 1-5 scale, so the familiar 0-500 US index is derived
+// from the pollutant concentrations instead: the highest sub-index wins.
+fn us_aqi(components: &Components) -> (f64, &'static str) {
+    [
+        (sub_index((components.pm2_5 * 10.0).floor() / 10.0, PM25_BREAKPOINTS), "PM2.5"),
+        (sub_index(components.pm10.floor(), PM10_BREAKPOINTS), "PM10"),
+        (sub_index((components.o3 / O3_UG_PER_PPB).floor(), O3_BREAKPOINTS), "Ozone"),
+        (sub_index((components.no2 / NO2_UG_PER_PPB).floor(), NO2_BREAKPOINTS), "NO₂"),
+    ]
+    .into_iter()
+    .fold((0.0, "PM2.5"), |best, next| if next.0 > best.0 { next } else { best })
+}
+
+fn sub_index(concentration: f64, breakpoints: &[(f64, f64, f64, f64)]) -> f64 {
+    for &(c_low, c_high, i_low, i_high) in breakpoints {
+        if concentration <= c_high {
+            let concentration = concentration.max(c_low);
+            return ((i_high - i_low) / (c_high - c_low) * (concentration - c_low) + i_low).round();
+        }
+    }
+    // Off the top of the scale.
+    500.0
+}
+
+/// EPA category name for an AQI value.
+pub fn aqi_category(aqi: f64) -> &'static str {
+    match aqi as u32 {
+        0..=50 => "Good",
+        51..=100 => "Moderate",
+        101..=150 => "Unhealthy for sensitive groups",
+        151..=200 => "Unhealthy",
+        201..=300 => "Very unhealthy",
+        _ => "Hazardous",
+    }
+}
+
+/// The EPA's color for the category, for a band behind the index.
+pub fn aqi_color(aqi: f64) -> Color32 {
+    match aqi as u32 {
+        0..=50 => Color32::from_rgb(0, 228, 0),
+        51..=100 => Color32::from_rgb(255, 255, 0),
+        101..=150 => Color32::from_rgb(255, 126, 0),
+        151..=200 => Color32::from_rgb(255, 0, 0),
+        201..=300 => Color32::from_rgb(143, 63, 151),
+        _ => Color32::from_rgb(126, 0, 35),
+    }
+}
+
+/// Short EPA health advice for the category.
+pub fn aqi_advisory(aqi: f64) -> &'static str {
+    match aqi as u32 {
+        0..=50 => "Air quality is satisfactory.",
+        51..=100 => "Unusually sensitive people should consider limiting prolonged exertion outdoors.",
+        101..=150 => "Children, older adults and people with heart or lung disease should reduce prolonged exertion outdoors.",
+        151..=200 => "Everyone should reduce prolonged exertion outdoors; sensitive groups should avoid it.",
+        201..=300 => "Everyone should avoid prolonged exertion outdoors; sensitive groups should stay inside.",
+        _ => "Everyone should avoid all physical activity outdoors.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(pm2_5: f64, pm10: f64, o3: f64, no2: f64) -> Components {
+        Components { pm2_5, pm10, o3, no2 }
+    }
+
+    #[test]
+    fn sub_index_meets_the_epa_breakpoints() {
+        assert_eq!(sub_index(0.0, PM25_BREAKPOINTS), 0.0);
+        assert_eq!(sub_index(9.0, PM25_BREAKPOINTS), 50.0);
+        assert_eq!(sub_index(9.1, PM25_BREAKPOINTS), 51.0);
+        assert_eq!(sub_index(35.4, PM25_BREAKPOINTS), 100.0);
+        assert_eq!(sub_index(35.5, PM25_BREAKPOINTS), 101.0);
+        assert_eq!(sub_index(325.4, PM25_BREAKPOINTS), 500.0);
+        assert_eq!(sub_index(1000.0, PM25_BREAKPOINTS), 500.0);
+        assert_eq!(sub_index(154.0, PM10_BREAKPOINTS), 100.0);
+        assert_eq!(sub_index(155.0, PM10_BREAKPOINTS), 101.0);
+    }
+
+    #[test]
+    fn sub_index_interpolates_within_a_band() {
+        // (100 - 51) / (35.4 - 9.1) * (12.0 - 9.1) + 51 = 56.4
+        assert_eq!(sub_index(12.0, PM25_BREAKPOINTS), 56.0);
+    }
+
+    #[test]
+    fn concentrations_are_truncated_before_lookup() {
+        // 9.05 would fall between the first two bands; the EPA truncates it to 9.0.
+        assert_eq!(us_aqi(&components(9.05, 0.0, 0.0, 0.0)), (50.0, "PM2.5"));
+        assert_eq!(us_aqi(&components(0.0, 54.9, 0.0, 0.0)), (50.0, "PM10"));
+    }
+
+    #[test]
+    fn highest_sub_index_wins() {
+        assert_eq!(us_aqi(&components(12.0, 20.0, 0.0, 0.0)), (56.0, "PM2.5"));
+        assert_eq!(us_aqi(&components(5.0, 200.0, 0.0, 0.0)).1, "PM10");
+        // 200 µg/m³ of ozone is 102 ppb.
+        assert_eq!(us_aqi(&components(5.0, 20.0, 200.0, 0.0)), (192.0, "Ozone"));
+        assert_eq!(us_aqi(&components(5.0, 20.0, 0.0, 400.0)).1, "NO₂");
+        assert_eq!(us_aqi(&components(0.0, 0.0, 0.0, 0.0)), (0.0, "PM2.5"));
+    }
+
+    #[test]
+    fn categories_change_at_the_band_edges() {
+        assert_eq!(aqi_category(50.0), "Good");
+        assert_eq!(aqi_category(51.0), "Moderate");
+        assert_eq!(aqi_category(101.0), "Unhealthy for sensitive groups");
+        assert_eq!(aqi_category(200.0), "Unhealthy");
+        assert_eq!(aqi_category(300.0), "Very unhealthy");
+        assert_eq!(aqi_category(301.0), "Hazardous");
+        assert_eq!(aqi_color(101.0), Color32::from_rgb(255, 126, 0));
+    }
+
+    #[test]
+    fn open_meteo_hours_without_particulates_are_left_out() {
+        let response: OpenMeteoResponse = serde_json::from_str(
+            r#"{"hourly": {
+                "time": [1760700000, 1760703600, 1760707200],
+                "pm2_5": [12.0, null, 40.0],
+                "pm10": [20.0, null, null],
+                "ozone": [null, 50.0, 60.0],
+                "nitrogen_dioxide": [null, null, 10.0]
+            }}"#,
+        )
+        .unwrap();
+        assert!(response.current.is_none());
+        let samples: Vec<_> =
+            response.hourly.unwrap().samples().iter().map(|sample| (sample.dt, sample.aqi)).collect();
+        assert_eq!(samples, [(1760700000, 56.0), (1760707200, 112.0)]);
+    }
+}
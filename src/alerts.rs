@@ -0,0 +1,280 @@
+#[cfg(feature = "gui")]
+use std::cmp::Ordering;
+#[cfg(feature = "gui")]
+use std::time::Duration;
+#[cfg(feature = "gui")]
+use chrono::Local;
+#[cfg(feature = "gui")]
+use eframe::egui;
+#[cfg(feature = "gui")]
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use crate::fetch::Fetch;
+#[cfg(feature = "gui")]
+use crate::nws::{get_alert_areas, AlertArea, AlertAreas};
+use crate::time_format::local_weekday_time;
+#[cfg(feature = "gui")]
+use crate::Location;
+
+#[cfg(feature = "gui")]
+const BANNER_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 30, 30);
+#[cfg(feature = "gui")]
+const HIGHLIGHT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 220, 90);
+
+/// A government weather alert from the One Call `alerts` list (NWS in the US,
+/// the national agency elsewhere).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    #[serde(default)]
+    pub sender_name: String,
+    pub event: String,
+    // Unix timestamps.
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// How serious an alert is, going by the NWS naming convention that other
+/// agencies mostly follow.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Advisory,
+    Watch,
+    Warning,
+}
+
+#[cfg(feature = "gui")]
+impl Severity {
+    pub fn of(alert: &Alert) -> Self {
+        let event = alert.event.to_lowercase();
+        if event.contains("warning") || event.contains("emergency") {
+            Severity::Warning
+        } else if event.contains("watch") {
+            Severity::Watch
+        } else {
+            Severity::Advisory
+        }
+    }
+}
+
+/// The hazard an alert is about, without its level: "Winter Storm Watch" and
+/// "Winter Storm Warning" are both "winter storm".
+#[cfg(feature = "gui")]
+fn hazard(event: &str) -> String {
+    let event = event.trim().to_lowercase();
+    ["warning", "watch", "advisory", "statement", "emergency"]
+        .iter()
+        .find_map(|level| event.strip_suffix(level))
+        .unwrap_or(&event)
+        .trim()
+        .to_string()
+}
+
+/// Banner listing active alerts, each expandable to its full text. In the
+/// US it also lists the counties and zones each alert covers, looked up
+/// from the NWS once there is an alert to show. Alerts that were re-issued
+/// since the previous fetch point out what changed.
+#[cfg(feature = "gui")]
+pub struct AlertBanner {
+    areas: Option<Fetch<AlertAreas>>,
+    // The alerts, and their areas if they had loaded, from before the
+    // last update.
+    previous: Vec<Alert>,
+    previous_areas: Option<AlertAreas>,
+}
+
+#[cfg(feature = "gui")]
+impl AlertBanner {
+    pub fn new() -> Self {
+        Self { areas: None, previous: Vec::new(), previous_areas: None }
+    }
+
+    /// Keeps `previous` to diff the new alerts against and looks their
+    /// areas up again.
+    pub fn alerts_updated(&mut self, previous: &[Alert]) {
+        self.previous = previous.to_vec();
+        self.previous_areas = match self.areas.take() {
+            Some(Fetch::Ready(areas)) => Some(areas),
+            _ => None,
+        };
+    }
+
+    /// Draws nothing when there are no alerts.
+    pub fn show(&mut self, ui: &mut egui::Ui, alerts: &[Alert], location: Option<&Location>) {
+        if let Some(location) = location.filter(|l| !alerts.is_empty() && l.country_code == "US") {
+            let (lat, lon) = (location.lat, location.lon);
+            let areas = self.areas.get_or_insert_with(|| {
+                Fetch::spawn(ui.ctx(), async move { get_alert_areas(&Client::new(), lat, lon).await })
+            });
+            areas.poll();
+        }
+
+        // The banner is an assertive live region, so screen readers announce an
+        // alert as soon as it is added instead of waiting for the user to find it.
+        // The region exists even while empty so that additions count as changes.
+        let banner = egui::Id::new("alerts_banner");
+        ui.ctx().accesskit_node_builder(banner, |node| {
+            node.set_role(egui::accesskit::Role::Group);
+            node.set_name("Weather alerts");
+            node.set_live(egui::accesskit::Live::Assertive);
+        });
+        let ctx = ui.ctx().clone();
+        ctx.with_accessibility_parent(banner, || self.show_alerts(ui, alerts));
+    }
+
+    fn show_alerts(&self, ui: &mut egui::Ui, alerts: &[Alert]) {
+        if alerts.is_empty() {
+            return;
+        }
+        // Failures are left silent; the alerts themselves still show.
+        let areas = match self.areas {
+            Some(Fetch::Ready(ref areas)) => Some(areas),
+            _ => None,
+        };
+        // Keeps the countdowns ticking without waiting for input.
+        ui.ctx().request_repaint_after(Duration::from_secs(1));
+        let now = Local::now().timestamp();
+        for alert in alerts {
+            let covered = areas.and_then(|areas| Some((areas, areas.find(&alert.event)?)));
+            let changes = self.changes(alert, covered.map(|(_, area)| area));
+            egui::Frame::group(ui.style()).fill(BANNER_COLOR).show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                let mut title = alert.event.clone();
+                if let Some((areas, area)) = covered {
+                    if areas.includes_own(area) {
+                        title.push_str(" — includes your area");
+                    }
+                }
+                if !changes.is_empty() {
+                    title.push_str(" — updated");
+                }
+                egui::CollapsingHeader::new(egui::RichText::new(title).strong().color(egui::Color32::WHITE))
+                    .id_source((&alert.event, alert.start))
+                    .show(ui, |ui| {
+                        for change in &changes {
+                            ui.colored_label(HIGHLIGHT_COLOR, change);
+                        }
+                        ui.label(format_period(alert));
+                        if !alert.sender_name.is_empty() {
+                            ui.small(format!("Issued by {}", alert.sender_name));
+                        }
+                        if let Some((areas, area)) = covered {
+                            show_areas(ui, areas, area);
+                        }
+                        ui.label(alert.description.trim());
+                    });
+                // Kept out of the header and the live region, or screen readers
+                // would re-announce the alert every second.
+                let remaining = ui.colored_label(egui::Color32::WHITE, countdown(alert, now));
+                ui.ctx().accesskit_node_builder(remaining.id, |node| node.set_live(egui::accesskit::Live::Off));
+            });
+        }
+    }
+
+    /// How `alert` differs from the version shown before the last update: the
+    /// same event, or failing that the same hazard at another level. Empty
+    /// for alerts that are new or unchanged.
+    fn changes(&self, alert: &Alert, area: Option<&AlertArea>) -> Vec<String> {
+        let old = self
+            .previous
+            .iter()
+            .find(|old| old.event == alert.event)
+            .or_else(|| self.previous.iter().find(|old| hazard(&old.event) == hazard(&alert.event)));
+        let Some(old) = old else { return Vec::new() };
+        if old == alert {
+            return Vec::new();
+        }
+
+        let mut changes = Vec::new();
+        if old.event != alert.event {
+            let verb = match Severity::of(alert).cmp(&Severity::of(old)) {
+                Ordering::Greater => "Upgraded",
+                Ordering::Less => "Downgraded",
+                Ordering::Equal => "Changed",
+            };
+            changes.push(format!("{} from {}", verb, old.event));
+        }
+        if alert.start != old.start {
+            changes.push(format!(
+                "Now starts {} (was {})",
+                local_weekday_time(alert.start),
+                local_weekday_time(old.start)
+            ));
+        }
+        if alert.end != old.end {
+            let verb = if alert.end > old.end { "Extended until" } else { "Now ends" };
+            changes.push(format!("{} {} (was {})", verb, local_weekday_time(alert.end), local_weekday_time(old.end)));
+        }
+        let old_area = self.previous_areas.as_ref().and_then(|areas| areas.find(&old.event));
+        if let (Some(area), Some(old_area)) = (area, old_area) {
+            let added: Vec<&str> =
+                area.areas.iter().filter(|a| !old_area.areas.contains(a)).map(String::as_str).collect();
+            let removed: Vec<&str> =
+                old_area.areas.iter().filter(|a| !area.areas.contains(a)).map(String::as_str).collect();
+            if !added.is_empty() {
+                changes.push(format!("Now also covers {}", added.join(", ")));
+            }
+            if !removed.is_empty() {
+                changes.push(format!("No longer covers {}", removed.join(", ")));
+            }
+        }
+        if alert.description.trim() != old.description.trim() {
+            changes.push("Details updated".to_string());
+        }
+        changes
+    }
+}
+
+/// The affected counties and zones as a wrapped list, with the user's own
+/// highlighted.
+#[cfg(feature = "gui")]
+fn show_areas(ui: &mut egui::Ui, areas: &AlertAreas, area: &AlertArea) {
+    if area.areas.is_empty() {
+        return;
+    }
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Affected areas:");
+        for name in &area.areas {
+            let own = areas.own_names.iter().any(|own| own.eq_ignore_ascii_case(name));
+            let text = egui::RichText::new(name);
+            ui.label(if own { text.strong().color(HIGHLIGHT_COLOR) } else { text });
+        }
+    });
+    if !areas.includes_own(area) {
+        ui.small("Your county and zone are not listed.");
+    }
+}
+
+/// "Starts in 2 h 5 min", "Expires in 42 minutes" or "Expired".
+#[cfg(feature = "gui")]
+pub fn countdown(alert: &Alert, now: i64) -> String {
+    if now < alert.start {
+        format!("Starts in {}", format_remaining(alert.start - now))
+    } else if now < alert.end {
+        format!("Expires in {}", format_remaining(alert.end - now))
+    } else {
+        "Expired".to_string()
+    }
+}
+
+#[cfg(feature = "gui")]
+fn format_remaining(secs: i64) -> String {
+    let minutes = secs / 60;
+    match secs {
+        0..=59 => format!("{} seconds", secs),
+        60..=119 => "1 minute".to_string(),
+        120..=3599 => format!("{} minutes", minutes),
+        3600..=86_399 => format!("{} h {} min", minutes / 60, minutes % 60),
+        _ => format!("{} d {} h", minutes / (24 * 60), minutes / 60 % 24),
+    }
+}
+
+/// When the alert is in effect, e.g. "Tue 14:00 until Wed 06:00".
+pub fn format_period(alert: &Alert) -> String {
+    format!("{} until {}", local_weekday_time(alert.start), local_weekday_time(alert.end))
+}
@@ -0,0 +1,773 @@
+use std::time::{Duration, Instant};
+use chrono::Local;
+use eframe::{egui, App, Frame};
+#[cfg(feature = "air-quality")]
+use reqwest::Client;
+#[cfg(feature = "air-quality")]
+use crate::air_quality::{get_air_quality, AqiSample};
+use crate::alerts::AlertBanner;
+use crate::cache;
+use crate::comparison::Comparison;
+use crate::current_conditions::{DisplayModel, HeadingCache};
+use crate::daily_panel::DailyPanel;
+use crate::debug_hud::{CacheStatus, DebugHud};
+use crate::export::Exporter;
+use crate::fetch::Fetch;
+use crate::forecast_changes::ForecastChanges;
+use crate::history::History;
+use crate::hotkeys::{HotkeyCommand, Hotkeys};
+use crate::i18n;
+use crate::local_api::LocalApi;
+use crate::locations::{self, Locations};
+use crate::locator::LocationSource;
+use crate::notifications::Notifier;
+use crate::panels::Panels;
+use crate::past_weather_panel::PastWeatherPanel;
+use crate::personal_station::PersonalStation;
+use crate::pressure_panel::PressurePanel;
+use crate::config::Config;
+use crate::station_check::StationCheck;
+use crate::region_grid::RegionGrid;
+use crate::mini_mode::MiniMode;
+use crate::rules::{Readings, Rules};
+use crate::template::Template;
+use crate::session::Session;
+use crate::settings_window::SettingsWindow;
+use crate::trip_planner::TripPlanner;
+#[cfg(all(feature = "tray", target_os = "linux"))]
+use crate::tray::{Tray, TrayCommand, TrayState};
+use crate::units::Units;
+use crate::watchdog::{self, Watchdog};
+use crate::wind_panel::WindPanel;
+use crate::{hourly_panel, minutely, road_conditions, summary, theme, weekend};
+use crate::{fetch_weather_at, fetch_weather_data, WeatherSnapshot};
+#[cfg(feature = "scripting")]
+use crate::scripting;
+
+pub struct WeatherApp {
+    snapshot: Option<WeatherSnapshot>,
+    config: Config,
+    history: History<(Config, locations::Store)>,
+    // Settings and locations as of the last undo step, to tell when the
+    // next one is due.
+    undo_base: (Config, locations::Store),
+    // Settings changed in the window but not yet saved and applied, e.g.
+    // while a value is still being dragged.
+    config_unsaved: bool,
+    fetch_latency: Option<Duration>,
+    cache_status: CacheStatus,
+    refresh: Option<(Instant, Fetch<WeatherSnapshot>)>,
+    refresh_error: Option<String>,
+    // Consecutive failed refreshes; drives the retry backoff.
+    failed_refreshes: u32,
+    // `None` disables automatic refreshes.
+    refresh_interval: Option<Duration>,
+    low_data: bool,
+    last_refresh_attempt: Instant,
+    locations: Locations,
+    local_api: Option<LocalApi>,
+    hotkeys: Hotkeys,
+    notifier: Notifier,
+    watchdog: Watchdog,
+    // The reading behind `NOTIFY_AQI`, fetched after each refresh, and the
+    // city it is for.
+    #[cfg(feature = "air-quality")]
+    aqi_check: Option<(String, Fetch<AqiSample>)>,
+    #[cfg(feature = "air-quality")]
+    aqi_check_due: bool,
+    // What the rules need beyond the forecast, fetched after each refresh;
+    // the rules are checked once it arrives.
+    readings: Readings,
+    readings_fetch: Option<Fetch<Readings>>,
+    rules_due: bool,
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    tray: Option<Tray>,
+    // With a tray icon, closing the window only minimizes it until this is set.
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    quitting: bool,
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    minimize_requested: bool,
+    // Notifications are only shown while the window is in the background.
+    window_focused: bool,
+    // Replaces the conditions table when the user configured one.
+    template: Option<Template>,
+    debug_hud: DebugHud,
+    daily_panel: DailyPanel,
+    wind_panel: WindPanel,
+    panels: Panels,
+    trip_planner: TripPlanner,
+    comparison: Comparison,
+    rules: Rules,
+    settings_window: SettingsWindow,
+    // As last saved, to notice when it needs writing again.
+    session: Session,
+    station_check: StationCheck,
+    alert_banner: AlertBanner,
+    personal_station: PersonalStation,
+    pressure_panel: PressurePanel,
+    past_weather: PastWeatherPanel,
+    region_grid: RegionGrid,
+    mini_mode: MiniMode,
+    forecast_changes: ForecastChanges,
+    heading: HeadingCache,
+    exporter: Exporter,
+    // Where the central panel was last drawn, for PNG exports.
+    panel_rect: egui::Rect,
+    #[cfg(feature = "scripting")]
+    script_output: Option<Result<scripting::ScriptOutput, String>>,
+}
+
+impl WeatherApp {
+    /// Opens with `cached` data, if there is any, and immediately starts a fresh fetch.
+    pub fn new(ctx: &egui::Context, cached: Option<WeatherSnapshot>) -> Self {
+        let config = Config::load();
+        config.clock.apply();
+        config.language.apply();
+        let session = Session::load(ctx);
+        let locations = Locations::load();
+        let undo_base = (config.clone(), locations.store().clone());
+        let mut app = Self {
+            snapshot: None,
+            refresh_interval: config.refresh_interval(),
+            low_data: config.low_data(),
+            history: History::new(),
+            undo_base,
+            config_unsaved: false,
+            fetch_latency: None,
+            cache_status: CacheStatus {
+                hit: cached.is_some(),
+                entry: cached.as_ref().map(|snapshot| snapshot.fetched_at),
+            },
+            refresh: None,
+            refresh_error: None,
+            failed_refreshes: 0,
+            last_refresh_attempt: Instant::now(),
+            locations,
+            local_api: LocalApi::start(ctx, &config),
+            hotkeys: Hotkeys::register(ctx, &config),
+            notifier: Notifier::new(ctx, &config),
+            watchdog: Watchdog::new(&config),
+            #[cfg(feature = "air-quality")]
+            aqi_check: None,
+            #[cfg(feature = "air-quality")]
+            aqi_check_due: false,
+            readings: Readings::default(),
+            readings_fetch: None,
+            rules_due: false,
+            #[cfg(all(feature = "tray", target_os = "linux"))]
+            tray: Tray::start(ctx, &config),
+            #[cfg(all(feature = "tray", target_os = "linux"))]
+            quitting: false,
+            #[cfg(all(feature = "tray", target_os = "linux"))]
+            minimize_requested: false,
+            window_focused: true,
+            template: Template::from_config(&config),
+            debug_hud: DebugHud::new(),
+            daily_panel: DailyPanel::new(),
+            wind_panel: WindPanel::new(&config),
+            panels: Panels::registered(&config),
+            trip_planner: TripPlanner::new(),
+            comparison: Comparison::new(),
+            rules: Rules::load(),
+            settings_window: SettingsWindow::new(),
+            session,
+            station_check: StationCheck::new(),
+            alert_banner: AlertBanner::new(),
+            personal_station: PersonalStation::new(&config),
+            pressure_panel: PressurePanel::new(),
+            past_weather: PastWeatherPanel::new(&config),
+            region_grid: RegionGrid::new(),
+            mini_mode: MiniMode::new(),
+            forecast_changes: ForecastChanges::new(),
+            heading: HeadingCache::default(),
+            exporter: Exporter::new(),
+            panel_rect: egui::Rect::NOTHING,
+            #[cfg(feature = "scripting")]
+            script_output: None,
+            config,
+        };
+        app.trip_planner.open = app.session.trip_planner_open;
+        app.comparison.open = app.session.comparison_open;
+        app.settings_window.open = app.session.settings_open;
+        app.locations.manager_open = app.session.locations_open;
+        app.rules.open = app.session.rules_open;
+        app.mini_mode.on = app.session.mini_mode;
+        if let Some(cached) = cached {
+            app.forecast_changes.update(&cached);
+            app.apply_snapshot(cached);
+        }
+        app.start_refresh(ctx);
+        app
+    }
+
+    /// Replaces everything derived from the last fetch.
+    fn apply_snapshot(&mut self, snapshot: WeatherSnapshot) {
+        let moved = self.snapshot.as_ref().is_some_and(|old| {
+            old.location.lat != snapshot.location.lat || old.location.lon != snapshot.location.lon
+        });
+        if moved {
+            // Panels, the station check, the elevation lookup and past
+            // weather load their data once per location.
+            self.panels = Panels::registered(&self.config);
+            self.station_check = StationCheck::new();
+            self.pressure_panel = PressurePanel::new();
+            self.past_weather = PastWeatherPanel::new(&self.config);
+        }
+        match self.snapshot {
+            _ if moved => self.alert_banner = AlertBanner::new(),
+            // Affected areas are looked up again for the alerts now shown,
+            // and re-issued ones are compared with what came before.
+            Some(ref old) if old.weather.alerts != snapshot.weather.alerts => {
+                self.alert_banner.alerts_updated(&old.weather.alerts);
+            }
+            _ => {}
+        }
+        #[cfg(feature = "scripting")]
+        {
+            self.script_output = scripting::run(&self.config, &snapshot.weather, &snapshot.location);
+        }
+        self.snapshot = Some(snapshot);
+        self.publish();
+    }
+
+    fn save_session(&mut self, ctx: &egui::Context) {
+        let mut session = Session::capture(ctx);
+        session.trip_planner_open = self.trip_planner.open;
+        session.comparison_open = self.comparison.open;
+        session.settings_open = self.settings_window.open;
+        session.locations_open = self.locations.manager_open;
+        session.rules_open = self.rules.open;
+        session.mini_mode = self.mini_mode.on;
+        if session != self.session {
+            session.save();
+            self.session = session;
+        }
+    }
+
+    /// Rewrites the outputs other programs read, which depend on the display units.
+    fn publish(&self) {
+        let Some(ref snapshot) = self.snapshot else { return };
+        let units = self.config.units;
+        if let Some(ref api) = self.local_api {
+            api.publish(snapshot, units, self.template.as_ref());
+        }
+        summary::write_files(snapshot, &self.config, self.template.as_ref());
+    }
+
+    /// Saves the config and brings everything that depends on it up to date.
+    fn apply_config(&mut self) {
+        self.config.save();
+        self.config.clock.apply();
+        self.config.language.apply();
+        self.refresh_interval = self.config.refresh_interval();
+        self.low_data = self.config.low_data();
+        self.template = Template::from_config(&self.config);
+        self.wind_panel = WindPanel::new(&self.config);
+        self.hotkeys.update(&self.config);
+        self.publish();
+    }
+
+    fn undoable_state(&self) -> (Config, locations::Store) {
+        (self.config.clone(), self.locations.store().clone())
+    }
+
+    /// Saves and applies settings changed in the window and records an undo
+    /// step if anything changed since the last one. Returns whether the
+    /// forecast has to be fetched again.
+    fn settle_changes(&mut self) -> bool {
+        let mut refetch = false;
+        if std::mem::take(&mut self.config_unsaved) {
+            self.apply_config();
+            // A different backend means the current forecast is stale too.
+            let previous = &self.undo_base.0;
+            refetch = self.config.provider() != previous.provider() || self.config.api_key() != previous.api_key();
+            if refetch {
+                // Panels hold on to the key they were made with.
+                self.panels = Panels::registered(&self.config);
+                self.past_weather = PastWeatherPanel::new(&self.config);
+            }
+        }
+        if self.config != self.undo_base.0 || *self.locations.store() != self.undo_base.1 {
+            let current = self.undoable_state();
+            self.history.record(std::mem::replace(&mut self.undo_base, current));
+        }
+        refetch
+    }
+
+    /// Puts back settings and locations from undo/redo. Returns whether the
+    /// selected location changed.
+    fn restore(&mut self, (config, store): (Config, locations::Store)) -> bool {
+        if config != self.config {
+            self.config = config;
+            self.apply_config();
+        }
+        self.locations.restore(store)
+    }
+
+    /// Ctrl+Z / Ctrl+Shift+Z (Cmd on macOS), unless a text field has the
+    /// keyboard and its own undo. Returns whether the selected location changed.
+    fn handle_undo(&mut self, ctx: &egui::Context) -> bool {
+        if ctx.wants_keyboard_input() {
+            return false;
+        }
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+        let state = if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+            self.history.redo(self.undoable_state())
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.history.undo(self.undoable_state())
+        } else {
+            None
+        };
+        let Some(state) = state else { return false };
+        let location_changed = self.restore(state);
+        self.undo_base = self.undoable_state();
+        location_changed
+    }
+
+    fn show_units(&mut self, ui: &mut egui::Ui) {
+        let mut units = self.config.units;
+        egui::ComboBox::from_id_source("units")
+            .selected_text(units.label())
+            .show_ui(ui, |ui| {
+                for option in Units::PRESETS {
+                    ui.selectable_value(&mut units, option, option.label());
+                }
+                if ui.button("Custom...").clicked() {
+                    self.settings_window.open = true;
+                    ui.close_menu();
+                }
+            });
+        if units != self.config.units {
+            self.config.units = units;
+            self.config.save();
+            self.publish();
+        }
+    }
+
+    /// Starts a background re-fetch unless one is already running.
+    fn start_refresh(&mut self, ctx: &egui::Context) {
+        if self.refresh.is_none() {
+            self.last_refresh_attempt = Instant::now();
+            let fetch = match self.locations.selected() {
+                Some(place) => Fetch::spawn(ctx, fetch_weather_at(self.config.clone(), place)),
+                None => {
+                    // A saved location the user was looking at isn't where they are.
+                    let last_known = self
+                        .snapshot
+                        .as_ref()
+                        .filter(|s| s.location.source.is_some())
+                        .map(|s| s.location.clone());
+                    Fetch::spawn(ctx, fetch_weather_data(self.config.clone(), last_known))
+                }
+            };
+            self.refresh = Some((Instant::now(), fetch));
+        }
+    }
+
+    /// Starts a refresh once the interval since the last attempt has passed,
+    /// and schedules a repaint for when the next one (or the "updated" label) is due.
+    fn schedule_refresh(&mut self, ctx: &egui::Context) {
+        let mut next_repaint = Duration::from_secs(60);
+        // After a failure, retry on the backoff whether or not auto-refresh is on.
+        let interval = match self.failed_refreshes {
+            0 => self.refresh_interval,
+            failures => Some(retry_delay(failures).min(self.refresh_interval.unwrap_or(Duration::MAX))),
+        };
+        if let Some(interval) = interval {
+            if self.refresh.is_none() && self.last_refresh_attempt.elapsed() >= interval {
+                self.start_refresh(ctx);
+            }
+            next_repaint = next_repaint.min(interval.saturating_sub(self.last_refresh_attempt.elapsed()));
+        }
+        ctx.request_repaint_after(next_repaint);
+    }
+
+    /// Carries out tray menu actions. Returns whether the location changed.
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    fn handle_tray(&mut self, ctx: &egui::Context, frame: &mut Frame) -> bool {
+        let commands = match self.tray {
+            Some(ref tray) => tray.take_commands(),
+            None => return false,
+        };
+        let mut location_changed = false;
+        for command in commands {
+            match command {
+                TrayCommand::ShowWindow => {
+                    frame.set_minimized(false);
+                    frame.focus();
+                }
+                TrayCommand::Refresh => self.start_refresh(ctx),
+                TrayCommand::SelectLocation(index) => location_changed |= self.locations.select(index),
+                TrayCommand::Quit => {
+                    self.quitting = true;
+                    frame.close();
+                }
+            }
+        }
+        if std::mem::take(&mut self.minimize_requested) {
+            frame.set_minimized(true);
+        }
+        location_changed
+    }
+
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    fn update_tray(&mut self) {
+        let Some(ref mut tray) = self.tray else { return };
+        let units = self.config.units;
+        let (locations, selected) = self.locations.names();
+        let state = match self.snapshot {
+            Some(ref snapshot) => TrayState {
+                title: units.format_temperature(snapshot.weather.current.temp, 0),
+                tooltip: crate::current_conditions::tooltip(snapshot, units),
+                icon: crate::glyphs::snapshot_icon(snapshot).to_string(),
+                locations,
+                selected,
+            },
+            None => TrayState {
+                title: "Weather Alerts".to_string(),
+                tooltip: "Fetching weather data...".to_string(),
+                icon: "weather-few-clouds".to_string(),
+                locations,
+                selected,
+            },
+        };
+        tray.set_state(state);
+    }
+
+    /// Returns whether a refresh just succeeded.
+    fn poll_refresh(&mut self) -> bool {
+        let Some((started, fetch)) = &mut self.refresh else { return false };
+        fetch.poll();
+        let started = *started;
+        match self.refresh.take() {
+            Some((_, Fetch::Ready(snapshot))) => {
+                self.fetch_latency = Some(started.elapsed());
+                tracing::info!(city = %snapshot.location.city, elapsed_ms = started.elapsed().as_millis() as u64, "Weather refreshed");
+                self.refresh_error = None;
+                self.failed_refreshes = 0;
+                cache::store(&snapshot);
+                self.cache_status.entry = Some(snapshot.fetched_at);
+                self.notifier.check(&snapshot, self.config.units, self.window_focused);
+                self.forecast_changes.update(&snapshot);
+                self.rules_due = true;
+                #[cfg(feature = "air-quality")]
+                {
+                    self.aqi_check_due = self.notifier.wants_aqi();
+                }
+                self.apply_snapshot(snapshot);
+                return true;
+            }
+            Some((_, Fetch::Failed(e))) => {
+                tracing::warn!("Weather refresh failed: {}", e);
+                self.refresh_error = Some(e);
+                self.failed_refreshes += 1;
+            }
+            pending => self.refresh = pending,
+        }
+        false
+    }
+
+    /// Fetches what the rules need after each refresh, then checks them.
+    fn poll_rules(&mut self, ctx: &egui::Context) {
+        if std::mem::take(&mut self.rules_due) {
+            let Some(ref snapshot) = self.snapshot else { return };
+            self.readings_fetch = Readings::fetch(ctx, self.rules.rules(), &snapshot.location, &self.config);
+            if self.readings_fetch.is_none() {
+                self.readings = Readings::default();
+                self.check_rules();
+            }
+        }
+        let Some(fetch) = &mut self.readings_fetch else { return };
+        fetch.poll();
+        match self.readings_fetch.take() {
+            Some(Fetch::Ready(readings)) => {
+                self.readings = readings;
+                self.check_rules();
+            }
+            Some(Fetch::Failed(e)) => {
+                tracing::warn!("Readings for the alert rules failed: {}", e);
+                self.check_rules();
+            }
+            pending => self.readings_fetch = pending,
+        }
+    }
+
+    fn check_rules(&mut self) {
+        let Some(ref snapshot) = self.snapshot else { return };
+        self.notifier.check_rules(
+            self.rules.rules(),
+            snapshot,
+            &self.readings,
+            self.forecast_changes.changes(&snapshot.location),
+            self.config.units,
+            self.window_focused,
+        );
+    }
+
+    /// Starts an air quality reading after each refresh and hands the
+    /// result to the notifier.
+    #[cfg(feature = "air-quality")]
+    fn poll_aqi_check(&mut self, ctx: &egui::Context) {
+        if std::mem::take(&mut self.aqi_check_due) {
+            if let Some(snapshot) = &self.snapshot {
+                let (lat, lon) = (snapshot.location.lat, snapshot.location.lon);
+                let api_key = self.config.api_key();
+                let fetch = Fetch::spawn(ctx, async move { get_air_quality(&Client::new(), lat, lon, api_key.as_deref()).await });
+                self.aqi_check = Some((snapshot.location.city.clone(), fetch));
+            }
+        }
+        let Some((_, fetch)) = &mut self.aqi_check else { return };
+        fetch.poll();
+        match self.aqi_check.take() {
+            Some((city, Fetch::Ready(reading))) => self.notifier.check_aqi(&reading, &city, self.window_focused),
+            Some((_, Fetch::Failed(e))) => tracing::warn!("Air quality check failed: {}", e),
+            pending => self.aqi_check = pending,
+        }
+    }
+}
+
+impl App for WeatherApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        let _render = tracing::trace_span!("render").entered();
+        self.window_focused = frame.info().window_info.focused;
+        // Re-applied every frame since moving to another monitor resets it.
+        if let Some(native) = frame.info().native_pixels_per_point {
+            let scale = self.config.ui_scale.pixels_per_point(native);
+            if scale != ctx.pixels_per_point() {
+                ctx.set_pixels_per_point(scale);
+            }
+        }
+        let mut refresh_requested = self.local_api.as_ref().is_some_and(|api| api.take_refresh_request());
+        let mut cycle_requested = false;
+        for command in self.hotkeys.take_commands() {
+            match command {
+                HotkeyCommand::Refresh => refresh_requested = true,
+                HotkeyCommand::CycleLocation => cycle_requested = true,
+            }
+        }
+        if refresh_requested {
+            self.start_refresh(ctx);
+        }
+        self.schedule_refresh(ctx);
+        if self.poll_refresh() && !self.low_data {
+            self.region_grid.refresh(ctx);
+        }
+        if let Some(breaches) = self.region_grid.breaches(self.rules.rules(), self.config.units) {
+            self.notifier.check_grid(&breaches, self.window_focused);
+        }
+        let stale = self
+            .snapshot
+            .as_ref()
+            .and_then(|snapshot| Some((self.watchdog.stale_age(snapshot.fetched_at, self.refresh_interval)?, snapshot)));
+        // A refresh in flight, as right after waking from sleep, may still
+        // bring the data up to date.
+        if self.refresh.is_none() {
+            let city = stale.map(|(_, snapshot)| snapshot.location.city.as_str()).unwrap_or_default();
+            self.notifier.check_stale(stale.map(|(age, _)| age), city, self.window_focused);
+        }
+        let stale = stale.map(|(age, _)| age);
+        self.poll_rules(ctx);
+        #[cfg(feature = "air-quality")]
+        self.poll_aqi_check(ctx);
+
+        let mut location_changed = self.handle_undo(ctx);
+        if cycle_requested {
+            location_changed |= self.locations.cycle();
+        }
+        #[cfg(all(feature = "tray", target_os = "linux"))]
+        {
+            location_changed |= self.handle_tray(ctx, frame);
+        }
+        self.config.theme.apply(ctx, self.snapshot.as_ref());
+        self.mini_mode.sync(ctx, frame);
+        // Saved as a PNG when asked for on the last frame, with the menu closed.
+        self.exporter.request_screenshot(ctx, frame, self.panel_rect);
+        let panel = if self.mini_mode.compact(frame) {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                theme::paint_background(ui, self.snapshot.as_ref());
+                self.mini_mode.show(ui, frame, self.snapshot.as_ref(), self.config.units);
+            })
+        } else {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                theme::paint_background(ui, self.snapshot.as_ref());
+                ui.horizontal(|ui| {
+                    match self.snapshot {
+                        Some(ref snapshot) => ui.heading(self.heading.get(snapshot)),
+                        None => ui.heading(i18n::tr("Today's Weather")),
+                    };
+                    location_changed |= self.locations.show_switcher(ui);
+                    self.show_units(ui);
+                    if ui.button(i18n::tr("Trip planner")).clicked() {
+                        self.trip_planner.open = true;
+                    }
+                    if ui.button(i18n::tr("Compare")).on_hover_text(i18n::tr("The next few days at each saved location")).clicked() {
+                        self.comparison.open = true;
+                    }
+                    if ui.button(i18n::tr("Rules")).on_hover_text(i18n::tr("Your own alert rules")).clicked() {
+                        self.rules.open = true;
+                    }
+                    if ui.button(i18n::tr("Settings")).clicked() {
+                        self.settings_window.open = true;
+                    }
+                    if ui.button(i18n::tr("Mini")).on_hover_text(i18n::tr("A small window that stays on top (Ctrl+M)")).clicked() {
+                        self.mini_mode.on = true;
+                    }
+                    if ui.add_enabled(self.refresh.is_none(), egui::Button::new(i18n::tr("Refresh"))).clicked() {
+                        self.start_refresh(ctx);
+                    }
+                    if let Some(ref snapshot) = self.snapshot {
+                        self.exporter.menu(ui, snapshot, self.config.units, &self.config, self.template.as_ref());
+                    }
+                    if self.refresh.is_some() {
+                        ui.spinner();
+                    }
+                    if let Some(ref snapshot) = self.snapshot {
+                        let age = (Local::now() - snapshot.fetched_at).to_std().unwrap_or_default();
+                        if self.failed_refreshes > 0 {
+                            ui.small(i18n::tr_args("Cached, {} old — reconnecting...", &[&format_duration(age)]));
+                        } else if self.refresh.is_none() {
+                            ui.small(i18n::tr_args("Updated {}", &[&format_age(age)]));
+                        }
+                        if let Some(source) = snapshot.location.source {
+                            let accuracy = match snapshot.location.accuracy_m {
+                                Some(metres) if metres < 1000.0 => format!(", ±{:.0} m", metres),
+                                Some(metres) => format!(", ±{:.0} km", metres / 1000.0),
+                                None if source == LocationSource::LastKnown => String::new(),
+                                None => i18n::tr(", approximate").to_string(),
+                            };
+                            ui.small(i18n::tr_args("Located by {}{}", &[&i18n::tr(source.describe()), &accuracy]));
+                        }
+                    }
+                });
+                match (stale, &self.refresh_error) {
+                    (Some(age), e) => watchdog::show(ui, age, e.as_deref()),
+                    (None, Some(e)) => {
+                        ui.small(i18n::tr_args("Refresh failed: {}", &[e]));
+                    }
+                    (None, None) => {}
+                }
+                let units = self.config.units;
+                let (alerts, location) = match self.snapshot {
+                    Some(ref snapshot) => (&snapshot.weather.alerts[..], Some(&snapshot.location)),
+                    None => (&[][..], None),
+                };
+                self.alert_banner.show(ui, alerts, location);
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if let Some(ref snapshot) = self.snapshot {
+                        let weather = &snapshot.weather;
+                        ui.separator();
+                        match self.template {
+                            Some(ref template) => {
+                                ui.label(template.render(snapshot, units));
+                            }
+                            None => DisplayModel::new(snapshot, units, self.config.pressure_unit, self.config.skin_type())
+                                .hide(&self.config.hidden_conditions)
+                                .show(ui),
+                        }
+                        self.forecast_changes.show(ui, &snapshot.location, units);
+                        self.station_check.show(ui, &snapshot.location, weather.current.temp, units, self.low_data);
+                        self.personal_station.show(ui, units);
+                        let mut pressure_unit = self.config.pressure_unit;
+                        self.pressure_panel.show(ui, &snapshot.location, &weather.current, units, &mut pressure_unit);
+                        if pressure_unit != self.config.pressure_unit {
+                            self.config.pressure_unit = pressure_unit;
+                            self.config.save();
+                        }
+                        #[cfg(feature = "scripting")]
+                        if let Some(ref output) = self.script_output {
+                            scripting::show(ui, output);
+                        }
+                        minutely::show(ui, &weather.minutely, &weather.current);
+                        road_conditions::show(ui, &weather.hourly);
+                        if !weather.hourly.is_empty() {
+                            ui.separator();
+                            hourly_panel::show(ui, &weather.hourly, units);
+                            if let Some(mph) = self.wind_panel.show(ui, &weather.hourly, units) {
+                                self.config.flyable_wind_mph = Some(mph);
+                                self.config_unsaved = true;
+                            }
+                        }
+                        if !weather.daily.is_empty() {
+                            ui.separator();
+                            weekend::show(ui, weather, units);
+                            self.daily_panel.show(ui, &weather.daily, units);
+                        }
+                        self.past_weather.show(ui, &snapshot.location, units, self.low_data);
+                        self.region_grid.show(ui, &snapshot.location, self.rules.rules(), units);
+                        self.panels.show(ui, &snapshot.location, self.low_data);
+                    } else {
+                        ui.spinner();
+                        ui.label(i18n::tr("Fetching weather data..."));
+                    }
+                });
+            })
+        };
+        self.panel_rect = panel.response.rect;
+        self.trip_planner.show(ctx, &self.config);
+        self.comparison.show(ctx, self.locations.saved(), &self.config);
+        let (weather, changes) = match self.snapshot {
+            Some(ref snapshot) => (Some(&snapshot.weather), self.forecast_changes.changes(&snapshot.location)),
+            None => (None, &[][..]),
+        };
+        self.rules.show(ctx, weather, &self.readings, changes, self.config.units);
+        self.config_unsaved |= self.settings_window.show(ctx, &mut self.config, &self.notifier);
+        location_changed |= self.locations.show_manager(ctx, &self.config);
+        // A drag changes a value every frame; it becomes one change, saved
+        // and undoable as a whole, once the button is let go.
+        if !ctx.input(|i| i.pointer.any_down()) {
+            location_changed |= self.settle_changes();
+        }
+        if location_changed {
+            // Whatever was in flight is for the old location.
+            self.refresh = None;
+            self.start_refresh(ctx);
+        }
+        self.save_session(ctx);
+        #[cfg(all(feature = "tray", target_os = "linux"))]
+        self.update_tray();
+        let state = match (self.refresh.is_some(), self.failed_refreshes) {
+            (true, 0) => "refreshing",
+            (true, _) => "retrying",
+            (false, 0) => "idle",
+            (false, _) => "backing off after a failure",
+        };
+        self.debug_hud.show(ctx, frame, self.fetch_latency, &self.cache_status, state);
+    }
+
+    fn post_rendering(&mut self, _window_size_px: [u32; 2], frame: &Frame) {
+        self.exporter.save_screenshot(frame);
+    }
+
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    fn on_close_event(&mut self) -> bool {
+        if self.tray.is_none() || self.quitting {
+            return true;
+        }
+        self.minimize_requested = true;
+        false
+    }
+}
+
+/// 30 seconds after the first failure, doubling up to 15 minutes.
+fn retry_delay(failures: u32) -> Duration {
+    let delay = Duration::from_secs(30).saturating_mul(1 << failures.saturating_sub(1).min(5));
+    delay.min(Duration::from_secs(15 * 60))
+}
+
+pub(crate) fn format_duration(age: Duration) -> String {
+    match age.as_secs() / 60 {
+        minutes @ 0..=59 => format!("{}m", minutes),
+        minutes if minutes < 48 * 60 => format!("{}h", minutes / 60),
+        minutes => format!("{}d", minutes / (24 * 60)),
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() / 60 {
+        0 => i18n::tr("just now").to_string(),
+        1 => i18n::tr("1 minute ago").to_string(),
+        minutes @ 2..=59 => i18n::tr_args("{} minutes ago", &[&minutes]),
+        minutes => i18n::tr_args("{}h {}m ago", &[&(minutes / 60), &(minutes % 60)]),
+    }
+}
@@ -0,0 +1,39 @@
+use crate::units::SpeedUnit;
+
+// Upper bound of each force in m/s, as defined by the WMO; anything at or
+// above the last one is force 12. Speeds are compared in m/s whatever the
+// display units, so a reading sits in the same force in every unit system.
+const UPPER_BOUNDS_MPS: [f64; 12] = [0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7];
+
+const DESCRIPTIONS: [&str; 13] = [
+    "Calm",
+    "Light air",
+    "Light breeze",
+    "Gentle breeze",
+    "Moderate breeze",
+    "Fresh breeze",
+    "Strong breeze",
+    "Near gale",
+    "Gale",
+    "Strong gale",
+    "Storm",
+    "Violent storm",
+    "Hurricane force",
+];
+
+/// Beaufort force (0-12) for a wind speed in mph.
+pub fn force(mph: f64) -> u8 {
+    let mps = SpeedUnit::Mps.convert(mph);
+    UPPER_BOUNDS_MPS.iter().take_while(|bound| mps >= **bound).count() as u8
+}
+
+/// "Fresh breeze" for force 5.
+pub fn description(force: u8) -> &'static str {
+    DESCRIPTIONS[usize::from(force).min(DESCRIPTIONS.len() - 1)]
+}
+
+/// "force 5, fresh breeze", to go after a numeric speed.
+pub fn describe(mph: f64) -> String {
+    let force = force(mph);
+    format!("force {}, {}", force, description(force).to_lowercase())
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::{platform, recording, WeatherSnapshot};
+
+fn cache_path() -> Option<PathBuf> {
+    platform::cache_dir().map(|dir| dir.join("last_fetch.json"))
+}
+
+/// The last successful fetch, kept so the window can show something useful on
+/// its first frame while a fresh request is in flight. Returns `None` when
+/// nothing was cached yet or the file no longer parses, e.g. after the
+/// response model changed. A replay neither reads nor replaces it.
+pub fn load() -> Option<WeatherSnapshot> {
+    if recording::replaying() {
+        return None;
+    }
+    let text = fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+pub fn store(snapshot: &WeatherSnapshot) {
+    if recording::replaying() {
+        return;
+    }
+    let Some(path) = cache_path() else { return };
+    let result = serde_json::to_string(snapshot)
+        .map_err(std::io::Error::from)
+        .and_then(|text| {
+            path.parent().map_or(Ok(()), fs::create_dir_all)?;
+            fs::write(&path, text)
+        });
+    if let Err(e) = result {
+        tracing::warn!("Unable to cache weather data at {}: {}", path.display(), e);
+    }
+}
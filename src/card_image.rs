@@ -0,0 +1,175 @@
+//! The weather card drawn without a window, for `--cli --export png`, using
+//! the fonts egui bundles so it looks like the app.
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
+use eframe::egui::{self, Color32, ColorImage};
+use crate::config::Config;
+use crate::current_conditions::{self, DisplayModel};
+use crate::units::Units;
+use crate::{alerts, glyphs, i18n, time_format, WeatherSnapshot};
+
+const WIDTH: usize = 520;
+const PADDING: f32 = 24.0;
+const HEADING_SIZE: f32 = 20.0;
+const TEMPERATURE_SIZE: f32 = 48.0;
+const TEXT_SIZE: f32 = 15.0;
+const SMALL_SIZE: f32 = 12.0;
+// Line height as a multiple of the font size.
+const LINE_SPACING: f32 = 1.4;
+const COLUMN_GAP: f32 = 16.0;
+
+/// Fonts in fallback order: text first, then the emoji fonts for glyphs.
+struct Fonts(Vec<FontVec>);
+
+impl Fonts {
+    fn load() -> Self {
+        let mut definitions = egui::FontDefinitions::default();
+        let fonts = ["Ubuntu-Light", "NotoEmoji-Regular", "emoji-icon-font"]
+            .iter()
+            .filter_map(|name| definitions.font_data.remove(*name))
+            .filter_map(|data| FontVec::try_from_vec_and_index(data.font.into_owned(), data.index).ok())
+            .collect();
+        Self(fonts)
+    }
+
+    /// The first font with a glyph for `c`. The bundled fonts miss a few
+    /// weather symbols, which are better left out than drawn as boxes.
+    fn for_char(&self, c: char) -> Option<&FontVec> {
+        self.0.iter().find(|font| font.glyph_id(c).0 != 0)
+    }
+
+    fn width(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .filter_map(|c| {
+                let font = self.for_char(c)?.as_scaled(PxScale::from(size));
+                Some(font.h_advance(font.glyph_id(c)))
+            })
+            .sum()
+    }
+
+    /// Splits `text` at spaces into lines no wider than `max_width`.
+    fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for word in text.split(' ') {
+            match lines.last_mut() {
+                Some(line) if self.width(&format!("{} {}", line, word), size) <= max_width => {
+                    line.push(' ');
+                    line.push_str(word);
+                }
+                _ => lines.push(word.to_string()),
+            }
+        }
+        lines
+    }
+}
+
+struct Canvas {
+    image: ColorImage,
+    fonts: Fonts,
+}
+
+impl Canvas {
+    /// Draws `text` with its baseline at `y`.
+    fn text(&mut self, mut x: f32, y: f32, size: f32, color: Color32, text: &str) {
+        for c in text.chars() {
+            let Some(font) = self.fonts.for_char(c) else { continue };
+            let scaled = font.as_scaled(PxScale::from(size));
+            let glyph = scaled.glyph_id(c).with_scale_and_position(PxScale::from(size), ab_glyph::point(x, y));
+            let advance = scaled.h_advance(glyph.id);
+            if let Some(outline) = font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                let [width, height] = self.image.size;
+                let pixels = &mut self.image.pixels;
+                outline.draw(|gx, gy, coverage| {
+                    let (px, py) = (bounds.min.x as i64 + i64::from(gx), bounds.min.y as i64 + i64::from(gy));
+                    if (0..width as i64).contains(&px) && (0..height as i64).contains(&py) {
+                        let pixel = &mut pixels[py as usize * width + px as usize];
+                        *pixel = blend(*pixel, color, coverage);
+                    }
+                });
+            }
+            x += advance;
+        }
+    }
+}
+
+fn blend(background: Color32, foreground: Color32, coverage: f32) -> Color32 {
+    let mix = |b: u8, f: u8| (f32::from(b) + (f32::from(f) - f32::from(b)) * coverage.clamp(0.0, 1.0)).round() as u8;
+    Color32::from_rgb(mix(background.r(), foreground.r()), mix(background.g(), foreground.g()), mix(background.b(), foreground.b()))
+}
+
+enum Line {
+    Text { size: f32, color: Color32, text: String },
+    Row { label: String, value: String },
+}
+
+/// The heading, the temperature with its glyph, any alerts and the
+/// conditions table, in egui's dark theme.
+pub fn render(snapshot: &WeatherSnapshot, units: Units, config: &Config) -> ColorImage {
+    let visuals = egui::Visuals::dark();
+    let (text_color, weak_color) = (visuals.widgets.noninteractive.fg_stroke.color, visuals.weak_text_color());
+    let fonts = Fonts::load();
+    let inner_width = WIDTH as f32 - 2.0 * PADDING;
+
+    let current = &snapshot.weather.current;
+    let glyph = current
+        .weather
+        .first()
+        .map(|w| glyphs::condition_glyph(w.id))
+        .filter(|glyph| glyph.chars().all(|c| fonts.for_char(c).is_some()));
+    let model = DisplayModel::new(snapshot, units, config.pressure_unit, config.skin_type()).hide(&config.hidden_conditions);
+    let label_width = model.rows.iter().map(|(label, _)| fonts.width(i18n::tr(label), TEXT_SIZE)).fold(0.0, f32::max);
+    let value_width = inner_width - label_width - COLUMN_GAP;
+
+    let mut lines = Vec::new();
+    for text in fonts.wrap(&current_conditions::heading(snapshot), HEADING_SIZE, inner_width) {
+        lines.push(Line::Text { size: HEADING_SIZE, color: text_color, text });
+    }
+    lines.push(Line::Text {
+        size: TEMPERATURE_SIZE,
+        color: text_color,
+        text: match glyph {
+            Some(glyph) => format!("{} {}", glyph, units.format_temperature(current.temp, 0)),
+            None => units.format_temperature(current.temp, 0),
+        },
+    });
+    for alert in &snapshot.weather.alerts {
+        let alert = format!("⚠ {} ({})", alert.event, alerts::format_period(alert));
+        for text in fonts.wrap(&alert, TEXT_SIZE, inner_width) {
+            lines.push(Line::Text { size: TEXT_SIZE, color: visuals.error_fg_color, text });
+        }
+    }
+    for (label, value) in &model.rows {
+        for (i, value) in fonts.wrap(value, TEXT_SIZE, value_width).into_iter().enumerate() {
+            let label = if i == 0 { i18n::tr(label).to_string() } else { String::new() };
+            lines.push(Line::Row { label, value });
+        }
+    }
+    lines.push(Line::Text {
+        size: SMALL_SIZE,
+        color: weak_color,
+        text: i18n::tr_args("Updated {}", &[&time_format::time(snapshot.fetched_at.naive_local())]),
+    });
+
+    let line_height = |line: &Line| match line {
+        Line::Text { size, .. } => size * LINE_SPACING,
+        Line::Row { .. } => TEXT_SIZE * LINE_SPACING,
+    };
+    let height = (2.0 * PADDING + lines.iter().map(line_height).sum::<f32>()).ceil() as usize;
+    let mut canvas = Canvas { image: ColorImage::new([WIDTH, height], visuals.panel_fill), fonts };
+    let mut top = PADDING;
+    for line in &lines {
+        let height = line_height(line);
+        // Baselines sit about four fifths of the way down the line.
+        let baseline = top + height * 0.8;
+        match line {
+            Line::Text { size, color, text } => canvas.text(PADDING, baseline, *size, *color, text),
+            Line::Row { label, value } => {
+                canvas.text(PADDING, baseline, TEXT_SIZE, weak_color, label);
+                canvas.text(PADDING + label_width + COLUMN_GAP, baseline, TEXT_SIZE, text_color, value);
+            }
+        }
+        top += height;
+    }
+    canvas.image
+}
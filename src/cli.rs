@@ -0,0 +1,161 @@
+//! `--cli`: fetch once, print to stdout and exit, for scripts and cron.
+
+use std::fs;
+use std::path::PathBuf;
+use clap::{Parser, ValueEnum};
+use crate::config::Config;
+use crate::export;
+use crate::template::Template;
+use crate::units::Units;
+use crate::recording;
+use crate::{fetch_weather_at, fetch_weather_data, summary, Place, WeatherSnapshot};
+
+#[derive(Debug, Parser)]
+#[command(about = "Current weather and alerts for where you are, or any city")]
+pub struct Args {
+    /// Print the forecast and exit instead of opening a window.
+    #[arg(long)]
+    #[cfg_attr(
+        not(feature = "gui"),
+        arg(help = "Print the forecast once and exit instead of refreshing every REFRESH_INTERVAL_MINUTES (15 by default)")
+    )]
+    pub cli: bool,
+    /// City to look up instead of the one detected from your IP address.
+    #[arg(long, requires = "cli")]
+    city: Option<String>,
+    /// ISO country code narrowing down --city, e.g. "US".
+    #[arg(long, requires = "city")]
+    country: Option<String>,
+    /// Overrides the units chosen in the settings.
+    #[arg(long, value_enum, requires = "cli")]
+    units: Option<UnitsArg>,
+    /// Print the fields of summary.json instead of text.
+    #[arg(long, requires = "cli")]
+    json: bool,
+    /// Save the forecast to a file instead of printing it.
+    #[arg(long, value_enum, requires = "cli", conflicts_with = "json")]
+    export: Option<ExportFormat>,
+    /// Where --export writes; a dated file in the current directory by default.
+    #[arg(long, value_name = "FILE", requires = "export")]
+    output: Option<PathBuf>,
+    /// Log debug messages to stderr as well as the log file; twice for
+    /// everything.
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    /// Save every raw response from the weather and location services to DIR.
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer requests from responses saved with --record instead of the network.
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+}
+
+impl Args {
+    /// Starts `--record` or `--replay`, whichever was given. Exits with
+    /// status 1 if the directory can't be used.
+    pub fn start_recording(&self) {
+        let result = match (&self.record, &self.replay) {
+            (Some(dir), _) => recording::record_to(dir).map_err(|e| format!("Unable to record to {}: {}", dir.display(), e)),
+            (_, Some(dir)) => recording::replay_from(dir).map_err(|e| format!("Unable to replay {}: {}", dir.display(), e)),
+            (None, None) => Ok(()),
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum UnitsArg {
+    Imperial,
+    Metric,
+    Kelvin,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// The full forecast as fetched.
+    Json,
+    /// A picture of the weather card.
+    #[cfg(feature = "gui")]
+    Png,
+}
+
+impl From<UnitsArg> for Units {
+    fn from(units: UnitsArg) -> Self {
+        match units {
+            UnitsArg::Imperial => Units::IMPERIAL,
+            UnitsArg::Metric => Units::METRIC,
+            UnitsArg::Kelvin => Units::KELVIN,
+        }
+    }
+}
+
+/// Exits with status 1 if the forecast can't be fetched.
+pub async fn run(args: Args) {
+    let config = Config::load();
+    config.clock.apply();
+    config.language.apply();
+    let units = args.units.map_or(config.units, Units::from);
+
+    let result = match args.city {
+        Some(city) => {
+            let country_code = args.country.unwrap_or_default();
+            fetch_weather_at(config.clone(), Place { city, country_code, coordinates: None }).await
+        }
+        None => fetch_weather_data(config.clone(), None).await,
+    };
+    let snapshot = match result {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(format) = args.export {
+        match save(format, args.output, &snapshot, units, &config) {
+            Ok(path) => println!("Saved {}", path.display()),
+            Err(e) => {
+                eprintln!("Unable to export the forecast: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary::to_json(&snapshot, units)).unwrap_or_default());
+        return;
+    }
+    print(&snapshot, units, &config, Template::from_config(&config).as_ref());
+}
+
+/// The heading, any alerts, then the conditions as a table or `template`.
+pub fn print(snapshot: &WeatherSnapshot, units: Units, config: &Config, template: Option<&Template>) {
+    println!("{}", export::to_text(snapshot, units, config, template));
+}
+
+/// Writes `--export` to `output` and returns where it went.
+// Only PNGs, which need the GUI build, depend on the units and settings.
+#[cfg_attr(not(feature = "gui"), allow(unused_variables))]
+fn save(
+    format: ExportFormat,
+    output: Option<PathBuf>,
+    snapshot: &WeatherSnapshot,
+    units: Units,
+    config: &Config,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let extension = match format {
+        ExportFormat::Json => "json",
+        #[cfg(feature = "gui")]
+        ExportFormat::Png => "png",
+    };
+    let path = output.unwrap_or_else(|| PathBuf::from(export::file_name(snapshot, extension)));
+    match format {
+        ExportFormat::Json => fs::write(&path, export::to_json(snapshot)?)?,
+        #[cfg(feature = "gui")]
+        ExportFormat::Png => export::save_png(&crate::card_image::render(snapshot, units, config), &path)?,
+    }
+    Ok(path)
+}
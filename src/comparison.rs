@@ -0,0 +1,96 @@
+//! The next few days at every saved location side by side, for deciding
+//! where the weather is better.
+
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::fetch::Fetch;
+use crate::geocode;
+use crate::i18n;
+use crate::locations::SavedLocation;
+use crate::open_meteo::{describe_weather_code, get_days, DayForecast};
+use crate::provider::Provider;
+use crate::units::Units;
+
+const COMPARISON_DAYS: u32 = 3;
+
+/// Window with the high, low and chance of rain for each saved location.
+pub struct Comparison {
+    pub open: bool,
+    // The locations the table is for, to fetch again when they change.
+    fetched_for: Vec<SavedLocation>,
+    table: Option<Fetch<Vec<Vec<DayForecast>>>>,
+}
+
+impl Comparison {
+    pub fn new() -> Self {
+        Self { open: false, fetched_for: Vec::new(), table: None }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, saved: &[SavedLocation], config: &Config) {
+        let mut open = self.open;
+        egui::Window::new(i18n::tr("Compare locations")).id(egui::Id::new("comparison_window")).open(&mut open).show(ctx, |ui| {
+            if saved.is_empty() {
+                ui.label(i18n::tr("Save a few locations to compare them here."));
+                return;
+            }
+            let refresh = ui.button(i18n::tr("Refresh")).clicked();
+            if refresh || self.table.is_none() || self.fetched_for != saved {
+                self.fetched_for = saved.to_vec();
+                let (provider, saved) = (Provider::from_config(config), self.fetched_for.clone());
+                self.table = Some(Fetch::spawn(ui.ctx(), async move { fetch_table(provider?, saved).await }));
+            }
+
+            let Some(table) = &mut self.table else { return };
+            table.poll();
+            match table {
+                Fetch::Pending(_) => {
+                    ui.spinner();
+                }
+                Fetch::Failed(e) => {
+                    ui.label(format!("Unable to compare locations: {}", e));
+                }
+                Fetch::Ready(table) => show_table(ui, &self.fetched_for, table, config.units),
+            }
+        });
+        self.open = open;
+    }
+}
+
+async fn fetch_table(provider: Provider, saved: Vec<SavedLocation>) -> Result<Vec<Vec<DayForecast>>, WeatherError> {
+    let mut points = Vec::with_capacity(saved.len());
+    for location in &saved {
+        points.push(match location.coordinates {
+            Some(coordinates) => coordinates,
+            None => geocode(&provider, &location.city, &location.country_code).await?,
+        });
+    }
+    get_days(&Client::new(), &points, COMPARISON_DAYS).await
+}
+
+fn show_table(ui: &mut egui::Ui, saved: &[SavedLocation], table: &[Vec<DayForecast>], units: Units) {
+    let temp = |t: Option<f64>| t.map(|t| units.format_temperature(t, 0)).unwrap_or_else(|| "n/a".to_string());
+    egui::Grid::new("location_comparison").striped(true).show(ui, |ui| {
+        ui.label("");
+        // Each location's days are in its own time zone, so these can be a
+        // day apart near midnight; the first one's dates head the columns.
+        for day in table.first().into_iter().flatten() {
+            ui.strong(i18n::format_date(day.date, "%a %-d"));
+        }
+        ui.end_row();
+
+        for (location, days) in saved.iter().zip(table) {
+            ui.label(&location.name);
+            for day in days {
+                let pop = day.precipitation_probability.map(|pop| format!(", {:.0}%", pop)).unwrap_or_default();
+                let response = ui.label(format!("{} / {}{}", temp(day.temp_max), temp(day.temp_min), pop));
+                if let Some(code) = day.weather_code {
+                    response.on_hover_text(describe_weather_code(code));
+                }
+            }
+            ui.end_row();
+        }
+    });
+    ui.small(i18n::tr("High / low, chance of precipitation"));
+}
@@ -0,0 +1,407 @@
+use std::env;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::i18n::Language;
+use crate::platform;
+use crate::sun_exposure::SkinType;
+use crate::theme::Theme;
+use crate::time_format::Clock;
+use crate::units::{PressureUnit, Units};
+
+const DEFAULT_REFRESH_MINUTES: u64 = 15;
+// Low data mode refreshes this many times less often.
+const LOW_DATA_REFRESH_FACTOR: u64 = 4;
+#[cfg(feature = "gui")]
+const DEFAULT_NOTIFY_RAIN_PERCENT: f64 = 70.0;
+#[cfg(feature = "gui")]
+const DEFAULT_LOCAL_API_PORT: u16 = 7979;
+
+/// Environment variables that take precedence over `config.toml`.
+#[cfg(feature = "gui")]
+pub const ENV_OVERRIDES: [&str; 30] = [
+    "WEATHER_PROVIDER",
+    "OPENWEATHERMAP_API_KEY",
+    "REFRESH_INTERVAL_MINUTES",
+    "LOW_DATA",
+    "FLYABLE_WIND_MPH",
+    "SKIN_TYPE",
+    "NOTIFY_ALERTS",
+    "NOTIFY_RAIN_PERCENT",
+    "NOTIFY_QUIET_HOURS",
+    "NOTIFY_HEAT_F",
+    "NOTIFY_COLD_F",
+    "NOTIFY_AQI",
+    "NOTIFY_STALE",
+    "QUAKE_RADIUS_KM",
+    "QUAKE_MIN_MAGNITUDE",
+    "WINDY_WEBCAMS_API_KEY",
+    "FIRMS_MAP_KEY",
+    "MPING_API_TOKEN",
+    "PWS_ECOWITT_HOST",
+    "LOCAL_API_PORT",
+    "REFRESH_HOTKEY",
+    "CYCLE_LOCATION_HOTKEY",
+    "TRAY_MODE",
+    "DISPLAY_TEMPLATE",
+    "WEATHER_SCRIPT",
+    "SUMMARY_DIR",
+    "STALE_AFTER_REFRESHES",
+    "LOCATION_SERVICES",
+    "IP_API_KEY",
+    "OWM_SCHEMA_CHECK",
+];
+
+/// Preferences kept in `config.toml` in the platform config directory and
+/// edited from the settings window. The matching environment variables (or
+/// `.env`) still win, so existing setups keep working.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// `OPENWEATHERMAP_API_KEY`.
+    pub api_key: Option<String>,
+    /// `WEATHER_PROVIDER`: `openweathermap` or `open-meteo`.
+    pub provider: Option<String>,
+    /// `REFRESH_INTERVAL_MINUTES`.
+    pub refresh_interval_minutes: Option<u64>,
+    /// `LOW_DATA`, for metered connections.
+    pub low_data: bool,
+    pub units: Units,
+    pub pressure_unit: PressureUnit,
+    pub clock: Clock,
+    /// Labels of the current-conditions rows the user turned off.
+    pub hidden_conditions: Vec<String>,
+    pub ui_scale: UiScale,
+    pub theme: Theme,
+    pub language: Language,
+    /// `SKIN_TYPE`: Fitzpatrick skin type, 1-6.
+    pub skin_type: Option<u8>,
+    /// `FLYABLE_WIND_MPH`.
+    pub flyable_wind_mph: Option<f64>,
+    /// `NOTIFY_ALERTS`: `warning`, `watch`, `advisory` or `off`.
+    pub notify_alerts: Option<String>,
+    /// `NOTIFY_RAIN_PERCENT`; `0` turns it off.
+    pub notify_rain_percent: Option<f64>,
+    /// `NOTIFY_QUIET_HOURS`, e.g. `22-7`.
+    pub notify_quiet_hours: Option<String>,
+    /// `NOTIFY_HEAT_F`.
+    pub notify_heat_f: Option<f64>,
+    /// `NOTIFY_COLD_F`.
+    pub notify_cold_f: Option<f64>,
+    /// `NOTIFY_AQI`.
+    pub notify_aqi: Option<f64>,
+    /// `NOTIFY_STALE`.
+    pub notify_stale: bool,
+    /// `QUAKE_RADIUS_KM`.
+    pub quake_radius_km: Option<f64>,
+    /// `QUAKE_MIN_MAGNITUDE`.
+    pub quake_min_magnitude: Option<f64>,
+    /// `WINDY_WEBCAMS_API_KEY`.
+    pub windy_webcams_api_key: Option<String>,
+    /// `FIRMS_MAP_KEY`.
+    pub firms_map_key: Option<String>,
+    /// `MPING_API_TOKEN`.
+    pub mping_api_token: Option<String>,
+    /// `PWS_ECOWITT_HOST`.
+    pub pws_ecowitt_host: Option<String>,
+    /// `LOCAL_API_PORT`; `0` turns the local API off.
+    pub local_api_port: Option<u16>,
+    /// `REFRESH_HOTKEY`; empty turns it off.
+    pub refresh_hotkey: Option<String>,
+    /// `CYCLE_LOCATION_HOTKEY`; empty turns it off.
+    pub cycle_location_hotkey: Option<String>,
+    /// `TRAY_MODE`.
+    pub tray_mode: bool,
+    /// `DISPLAY_TEMPLATE`.
+    pub display_template: Option<String>,
+    /// `WEATHER_SCRIPT`: path to a Rhai script.
+    pub weather_script: Option<String>,
+    /// `SUMMARY_DIR`; empty turns the summary files off.
+    pub summary_dir: Option<String>,
+    /// `STALE_AFTER_REFRESHES`; `0` turns the check off.
+    pub stale_after_refreshes: Option<u32>,
+    /// `LOCATION_SERVICES`.
+    pub location_services: bool,
+    /// `IP_API_KEY`.
+    pub ip_api_key: Option<String>,
+    /// `OWM_SCHEMA_CHECK`: `warn` or `strict`.
+    pub owm_schema_check: Option<String>,
+}
+
+// Written out rather than derived so that location services start on.
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api_key: Default::default(),
+            provider: Default::default(),
+            refresh_interval_minutes: Default::default(),
+            low_data: Default::default(),
+            units: Default::default(),
+            pressure_unit: Default::default(),
+            clock: Default::default(),
+            hidden_conditions: Default::default(),
+            ui_scale: Default::default(),
+            theme: Default::default(),
+            language: Default::default(),
+            skin_type: Default::default(),
+            flyable_wind_mph: Default::default(),
+            notify_alerts: Default::default(),
+            notify_rain_percent: Default::default(),
+            notify_quiet_hours: Default::default(),
+            notify_heat_f: Default::default(),
+            notify_cold_f: Default::default(),
+            notify_aqi: Default::default(),
+            notify_stale: Default::default(),
+            quake_radius_km: Default::default(),
+            quake_min_magnitude: Default::default(),
+            windy_webcams_api_key: Default::default(),
+            firms_map_key: Default::default(),
+            mping_api_token: Default::default(),
+            pws_ecowitt_host: Default::default(),
+            local_api_port: Default::default(),
+            refresh_hotkey: Default::default(),
+            cycle_location_hotkey: Default::default(),
+            tray_mode: Default::default(),
+            display_template: Default::default(),
+            weather_script: Default::default(),
+            summary_dir: Default::default(),
+            stale_after_refreshes: Default::default(),
+            location_services: true,
+            ip_api_key: Default::default(),
+            owm_schema_check: Default::default(),
+        }
+    }
+}
+
+/// How large the interface is drawn. Some Linux compositors report a wrong
+/// or fractional scale factor that leaves text blurry or tiny.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiScale {
+    /// Whatever the monitor reports, following the window between monitors.
+    #[default]
+    System,
+    /// The reported factor rounded to a whole number, e.g. 1.25 becomes 1.
+    Integer,
+    Custom(f32),
+}
+
+#[cfg(feature = "gui")]
+impl UiScale {
+    /// Pixels per point to draw at, given what the monitor reports.
+    pub fn pixels_per_point(self, native: f32) -> f32 {
+        match self {
+            UiScale::System => native,
+            UiScale::Integer => native.round().max(1.0),
+            UiScale::Custom(scale) => scale.clamp(0.5, 4.0),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let Some(dir) = platform::config_dir() else { return Self::default() };
+        match fs::read_to_string(dir.join("config.toml")) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid {}: {}", dir.join("config.toml").display(), e);
+                Self::default()
+            }),
+            // Units used to be saved on their own in `settings.json`.
+            Err(_) => fs::read_to_string(dir.join("settings.json"))
+                .ok()
+                .and_then(|text| serde_json::from_str(&text).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn save(&self) {
+        let Some(path) = platform::config_dir().map(|dir| dir.join("config.toml")) else { return };
+        let result = toml::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            .and_then(|text| {
+                path.parent().map_or(Ok(()), fs::create_dir_all)?;
+                fs::write(&path, text)
+            });
+        if let Err(e) = result {
+            tracing::warn!("Unable to save settings to {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn api_key(&self) -> Option<String> {
+        env::var("OPENWEATHERMAP_API_KEY")
+            .ok()
+            .or_else(|| self.api_key.clone())
+            .filter(|key| !key.trim().is_empty())
+    }
+
+    pub fn provider(&self) -> Option<String> {
+        env::var("WEATHER_PROVIDER").ok().or_else(|| self.provider.clone())
+    }
+
+    /// Defaults to 15 minutes, four times that in low data mode; `0` turns
+    /// auto-refresh off.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        let minutes = env::var("REFRESH_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .or(self.refresh_interval_minutes)
+            .unwrap_or(DEFAULT_REFRESH_MINUTES);
+        let factor = if self.low_data() { LOW_DATA_REFRESH_FACTOR } else { 1 };
+        (minutes > 0).then(|| Duration::from_secs(minutes * factor * 60))
+    }
+
+    /// Whether to keep downloads to the forecast itself: no map tiles or
+    /// webcam images, and extra panels only load when asked to.
+    pub fn low_data(&self) -> bool {
+        env_flag("LOW_DATA", self.low_data)
+    }
+
+    pub fn skin_type(&self) -> Option<SkinType> {
+        env_or("SKIN_TYPE", self.skin_type).and_then(SkinType::new)
+    }
+
+    pub fn display_template(&self) -> Option<String> {
+        env_or("DISPLAY_TEMPLATE", self.display_template.clone()).filter(|template| !template.trim().is_empty())
+    }
+
+    /// `Some("")` when the summary files are turned off.
+    pub fn summary_dir(&self) -> Option<String> {
+        env_or("SUMMARY_DIR", self.summary_dir.clone())
+    }
+
+    /// On unless `LOCATION_SERVICES` is `0` or `false`.
+    pub fn location_services(&self) -> bool {
+        match env::var("LOCATION_SERVICES") {
+            Ok(v) => !(v == "0" || v.eq_ignore_ascii_case("false")),
+            Err(_) => self.location_services,
+        }
+    }
+
+    pub fn ip_api_key(&self) -> Option<String> {
+        env_or("IP_API_KEY", self.ip_api_key.clone()).filter(|key| !key.trim().is_empty())
+    }
+
+    pub fn owm_schema_check(&self) -> Option<String> {
+        env_or("OWM_SCHEMA_CHECK", self.owm_schema_check.clone())
+    }
+}
+
+/// Settings only the window uses.
+#[cfg(feature = "gui")]
+impl Config {
+    pub fn flyable_wind_mph(&self) -> Option<f64> {
+        env_or("FLYABLE_WIND_MPH", self.flyable_wind_mph)
+    }
+
+    pub fn notify_alerts(&self) -> Option<String> {
+        env_or("NOTIFY_ALERTS", self.notify_alerts.clone())
+    }
+
+    /// Defaults to 70%; `0` or an empty variable turns it off.
+    pub fn notify_rain_percent(&self) -> Option<f64> {
+        match env::var("NOTIFY_RAIN_PERCENT") {
+            Ok(v) => v.trim().parse().ok(),
+            Err(_) => Some(self.notify_rain_percent.unwrap_or(DEFAULT_NOTIFY_RAIN_PERCENT)),
+        }
+        .filter(|percent| *percent > 0.0)
+    }
+
+    /// Start and end hour.
+    pub fn notify_quiet_hours(&self) -> Option<(u32, u32)> {
+        let hours = env_or("NOTIFY_QUIET_HOURS", self.notify_quiet_hours.clone())?;
+        let (start, end) = hours.split_once('-')?;
+        Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+    }
+
+    pub fn notify_heat_f(&self) -> Option<f64> {
+        env_or("NOTIFY_HEAT_F", self.notify_heat_f)
+    }
+
+    pub fn notify_cold_f(&self) -> Option<f64> {
+        env_or("NOTIFY_COLD_F", self.notify_cold_f)
+    }
+
+    #[cfg(feature = "air-quality")]
+    pub fn notify_aqi(&self) -> Option<f64> {
+        env_or("NOTIFY_AQI", self.notify_aqi)
+    }
+
+    pub fn notify_stale(&self) -> bool {
+        env_flag("NOTIFY_STALE", self.notify_stale)
+    }
+
+    #[cfg(feature = "earthquakes")]
+    pub fn quake_radius_km(&self) -> Option<f64> {
+        env_or("QUAKE_RADIUS_KM", self.quake_radius_km)
+    }
+
+    #[cfg(feature = "earthquakes")]
+    pub fn quake_min_magnitude(&self) -> Option<f64> {
+        env_or("QUAKE_MIN_MAGNITUDE", self.quake_min_magnitude)
+    }
+
+    #[cfg(feature = "webcams")]
+    pub fn windy_webcams_api_key(&self) -> Option<String> {
+        env_or("WINDY_WEBCAMS_API_KEY", self.windy_webcams_api_key.clone()).filter(|key| !key.trim().is_empty())
+    }
+
+    #[cfg(feature = "wildfires")]
+    pub fn firms_map_key(&self) -> Option<String> {
+        env_or("FIRMS_MAP_KEY", self.firms_map_key.clone()).filter(|key| !key.trim().is_empty())
+    }
+
+    #[cfg(feature = "spotter-reports")]
+    pub fn mping_api_token(&self) -> Option<String> {
+        env_or("MPING_API_TOKEN", self.mping_api_token.clone()).filter(|token| !token.trim().is_empty())
+    }
+
+    pub fn pws_ecowitt_host(&self) -> Option<String> {
+        env_or("PWS_ECOWITT_HOST", self.pws_ecowitt_host.clone()).filter(|host| !host.trim().is_empty())
+    }
+
+    /// Defaults to 7979; `None` when the local API is off, including when
+    /// the variable isn't a port, rather than opening it somewhere unexpected.
+    pub fn local_api_port(&self) -> Option<u16> {
+        env_or("LOCAL_API_PORT", Some(self.local_api_port.unwrap_or(DEFAULT_LOCAL_API_PORT))).filter(|port| *port != 0)
+    }
+
+    pub fn refresh_hotkey(&self) -> Option<String> {
+        env_or("REFRESH_HOTKEY", self.refresh_hotkey.clone())
+    }
+
+    pub fn cycle_location_hotkey(&self) -> Option<String> {
+        env_or("CYCLE_LOCATION_HOTKEY", self.cycle_location_hotkey.clone())
+    }
+
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    pub fn tray_mode(&self) -> bool {
+        env_flag("TRAY_MODE", self.tray_mode)
+    }
+
+    #[cfg(feature = "scripting")]
+    pub fn weather_script(&self) -> Option<String> {
+        env_or("WEATHER_SCRIPT", self.weather_script.clone()).filter(|path| !path.trim().is_empty())
+    }
+
+    pub fn stale_after_refreshes(&self) -> Option<u32> {
+        env_or("STALE_AFTER_REFRESHES", self.stale_after_refreshes)
+    }
+}
+
+/// `name` from the environment when it is set, otherwise the saved value.
+/// A variable that is set but doesn't parse gives `None`.
+fn env_or<T: FromStr>(name: &str, saved: Option<T>) -> Option<T> {
+    match env::var(name) {
+        Ok(v) => v.parse().ok(),
+        Err(_) => saved,
+    }
+}
+
+fn env_flag(name: &str, saved: bool) -> bool {
+    match env::var(name) {
+        Ok(v) => v == "1" || v.eq_ignore_ascii_case("true"),
+        Err(_) => saved,
+    }
+}
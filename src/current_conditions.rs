@@ -0,0 +1,333 @@
+#[cfg(feature = "gui")]
+use chrono::DateTime;
+use chrono::Local;
+#[cfg(feature = "gui")]
+use eframe::egui;
+use crate::i18n;
+use crate::sun_exposure::SkinType;
+use crate::time_format;
+use crate::units::{PressureUnit, Units};
+use crate::{beaufort, capitalize_first_letter, degrees_to_cardinal, format_chance_of_rain, format_temperature, format_uv_index};
+use crate::{Current, Daily, Hourly, WeatherSnapshot, DATA_UNAVAILABLE};
+
+// Hours ahead compared against for the pressure trend, and the change in
+// hPa below which pressure counts as steady.
+const TREND_HOURS: usize = 3;
+const STEADY_HPA: f64 = 1.0;
+// How long after sunset a forecast still counts as this evening's; older
+// snapshots are left showing the day as usual.
+const EVENING_SECS: i64 = 12 * 3600;
+const DAY_SECS: i64 = 24 * 3600;
+
+/// "Today's weather for Paris - Light rain", or after sunset "Tonight in
+/// Paris - Clear sky".
+pub fn heading(snapshot: &WeatherSnapshot) -> String {
+    if let Some(tonight) = Tonight::after_sunset(snapshot) {
+        return format!(
+            "{} {} - {}",
+            i18n::tr("Tonight in"),
+            snapshot.location.city,
+            capitalize_first_letter(tonight.description)
+        );
+    }
+    let description = snapshot
+        .weather
+        .daily
+        .first()
+        .and_then(|day| day.weather.first())
+        .map_or(i18n::tr(DATA_UNAVAILABLE), |w| w.description.as_str());
+    format!(
+        "{} {} - {}",
+        i18n::tr("Today's weather for"),
+        snapshot.location.city,
+        capitalize_first_letter(description)
+    )
+}
+
+/// [`heading`] for the window, rebuilt only when the forecast, the language
+/// or the part of the day it describes changes rather than every frame.
+#[cfg(feature = "gui")]
+#[derive(Default)]
+pub struct HeadingCache {
+    key: Option<(DateTime<Local>, &'static str, bool)>,
+    text: String,
+}
+
+#[cfg(feature = "gui")]
+impl HeadingCache {
+    pub fn get(&mut self, snapshot: &WeatherSnapshot) -> &str {
+        let key = (snapshot.fetched_at, i18n::code(), is_evening(snapshot));
+        if self.key != Some(key) {
+            self.text = heading(snapshot);
+            self.key = Some(key);
+        }
+        &self.text
+    }
+}
+
+/// Condensed conditions for tooltips: city, temperature, description,
+/// today's range and the names of any active alerts.
+#[cfg(feature = "gui")]
+pub fn tooltip(snapshot: &WeatherSnapshot, units: Units) -> String {
+    let weather = &snapshot.weather;
+    let today = weather.daily.first();
+    let mut tooltip = format!(
+        "{}: {}",
+        snapshot.location.city,
+        units.format_temperature(weather.current.temp, 0)
+    );
+    if let Some(condition) = weather.current.weather.first() {
+        tooltip.push_str(&format!(", {}", capitalize_first_letter(&condition.description)));
+    }
+    tooltip.push('\n');
+    tooltip.push_str(&i18n::tr_args(
+        "High {} / Low {}",
+        &[
+            &format_temperature(today.map(|day| day.temp.max), units),
+            &format_temperature(today.map(|day| day.temp.min), units),
+        ],
+    ));
+    for alert in &weather.alerts {
+        tooltip.push_str(&format!("\n{}", alert.event));
+    }
+    tooltip
+}
+
+/// Whether it is the evening after the snapshot's sunset, when the night
+/// is shown instead of the day.
+fn is_evening(snapshot: &WeatherSnapshot) -> bool {
+    let now = Local::now().timestamp();
+    snapshot.weather.current.sunset.is_some_and(|sunset| now >= sunset && now - sunset <= EVENING_SECS)
+}
+
+/// The rest of the night, from now until tomorrow's sunrise.
+struct Tonight<'a> {
+    description: &'a str,
+    low: Option<f64>,
+    // Highest hourly chance, 0.0 to 1.0.
+    pop: Option<f64>,
+}
+
+impl<'a> Tonight<'a> {
+    /// `None` during the day, or when there is no sunset to go by.
+    fn after_sunset(snapshot: &'a WeatherSnapshot) -> Option<Self> {
+        if !is_evening(snapshot) {
+            return None;
+        }
+        let current = &snapshot.weather.current;
+        let now = Local::now().timestamp();
+        let sunset = current.sunset?;
+        // Tomorrow's sunrise is close enough to a day after today's.
+        let sunrise = current.sunrise.map_or(sunset + EVENING_SECS, |rise| rise + DAY_SECS);
+        let hours: Vec<&Hourly> = snapshot
+            .weather
+            .hourly
+            .iter()
+            .filter(|hour| hour.dt + 3600 > now && hour.dt < sunrise)
+            .collect();
+        let low = hours.iter().map(|hour| hour.temp).reduce(f64::min);
+        let pop = hours.iter().map(|hour| hour.pop.min(1.0)).reduce(f64::max);
+        Some(Self {
+            description: current.weather.first().map_or(i18n::tr(DATA_UNAVAILABLE), |w| w.description.as_str()),
+            low: low.or_else(|| snapshot.weather.daily.get(1).map(|day| day.temp.min)),
+            pop,
+        })
+    }
+}
+
+/// Today's conditions as label/value rows, formatted once and then laid
+/// out by whichever front end is showing them.
+pub struct DisplayModel {
+    pub rows: Vec<(&'static str, String)>,
+}
+
+impl DisplayModel {
+    /// Every row label, in display order, for choosing which to hide.
+    #[cfg(feature = "gui")]
+    pub const LABELS: [&'static str; 19] = [
+        "Tonight's low",
+        "Chance of rain overnight",
+        "Tomorrow",
+        "Summary",
+        "Current weather",
+        "Temperature",
+        "High",
+        "Low",
+        "Humidity",
+        "Dew point",
+        "Wind",
+        "Pressure",
+        "Visibility",
+        "UV Index",
+        "Chance of rain today",
+        "Chance of rain tomorrow",
+        "Sunrise",
+        "Sunset",
+        "Daylight",
+    ];
+
+    /// After sunset the rows lead with tonight and tomorrow instead of a
+    /// day that is mostly over.
+    pub fn new(snapshot: &WeatherSnapshot, units: Units, pressure_unit: PressureUnit, skin_type: Option<SkinType>) -> Self {
+        let current = &snapshot.weather.current;
+        let today = snapshot.weather.daily.first();
+        let tomorrow = snapshot.weather.daily.get(1);
+
+        let summary = today.map(|day| day.summary.as_str()).filter(|s| !s.is_empty());
+        let description = current.weather.first().map_or(i18n::tr(DATA_UNAVAILABLE), |w| w.description.as_str());
+        let mut rows = vec![
+            ("Summary", summary.unwrap_or(i18n::tr(DATA_UNAVAILABLE)).to_string()),
+            ("Current weather", capitalize_first_letter(description)),
+            (
+                "Temperature",
+                i18n::tr_args(
+                    "{} (feels like {})",
+                    &[&units.format_temperature(current.temp, 1), &units.format_temperature(current.feels_like(), 1)],
+                ),
+            ),
+            ("High", format_temperature(today.map(|day| day.temp.max), units)),
+            ("Low", format_temperature(today.map(|day| day.temp.min), units)),
+            ("Humidity", format!("{}%", current.humidity)),
+            ("Dew point", units.format_temperature(current.dew_point(), 1)),
+            (
+                "Wind",
+                format!(
+                    "{} {} ({})",
+                    units.format_speed(current.wind_speed, 1),
+                    degrees_to_cardinal(current.wind_deg),
+                    beaufort::describe(current.wind_speed)
+                ),
+            ),
+            ("Pressure", format_pressure(current, &snapshot.weather.hourly, pressure_unit)),
+            ("Visibility", current.visibility.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), |m| units.format_distance(m))),
+            ("UV Index", format_uv_index(current.uvi, skin_type)),
+            ("Chance of rain today", format_chance_of_rain(today)),
+            ("Chance of rain tomorrow", format_chance_of_rain(tomorrow)),
+            ("Sunrise", current.sunrise.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), time_format::local_time)),
+            ("Sunset", current.sunset.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), time_format::local_time)),
+            ("Daylight", format_daylight(current)),
+        ];
+        if let Some(tonight) = Tonight::after_sunset(snapshot) {
+            rows.retain(|(label, _)| !matches!(*label, "Summary" | "High" | "Low" | "Chance of rain today"));
+            let evening = [
+                ("Tonight's low", format_temperature(tonight.low, units)),
+                (
+                    "Chance of rain overnight",
+                    tonight.pop.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), |pop| format!("{:.0}%", (pop * 100.0).round())),
+                ),
+                ("Tomorrow", format_outlook(tomorrow, units)),
+            ];
+            rows.splice(0..0, evening);
+        }
+        Self { rows }
+    }
+
+    /// Drops the rows the user turned off.
+    pub fn hide(mut self, hidden: &[String]) -> Self {
+        self.rows.retain(|(label, _)| !hidden.iter().any(|h| h == label));
+        self
+    }
+
+    /// Plain text with the values lined up in a column, for terminals.
+    pub fn to_columns(&self) -> String {
+        let width = self.rows.iter().map(|(label, _)| i18n::tr(label).chars().count()).max().unwrap_or(0);
+        self.rows
+            .iter()
+            .map(|(label, value)| format!("{:width$}  {}", i18n::tr(label), value, width = width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A two-column table.
+    #[cfg(feature = "gui")]
+    pub fn show(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("current_conditions").num_columns(2).show(ui, |ui| {
+            for (label, value) in &self.rows {
+                ui.label(i18n::tr(label));
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// "1013 hPa, falling", going by the hourly forecast a few hours out.
+fn format_pressure(current: &Current, hourly: &[Hourly], unit: PressureUnit) -> String {
+    let Some(now) = current.pressure else { return i18n::tr(DATA_UNAVAILABLE).to_string() };
+    let later = hourly.get(TREND_HOURS).and_then(|hour| hour.pressure);
+    match later.map(|later| later - now) {
+        Some(change) if change >= STEADY_HPA => i18n::tr_args("{}, rising", &[&unit.format(now)]),
+        Some(change) if change <= -STEADY_HPA => i18n::tr_args("{}, falling", &[&unit.format(now)]),
+        Some(_) => i18n::tr_args("{}, steady", &[&unit.format(now)]),
+        None => unit.format(now),
+    }
+}
+
+/// "Sunny with a breeze. High 75.0°F, low 55.0°F"
+fn format_outlook(day: Option<&Daily>, units: Units) -> String {
+    let Some(day) = day else { return i18n::tr(DATA_UNAVAILABLE).to_string() };
+    let description = Some(day.summary.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or_else(|| day.weather.first().map(|w| capitalize_first_letter(&w.description)));
+    let range = i18n::tr_args(
+        "High {}, low {}",
+        &[&units.format_temperature(day.temp.max, 1), &units.format_temperature(day.temp.min, 1)],
+    );
+    match description {
+        Some(description) => format!("{}. {}", description.trim_end_matches('.'), range),
+        None => range,
+    }
+}
+
+fn format_daylight(current: &Current) -> String {
+    match (current.sunrise, current.sunset) {
+        (Some(rise), Some(set)) if set > rise => {
+            let minutes = (set - rise) / 60;
+            format!("{}h {}m", minutes / 60, minutes % 60)
+        }
+        _ => i18n::tr(DATA_UNAVAILABLE).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use crate::tests::{snapshot, ONECALL};
+
+    fn with_sunset(offset_secs: i64) -> WeatherSnapshot {
+        // The headings are checked in English, whatever the locale.
+        Language::En.apply();
+        let mut snapshot = snapshot(serde_json::from_str(ONECALL).unwrap());
+        snapshot.weather.current.sunset = Some(Local::now().timestamp() + offset_secs);
+        snapshot
+    }
+
+    #[test]
+    fn heading_follows_the_time_of_day() {
+        assert!(heading(&with_sunset(3600)).starts_with("Today's weather for New York"));
+        assert!(heading(&with_sunset(-3600)).starts_with("Tonight in New York"));
+        // A day-old forecast isn't tonight's any more.
+        assert!(heading(&with_sunset(-EVENING_SECS - 60)).starts_with("Today's weather"));
+    }
+
+    #[cfg(feature = "gui")]
+    #[test]
+    fn heading_cache_builds_once_per_snapshot() {
+        use chrono::Duration;
+        use crate::tests::heap;
+
+        let mut snapshot = with_sunset(3600);
+        let mut cache = HeadingCache::default();
+        assert_eq!(cache.get(&snapshot), heading(&snapshot));
+        // Later frames reuse the text without allocating.
+        assert_eq!(heap::peak_during(|| assert!(!cache.get(&snapshot).is_empty())), 0);
+
+        snapshot.location.city = "Boston".to_string();
+        snapshot.fetched_at += Duration::minutes(15);
+        assert!(cache.get(&snapshot).contains("Boston"));
+        snapshot.weather.current.sunset = Some(Local::now().timestamp() - 60);
+        assert!(cache.get(&snapshot).starts_with("Tonight in Boston"));
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{Local, TimeZone};
+use eframe::egui;
+use crate::glyphs::condition_glyph;
+use crate::i18n;
+use crate::session;
+use crate::units::Units;
+use crate::{beaufort, capitalize_first_letter, degrees_to_cardinal, Daily};
+
+/// One card per day of the One Call `daily` list; clicking a card shows the
+/// rest of that day's forecast underneath.
+pub struct DailyPanel {
+    selected: Option<usize>,
+}
+
+impl DailyPanel {
+    pub fn new() -> Self {
+        Self { selected: None }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, daily: &[Daily], units: Units) {
+        session::card(ui, "Daily forecast", true, |ui| {
+            egui::ScrollArea::horizontal().id_source("daily_forecast").show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (i, day) in daily.iter().enumerate() {
+                        if day_card(ui, day, units, self.selected == Some(i)).clicked() {
+                            self.selected = if self.selected == Some(i) { None } else { Some(i) };
+                        }
+                    }
+                });
+            });
+            if let Some(day) = self.selected.and_then(|i| daily.get(i)) {
+                day_details(ui, day, units);
+            }
+        });
+    }
+}
+
+fn weekday(dt: i64, format: &str) -> String {
+    Local
+        .timestamp_opt(dt, 0)
+        .single()
+        .map(|t| i18n::format_datetime(&t, format))
+        .unwrap_or_default()
+}
+
+fn day_card(ui: &mut egui::Ui, day: &Daily, units: Units, selected: bool) -> egui::Response {
+    let mut frame = egui::Frame::group(ui.style());
+    if selected {
+        frame = frame.fill(ui.visuals().selection.bg_fill);
+    }
+    let response = frame
+        .show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.set_width(64.0);
+                ui.strong(weekday(day.dt, "%a"));
+                let condition = day.weather.first();
+                ui.label(egui::RichText::new(condition.map_or("", |c| condition_glyph(c.id))).size(24.0));
+                ui.label(format!(
+                    "{} / {}",
+                    units.format_temperature(day.temp.max, 0),
+                    units.format_temperature(day.temp.min, 0)
+                ));
+                ui.small(format!("{:.0}% rain", (day.pop.min(1.0) * 100.0).round()));
+            });
+        })
+        .response;
+    response.interact(egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
+fn day_details(ui: &mut egui::Ui, day: &Daily, units: Units) {
+    ui.separator();
+    ui.strong(weekday(day.dt, "%A, %B %-d"));
+    if let Some(condition) = day.weather.first() {
+        ui.label(capitalize_first_letter(&condition.description));
+    }
+    if !day.summary.is_empty() {
+        ui.label(&day.summary);
+    }
+    if let Some(humidity) = day.humidity {
+        ui.label(format!("Humidity: {}%", humidity));
+    }
+    if let Some(speed) = day.wind_speed {
+        let direction = day.wind_deg.map_or("", degrees_to_cardinal);
+        ui.label(format!(
+            "Wind: {} {} ({})",
+            units.format_speed(speed, 1),
+            direction,
+            beaufort::describe(speed)
+        ));
+    }
+    if let Some(uvi) = day.uvi {
+        ui.label(format!("UV Index: {:.1}", uvi));
+    }
+}
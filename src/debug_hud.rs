@@ -0,0 +1,86 @@
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use eframe::{egui, Frame};
+use egui::util::History;
+
+/// The last-fetch cache as far as this run has seen it.
+#[derive(Debug, Default)]
+pub struct CacheStatus {
+    /// Whether the window opened with a cached forecast.
+    pub hit: bool,
+    /// When the forecast now in the cache was fetched.
+    pub entry: Option<DateTime<Local>>,
+}
+
+impl CacheStatus {
+    fn describe(&self) -> String {
+        let lookup = if self.hit { "hit" } else { "miss" };
+        match self.entry {
+            Some(fetched_at) => {
+                let age = (Local::now() - fetched_at).num_seconds().max(0);
+                format!("{}, entry {}m {:02}s old", lookup, age / 60, age % 60)
+            }
+            None => format!("{}, empty", lookup),
+        }
+    }
+}
+
+/// Frame timing overlay toggled with F12.
+pub struct DebugHud {
+    visible: bool,
+    frame_times: History<f32>,
+}
+
+impl DebugHud {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            // Keep up to 300 samples from the last second.
+            frame_times: History::new(0..300, 1.0),
+        }
+    }
+
+    /// `state` is what the refresh cycle is doing, e.g. "idle".
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        frame: &Frame,
+        fetch_latency: Option<Duration>,
+        cache: &CacheStatus,
+        state: &str,
+    ) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.visible = !self.visible;
+        }
+
+        let now = ctx.input(|i| i.time);
+        let previous_frame_time = frame.info().cpu_usage.unwrap_or_default();
+        self.frame_times.add(now, previous_frame_time);
+
+        if !self.visible {
+            return;
+        }
+
+        let fps = self.frame_times.mean_time_interval().map(|dt| 1.0 / dt).unwrap_or_default();
+        let frame_time_ms = self.frame_times.average().unwrap_or_default() * 1000.0;
+        let fetch_latency = match fetch_latency {
+            Some(latency) => format!("{} ms", latency.as_millis()),
+            None => "n/a".to_string(),
+        };
+
+        egui::Area::new("debug_hud")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.monospace(format!("FPS:        {:.1}", fps));
+                    ui.monospace(format!("Frame time: {:.2} ms", frame_time_ms));
+                    ui.monospace(format!("Last fetch: {}", fetch_latency));
+                    ui.monospace(format!("Cache:      {}", cache.describe()));
+                    ui.monospace(format!("State:      {}", state));
+                });
+            });
+
+        // Keep frames coming so the numbers stay live while the overlay is open.
+        ctx.request_repaint();
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+use crate::geo::distance_km;
+
+const USGS_EVENT_URL: &str = "https://earthquake.usgs.gov/fdsnws/event/1/query";
+const LOOKBACK_DAYS: i64 = 7;
+const MAX_QUAKES: u32 = 20;
+pub const DEFAULT_RADIUS_KM: f64 = 300.0;
+pub const DEFAULT_MIN_MAGNITUDE: f64 = 2.5;
+
+#[derive(Debug, Deserialize)]
+struct FeatureCollection {
+    features: Vec<Feature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Feature {
+    properties: Properties,
+    geometry: Option<Geometry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Geometry {
+    // Longitude, latitude and depth.
+    coordinates: Vec<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Properties {
+    mag: Option<f64>,
+    place: Option<String>,
+    // Milliseconds since the epoch.
+    time: i64,
+}
+
+#[derive(Debug)]
+pub struct Earthquake {
+    pub magnitude: f64,
+    pub place: String,
+    pub time: DateTime<Utc>,
+    pub distance_km: f64,
+}
+
+/// Recent earthquakes within `radius_km` of the coordinates, newest first.
+pub async fn get_earthquakes(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    radius_km: f64,
+    min_magnitude: f64,
+) -> Result<Vec<Earthquake>, WeatherError> {
+    let start_time = (Utc::now() - Duration::days(LOOKBACK_DAYS)).format("%Y-%m-%dT%H:%M:%S").to_string();
+    let res = client
+        .get(USGS_EVENT_URL)
+        .query(&[
+            ("format", "geojson".to_string()),
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("maxradiuskm", radius_km.to_string()),
+            ("minmagnitude", min_magnitude.to_string()),
+            ("starttime", start_time),
+            ("orderby", "time".to_string()),
+            ("limit", MAX_QUAKES.to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    let collection: FeatureCollection = res.json().await?;
+
+    let quakes = collection
+        .features
+        .into_iter()
+        .filter_map(|feature| {
+            let p = feature.properties;
+            let position = feature.geometry?.coordinates;
+            Some(Earthquake {
+                magnitude: p.mag?,
+                place: p.place.unwrap_or_else(|| "Unknown location".to_string()),
+                time: DateTime::from_timestamp_millis(p.time)?,
+                distance_km: distance_km(lat, lon, *position.get(1)?, *position.first()?),
+            })
+        })
+        .collect();
+    Ok(quakes)
+}
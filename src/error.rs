@@ -0,0 +1,68 @@
+use reqwest::StatusCode;
+
+/// Why a forecast, or anything else the app asks a web service for,
+/// couldn't be fetched. The messages are written to be shown to the user as
+/// they are. Reading local files (imports, scripts, exports) keeps the
+/// errors of the format in question.
+#[derive(Debug, thiserror::Error)]
+pub enum WeatherError {
+    #[error("No OpenWeatherMap API key; add one in Settings or switch to Open-Meteo")]
+    MissingApiKey,
+    #[error("Couldn't work out your location; pick a saved location instead")]
+    GeolocationFailed,
+    #[error("No place called \"{0}\" was found")]
+    GeocodingEmpty(String),
+    #[error("{}", describe_status(*.0))]
+    HttpStatus(StatusCode),
+    #[error("The weather service sent a response the app couldn't read ({0})")]
+    Deserialize(String),
+    #[error("The weather service took too long to answer")]
+    Timeout,
+    #[error("Couldn't reach the weather service; check your connection")]
+    Network(#[source] reqwest::Error),
+    #[error("{0}")]
+    InvalidSetting(String),
+    #[error("No {0} response was recorded in {1} to replay")]
+    NotRecorded(String, String),
+    /// A service answered, but with nothing usable; the message says which.
+    #[cfg(feature = "gui")]
+    #[error("{0}")]
+    Service(String),
+}
+
+fn describe_status(status: StatusCode) -> String {
+    match status {
+        StatusCode::UNAUTHORIZED => "The weather service rejected the API key (401)".to_string(),
+        StatusCode::TOO_MANY_REQUESTS => "Too many requests to the weather service; try again later".to_string(),
+        status if status.is_server_error() => format!("The weather service is having problems ({})", status),
+        status => format!("The weather service answered {}", status),
+    }
+}
+
+impl From<reqwest::Error> for WeatherError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            WeatherError::Timeout
+        } else if let Some(status) = e.status() {
+            WeatherError::HttpStatus(status)
+        } else if e.is_decode() {
+            WeatherError::Deserialize(e.without_url().to_string())
+        } else {
+            // The URL can carry an API key, and the error ends up in the UI and logs.
+            WeatherError::Network(e.without_url())
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<image::ImageError> for WeatherError {
+    fn from(e: image::ImageError) -> Self {
+        WeatherError::Deserialize(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for WeatherError {
+    fn from(e: serde_json::Error) -> Self {
+        WeatherError::Deserialize(e.to_string())
+    }
+}
@@ -0,0 +1,357 @@
+//! Sharing the forecast: the text `--cli` prints, the whole snapshot as
+//! JSON, or a PNG of the weather card.
+
+#[cfg(feature = "gui")]
+use std::fs;
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "gui")]
+use eframe::egui;
+use crate::config::Config;
+use crate::current_conditions::{self, DisplayModel};
+use crate::template::Template;
+use crate::units::Units;
+use crate::{alerts, minutely, WeatherSnapshot};
+#[cfg(feature = "gui")]
+use crate::{i18n, platform};
+
+// How long "Saved to ..." stays next to the Share menu.
+#[cfg(feature = "gui")]
+const STATUS_SECS: u64 = 8;
+
+/// The heading, any alerts, then the conditions as a table or `template`.
+pub fn to_text(snapshot: &WeatherSnapshot, units: Units, config: &Config, template: Option<&Template>) -> String {
+    let mut lines = vec![current_conditions::heading(snapshot)];
+    for alert in &snapshot.weather.alerts {
+        lines.push(format!("ALERT: {} ({})", alert.event, alerts::format_period(alert)));
+    }
+    if let Some(headline) = minutely::headline(&snapshot.weather.minutely, &snapshot.weather.current) {
+        lines.push(headline);
+    }
+    lines.push(match template {
+        Some(template) => template.render(snapshot, units),
+        None => DisplayModel::new(snapshot, units, config.pressure_unit, config.skin_type()).hide(&config.hidden_conditions).to_columns(),
+    });
+    lines.join("\n")
+}
+
+/// Everything fetched, as `location`, `fetched_at` and `weather`, in the
+/// forecast's own units.
+pub fn to_json(snapshot: &WeatherSnapshot) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(snapshot)
+}
+
+/// E.g. `weather_New_York_2026-10-17_1405.png`.
+pub fn file_name(snapshot: &WeatherSnapshot, extension: &str) -> String {
+    let city: String = snapshot.location.city.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect();
+    format!("weather_{}_{}.{}", city, snapshot.fetched_at.format("%Y-%m-%d_%H%M"), extension)
+}
+
+#[cfg(feature = "gui")]
+pub fn save_png(image: &egui::ColorImage, path: &Path) -> image::ImageResult<()> {
+    let [width, height] = image.size;
+    let rgba: Vec<u8> = image.pixels.iter().flat_map(|pixel| pixel.to_srgba_unmultiplied()).collect();
+    image::save_buffer_with_format(path, &rgba, width as u32, height as u32, image::ColorType::Rgba8, image::ImageFormat::Png)
+}
+
+/// The Downloads folder, or the data directory where there isn't one.
+#[cfg(feature = "gui")]
+fn export_dir() -> Option<PathBuf> {
+    directories::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(Path::to_path_buf))
+        .or_else(platform::data_dir)
+}
+
+/// The Share menu in the window header. A PNG is the window as drawn, so
+/// it takes a screenshot on the next frame and saves it once rendered.
+#[cfg(feature = "gui")]
+pub struct Exporter {
+    // The PNG's file name, set from the menu and taken on the next frame.
+    png_requested: Option<String>,
+    // The screenshot on its way: file name, area in points and pixels per point.
+    screenshot: Option<(String, egui::Rect, f32)>,
+    status: Option<(Instant, String)>,
+}
+
+#[cfg(feature = "gui")]
+impl Exporter {
+    pub fn new() -> Self {
+        Self { png_requested: None, screenshot: None, status: None }
+    }
+
+    pub fn menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        snapshot: &WeatherSnapshot,
+        units: Units,
+        config: &Config,
+        template: Option<&Template>,
+    ) {
+        ui.menu_button(i18n::tr("Share"), |ui| {
+            if ui.button("Copy as text").clicked() {
+                ui.output_mut(|o| o.copied_text = to_text(snapshot, units, config, template));
+                self.set_status("Copied".to_string());
+                ui.close_menu();
+            }
+            if ui.button("Save as JSON").on_hover_text("The full forecast, as fetched").clicked() {
+                let result = to_json(snapshot).map_err(|e| e.to_string()).and_then(|json| {
+                    let path = export_dir().ok_or("no folder to save to")?.join(file_name(snapshot, "json"));
+                    path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, json)).map_err(|e| e.to_string())?;
+                    Ok(path)
+                });
+                self.saved(result);
+                ui.close_menu();
+            }
+            if ui.button("Save as PNG").on_hover_text("A picture of the window").clicked() {
+                self.png_requested = Some(file_name(snapshot, "png"));
+                ui.ctx().request_repaint();
+                ui.close_menu();
+            }
+        });
+        if let Some((at, ref status)) = self.status {
+            if at.elapsed() < Duration::from_secs(STATUS_SECS) {
+                ui.small(status);
+                ui.ctx().request_repaint_after(Duration::from_secs(1));
+            }
+        }
+    }
+
+    /// Asks for a screenshot of `area` if a PNG was requested on the last
+    /// frame, once the menu has closed.
+    pub fn request_screenshot(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame, area: egui::Rect) {
+        if let Some(name) = self.png_requested.take() {
+            frame.request_screenshot();
+            self.screenshot = Some((name, area, ctx.pixels_per_point()));
+        }
+    }
+
+    /// Saves the screenshot asked for in [`Exporter::request_screenshot`];
+    /// call from `post_rendering`.
+    pub fn save_screenshot(&mut self, frame: &eframe::Frame) {
+        let Some((name, area, pixels_per_point)) = self.screenshot.take() else { return };
+        let Some(screenshot) = frame.screenshot() else {
+            self.set_status("Screenshots aren't supported here".to_string());
+            return;
+        };
+        let result = export_dir().ok_or_else(|| "no folder to save to".to_string()).and_then(|dir| {
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let path = dir.join(name);
+            save_png(&screenshot.region(&area, Some(pixels_per_point)), &path).map_err(|e| e.to_string())?;
+            Ok(path)
+        });
+        self.saved(result);
+    }
+
+    fn saved(&mut self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => self.set_status(format!("Saved to {}", path.display())),
+            Err(e) => {
+                tracing::warn!("Unable to export the forecast: {}", e);
+                self.set_status(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    fn set_status(&mut self, status: String) {
+        self.status = Some((Instant::now(), status));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+    use super::*;
+    use crate::i18n::Language;
+    use crate::summary;
+    use crate::tests::snapshot;
+    use crate::time_format::Clock;
+
+    // Cut-down One Call responses, without sunrise and sunset so the output
+    // doesn't depend on the time zone or the time of day.
+    const HOT: &str = r#"{
+        "current": {
+            "dt": 1760700000, "temp": 98.6, "feels_like": 104.2, "pressure": 1008, "humidity": 40,
+            "dew_point": 70.1, "uvi": 10.2, "visibility": 10000, "wind_speed": 5.0, "wind_deg": 180,
+            "weather": [{"id": 800, "main": "Clear", "description": "clear sky", "icon": "01d"}]
+        },
+        "daily": [
+            {
+                "dt": 1760716800, "summary": "Hot and sunny all day", "pop": 0.0,
+                "temp": {"day": 99.0, "min": 80.2, "max": 101.3, "night": 84.0, "eve": 95.0, "morn": 82.0},
+                "weather": [{"id": 800, "main": "Clear", "description": "clear sky", "icon": "01d"}]
+            },
+            {
+                "dt": 1760803200, "summary": "Hot with afternoon storms", "pop": 0.4,
+                "temp": {"day": 96.0, "min": 78.4, "max": 97.5, "night": 80.0, "eve": 90.0, "morn": 79.0},
+                "weather": [{"id": 211, "main": "Thunderstorm", "description": "thunderstorm", "icon": "11d"}]
+            }
+        ]
+    }"#;
+
+    const FREEZING: &str = r#"{
+        "current": {
+            "dt": 1760700000, "temp": 5.0, "feels_like": -12.3, "pressure": 1031, "humidity": 65,
+            "dew_point": -3.2, "uvi": 0.4, "visibility": 16093, "wind_speed": 20.0, "wind_deg": 350,
+            "weather": [{"id": 800, "main": "Clear", "description": "clear sky", "icon": "01d"}]
+        },
+        "daily": [
+            {
+                "dt": 1760716800, "summary": "Bitterly cold and clear", "pop": 0.0,
+                "temp": {"day": 8.0, "min": -4.0, "max": 11.5, "night": -2.0, "eve": 4.0, "morn": -1.0},
+                "weather": [{"id": 800, "main": "Clear", "description": "clear sky", "icon": "01d"}]
+            },
+            {
+                "dt": 1760803200, "summary": "", "pop": 0.05,
+                "temp": {"day": 14.0, "min": 2.0, "max": 17.0, "night": 5.0, "eve": 10.0, "morn": 3.0},
+                "weather": [{"id": 801, "main": "Clouds", "description": "few clouds", "icon": "02d"}]
+            }
+        ]
+    }"#;
+
+    const SNOW: &str = r#"{
+        "current": {
+            "dt": 1760700000, "temp": 28.4, "feels_like": 19.0, "pressure": 1002, "humidity": 92,
+            "dew_point": 26.5, "uvi": 0.8, "visibility": 1200, "wind_speed": 12.5, "wind_deg": 45,
+            "weather": [{"id": 601, "main": "Snow", "description": "snow", "icon": "13d"}]
+        },
+        "daily": [
+            {
+                "dt": 1760716800, "summary": "Snow through the day", "pop": 0.95,
+                "temp": {"day": 29.0, "min": 24.1, "max": 31.0, "night": 25.0, "eve": 27.0, "morn": 26.0},
+                "weather": [{"id": 601, "main": "Snow", "description": "snow", "icon": "13d"}]
+            },
+            {
+                "dt": 1760803200, "summary": "Light snow in the morning", "pop": 0.6,
+                "temp": {"day": 30.0, "min": 22.0, "max": 33.3, "night": 23.0, "eve": 28.0, "morn": 24.0},
+                "weather": [{"id": 600, "main": "Snow", "description": "light snow", "icon": "13d"}]
+            }
+        ]
+    }"#;
+
+    const NO_TOMORROW: &str = r#"{
+        "current": {
+            "dt": 1760700000, "temp": 61.5, "feels_like": 60.2, "pressure": 1016, "humidity": 72,
+            "dew_point": 52.3, "uvi": 3.4, "visibility": 10000, "wind_speed": 8.1, "wind_deg": 230,
+            "weather": [{"id": 802, "main": "Clouds", "description": "scattered clouds", "icon": "03d"}]
+        },
+        "daily": [{
+            "dt": 1760716800, "summary": "Cloudy in the morning", "pop": 0.35,
+            "temp": {"day": 64.0, "min": 55.2, "max": 66.8, "night": 57.0, "eve": 62.0, "morn": 56.0},
+            "weather": [{"id": 500, "main": "Rain", "description": "light rain", "icon": "10d"}]
+        }]
+    }"#;
+
+    fn render(json: &str, units: Units) -> (String, String) {
+        Language::En.apply();
+        Clock::TwentyFourHour.apply();
+        let mut snapshot = snapshot(serde_json::from_str(json).unwrap());
+        snapshot.fetched_at = Local.with_ymd_and_hms(2026, 10, 17, 14, 5, 0).unwrap();
+        // Sunrise and sunset are left out of the fixtures, so their rows are too.
+        let hidden = ["Sunrise", "Sunset", "Daylight"].map(str::to_string).to_vec();
+        let config = Config { hidden_conditions: hidden, ..Config::default() };
+        let cli = to_text(&snapshot, units, &config, None);
+        (cli, summary::to_text(&snapshot, units, None))
+    }
+
+    #[test]
+    fn hot_day() {
+        let (cli, summary) = render(HOT, Units::IMPERIAL);
+        assert_eq!(
+            cli.lines().collect::<Vec<_>>(),
+            [
+                "Today's weather for New York - Clear sky",
+                "Summary                  Hot and sunny all day",
+                "Current weather          Clear sky",
+                "Temperature              98.6°F (feels like 104.2°F)",
+                "High                     101.3°F",
+                "Low                      80.2°F",
+                "Humidity                 40%",
+                "Dew point                70.1°F",
+                "Wind                     5.0 mph S (force 2, light breeze)",
+                "Pressure                 1008 hPa",
+                "Visibility               6.2 mi",
+                "UV Index                 10.2 Very high",
+                "Chance of rain today     0%",
+                "Chance of rain tomorrow  40%",
+            ]
+        );
+        assert_eq!(summary, "New York\n99°F, Clear sky\nHigh 101.3°F / Low 80.2°F, 0% chance of rain\nLight breeze from the S\nUpdated 14:05\n");
+    }
+
+    #[test]
+    fn freezing_day_in_metric() {
+        let (cli, summary) = render(FREEZING, Units::METRIC);
+        assert_eq!(
+            cli.lines().collect::<Vec<_>>(),
+            [
+                "Today's weather for New York - Clear sky",
+                "Summary                  Bitterly cold and clear",
+                "Current weather          Clear sky",
+                "Temperature              -15.0°C (feels like -24.6°C)",
+                "High                     -11.4°C",
+                "Low                      -20.0°C",
+                "Humidity                 65%",
+                "Dew point                -19.6°C",
+                "Wind                     8.9 m/s N (force 5, fresh breeze)",
+                "Pressure                 1031 hPa",
+                "Visibility               16.1 km",
+                "UV Index                 0.4 Low",
+                "Chance of rain today     0%",
+                "Chance of rain tomorrow  5%",
+            ]
+        );
+        assert_eq!(summary, "New York\n-15°C, Clear sky\nHigh -11.4°C / Low -20.0°C, 0% chance of rain\nFresh breeze from the N\nUpdated 14:05\n");
+    }
+
+    #[test]
+    fn snow_day() {
+        let (cli, summary) = render(SNOW, Units::IMPERIAL);
+        assert_eq!(
+            cli.lines().collect::<Vec<_>>(),
+            [
+                "Today's weather for New York - Snow",
+                "Summary                  Snow through the day",
+                "Current weather          Snow",
+                "Temperature              28.4°F (feels like 19.0°F)",
+                "High                     31.0°F",
+                "Low                      24.1°F",
+                "Humidity                 92%",
+                "Dew point                26.5°F",
+                "Wind                     12.5 mph NE (force 4, moderate breeze)",
+                "Pressure                 1002 hPa",
+                "Visibility               0.7 mi",
+                "UV Index                 0.8 Low",
+                "Chance of rain today     95%",
+                "Chance of rain tomorrow  60%",
+            ]
+        );
+        assert_eq!(summary, "New York\n28°F, Snow\nHigh 31.0°F / Low 24.1°F, 95% chance of rain\nModerate breeze from the NE\nUpdated 14:05\n");
+    }
+
+    #[test]
+    fn no_forecast_for_tomorrow() {
+        let (cli, summary) = render(NO_TOMORROW, Units::IMPERIAL);
+        assert_eq!(
+            cli.lines().collect::<Vec<_>>(),
+            [
+                "Today's weather for New York - Light rain",
+                "Summary                  Cloudy in the morning",
+                "Current weather          Scattered clouds",
+                "Temperature              61.5°F (feels like 60.2°F)",
+                "High                     66.8°F",
+                "Low                      55.2°F",
+                "Humidity                 72%",
+                "Dew point                52.3°F",
+                "Wind                     8.1 mph SW (force 3, gentle breeze)",
+                "Pressure                 1016 hPa",
+                "Visibility               6.2 mi",
+                "UV Index                 3.4 Moderate",
+                "Chance of rain today     35%",
+                "Chance of rain tomorrow  data unavailable",
+            ]
+        );
+        assert_eq!(summary, "New York\n62°F, Scattered clouds\nHigh 66.8°F / Low 55.2°F, 35% chance of rain\nGentle breeze from the SW\nUpdated 14:05\n");
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use eframe::egui;
+
+/// A background request whose result is polled from `update`.
+pub enum Fetch<T> {
+    Pending(Receiver<Result<T, String>>),
+    Ready(T),
+    Failed(String),
+}
+
+impl<T: Send + 'static> Fetch<T> {
+    /// Runs `future` on the tokio runtime and repaints once it finishes.
+    pub fn spawn<F, E>(ctx: &egui::Context, future: F) -> Self
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+        E: Display,
+    {
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let result = future.await.map_err(|e| e.to_string());
+            let _ = tx.send(result);
+            ctx.request_repaint();
+        });
+        Fetch::Pending(rx)
+    }
+
+    pub fn poll(&mut self) {
+        if let Fetch::Pending(rx) = self {
+            match rx.try_recv() {
+                Ok(Ok(value)) => *self = Fetch::Ready(value),
+                Ok(Err(e)) => *self = Fetch::Failed(e),
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => *self = Fetch::Failed("Request was cancelled".to_string()),
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! What a refresh changed in the daily forecast, e.g. "Chance of rain
+//! tomorrow up from 20% to 60%", compared with the last forecast for the
+//! same place.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+use eframe::egui;
+use crate::{i18n, time_format};
+use crate::units::Units;
+use crate::{Location, WeatherResponse, WeatherSnapshot};
+
+// Smallest changes worth mentioning, in °F, percentage points and mph.
+const MIN_TEMPERATURE_CHANGE: f64 = 3.0;
+const MIN_RAIN_CHANGE: f64 = 20.0;
+const MIN_WIND_CHANGE: f64 = 8.0;
+// Days further out are revised all the time.
+const DAYS_COMPARED: usize = 5;
+
+/// The part of a day's forecast that changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    High,
+    Low,
+    ChanceOfRain,
+    Wind,
+}
+
+impl Quantity {
+    fn min_change(self) -> f64 {
+        match self {
+            Quantity::High | Quantity::Low => MIN_TEMPERATURE_CHANGE,
+            Quantity::ChanceOfRain => MIN_RAIN_CHANGE,
+            Quantity::Wind => MIN_WIND_CHANGE,
+        }
+    }
+}
+
+/// One notable revision; values are in the forecast's units, with the
+/// chance of rain in percent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub quantity: Quantity,
+    pub date: NaiveDate,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl Change {
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+
+    /// E.g. "High Saturday revised down 5°F to 68°F".
+    pub fn describe(&self, units: Units) -> String {
+        let day = day_name(self.date);
+        let direction = if self.delta() > 0.0 { "up" } else { "down" };
+        match self.quantity {
+            Quantity::High | Quantity::Low => format!(
+                "{} {} revised {} {:.0}{} to {}",
+                if self.quantity == Quantity::High { "High" } else { "Low" },
+                day,
+                direction,
+                units.temperature_delta(self.delta().abs()),
+                units.temperature_unit(),
+                units.format_temperature(self.after, 0),
+            ),
+            Quantity::ChanceOfRain => {
+                format!("Chance of rain {} {} from {:.0}% to {:.0}%", day, direction, self.before, self.after)
+            }
+            Quantity::Wind => format!(
+                "Wind {} {} from {} to {}",
+                day,
+                direction,
+                units.format_speed(self.before, 0),
+                units.format_speed(self.after, 0)
+            ),
+        }
+    }
+}
+
+fn local_date(dt: i64) -> Option<NaiveDate> {
+    Local.timestamp_opt(dt, 0).single().map(|t| t.date_naive())
+}
+
+/// "today", "tomorrow" or the day of the week.
+fn day_name(date: NaiveDate) -> String {
+    let today = Local::now().date_naive();
+    if date == today {
+        "today".to_string()
+    } else if date == today + Duration::days(1) {
+        "tomorrow".to_string()
+    } else {
+        i18n::format_date(date, "%A")
+    }
+}
+
+/// The values compared for one day.
+struct Day {
+    date: NaiveDate,
+    high: f64,
+    low: f64,
+    rain: f64,
+    wind: Option<f64>,
+}
+
+impl Day {
+    fn value(&self, quantity: Quantity) -> Option<f64> {
+        match quantity {
+            Quantity::High => Some(self.high),
+            Quantity::Low => Some(self.low),
+            Quantity::ChanceOfRain => Some(self.rain),
+            Quantity::Wind => self.wind,
+        }
+    }
+}
+
+fn days(weather: &WeatherResponse) -> Vec<Day> {
+    let today = Local::now().date_naive();
+    weather
+        .daily
+        .iter()
+        .filter_map(|day| {
+            let date = local_date(day.dt).filter(|date| *date >= today)?;
+            Some(Day { date, high: day.temp.max, low: day.temp.min, rain: day.pop * 100.0, wind: day.wind_speed })
+        })
+        .take(DAYS_COMPARED)
+        .collect()
+}
+
+/// The notable differences between two forecasts for the same place, for
+/// the days both cover.
+fn diff(before: &[Day], after: &[Day]) -> Vec<Change> {
+    let quantities = [Quantity::ChanceOfRain, Quantity::High, Quantity::Low, Quantity::Wind];
+    let mut changes = Vec::new();
+    for new in after {
+        let Some(old) = before.iter().find(|old| old.date == new.date) else { continue };
+        for quantity in quantities {
+            let (Some(before), Some(after)) = (old.value(quantity), new.value(quantity)) else { continue };
+            if (after - before).abs() >= quantity.min_change() {
+                changes.push(Change { quantity, date: new.date, before, after });
+            }
+        }
+    }
+    changes
+}
+
+fn key(location: &Location) -> String {
+    format!("{:.2},{:.2}", location.lat, location.lon)
+}
+
+/// The last forecast seen for each place and what the latest refresh
+/// changed.
+pub struct ForecastChanges {
+    // When each place's forecast was fetched, and its days.
+    previous: HashMap<String, (DateTime<Local>, Vec<Day>)>,
+    // Place, the time of the forecast compared against, and the changes.
+    latest: Option<(String, DateTime<Local>, Vec<Change>)>,
+}
+
+impl ForecastChanges {
+    pub fn new() -> Self {
+        Self { previous: HashMap::new(), latest: None }
+    }
+
+    /// Compares `snapshot` with the last forecast for its location and
+    /// remembers it for next time. The first forecast for a place changes
+    /// nothing.
+    pub fn update(&mut self, snapshot: &WeatherSnapshot) {
+        let key = key(&snapshot.location);
+        let latest = (snapshot.fetched_at, days(&snapshot.weather));
+        let Some((since, before)) = self.previous.insert(key.clone(), latest) else { return };
+        let changes = diff(&before, &self.previous[&key].1);
+        for change in &changes {
+            tracing::info!(city = %snapshot.location.city, "Forecast changed: {}", change.describe(Units::IMPERIAL));
+        }
+        // The last changes stay up until something else changes.
+        if !changes.is_empty() {
+            self.latest = Some((key, since, changes));
+        }
+    }
+
+    /// What the latest refresh of `location` changed, if anything.
+    pub fn changes(&self, location: &Location) -> &[Change] {
+        match self.latest {
+            Some((ref place, _, ref changes)) if *place == key(location) => changes,
+            _ => &[],
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, location: &Location, units: Units) {
+        let Some((ref place, since, ref changes)) = self.latest else { return };
+        if *place != key(location) {
+            return;
+        }
+        let mut dismissed = false;
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.strong(format!("Forecast changes since {}", time_format::time(since.naive_local())));
+            dismissed = ui.small_button("Dismiss").clicked();
+        });
+        for change in changes {
+            let text = change.describe(units);
+            match change.quantity {
+                Quantity::ChanceOfRain | Quantity::Wind if change.delta() > 0.0 => {
+                    ui.colored_label(ui.visuals().warn_fg_color, text);
+                }
+                _ => {
+                    ui.label(text);
+                }
+            }
+        }
+        if dismissed {
+            self.latest = None;
+        }
+    }
+}
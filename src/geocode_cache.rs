@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::platform;
+
+// City coordinates practically never change, so entries are kept indefinitely.
+type Entries = HashMap<String, (f64, f64)>;
+
+fn cache_path() -> Option<PathBuf> {
+    platform::cache_dir().map(|dir| dir.join("geocode.json"))
+}
+
+fn load() -> Entries {
+    cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn key(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// Coordinates previously resolved for a geocoding query such as "paris,fr".
+pub fn lookup(query: &str) -> Option<(f64, f64)> {
+    load().get(&key(query)).copied()
+}
+
+pub fn insert(query: &str, coordinates: (f64, f64)) {
+    let Some(path) = cache_path() else { return };
+    let mut entries = load();
+    entries.insert(key(query), coordinates);
+    let result = serde_json::to_string(&entries)
+        .map_err(std::io::Error::from)
+        .and_then(|text| {
+            path.parent().map_or(Ok(()), fs::create_dir_all)?;
+            fs::write(&path, text)
+        });
+    if let Err(e) = result {
+        tracing::warn!("Unable to update geocoding cache at {}: {}", path.display(), e);
+    }
+}
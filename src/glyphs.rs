@@ -0,0 +1,42 @@
+use crate::WeatherSnapshot;
+
+/// Symbol for an OpenWeatherMap condition code, drawn with the emoji font
+/// egui bundles by default.
+pub fn condition_glyph(condition_id: u16) -> &'static str {
+    match condition_id {
+        200..=299 => "⛈",
+        300..=399 => "🌦",
+        500..=599 => "🌧",
+        600..=699 => "❄",
+        700..=799 => "🌫",
+        800 => "☀",
+        801 | 802 => "⛅",
+        803..=899 => "☁",
+        _ => "?",
+    }
+}
+
+/// Freedesktop icon name for an OpenWeatherMap condition code, which every
+/// common icon theme ships.
+pub fn icon_name(condition_id: u16) -> &'static str {
+    match condition_id {
+        200..=299 => "weather-storm",
+        300..=399 => "weather-showers-scattered",
+        500..=599 => "weather-showers",
+        600..=699 => "weather-snow",
+        700..=799 => "weather-fog",
+        800 => "weather-clear",
+        801 | 802 => "weather-few-clouds",
+        803..=899 => "weather-overcast",
+        _ => "weather-severe-alert",
+    }
+}
+
+/// Icon for the current conditions, or the alert icon while any alert is active.
+pub fn snapshot_icon(snapshot: &WeatherSnapshot) -> &'static str {
+    let weather = &snapshot.weather;
+    match weather.current.weather.first() {
+        Some(condition) if weather.alerts.is_empty() => icon_name(condition.id),
+        _ => "weather-severe-alert",
+    }
+}
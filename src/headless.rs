@@ -0,0 +1,38 @@
+//! Entry point for builds without the `gui` feature: fetch, print, write the
+//! summary files, and repeat on the refresh interval.
+
+use crate::cli;
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::template::Template;
+use crate::{fetch_weather_data, summary, WeatherSnapshot};
+
+/// Fetches every `REFRESH_INTERVAL_MINUTES` (15 by default) until stopped,
+/// or once when it is `0`, as with `--cli`. A failed fetch ends a one-shot
+/// run but is only logged by the daemon.
+pub async fn run() -> Result<(), WeatherError> {
+    let config = Config::load();
+    config.clock.apply();
+    config.language.apply();
+    let interval = config.refresh_interval();
+    let template = Template::from_config(&config);
+    let mut last_location = None;
+    loop {
+        match fetch_weather_data(config.clone(), last_location.clone()).await {
+            Ok(snapshot) => {
+                report(&snapshot, &config, template.as_ref());
+                last_location = Some(snapshot.location);
+            }
+            Err(e) if interval.is_some() => tracing::warn!("Weather fetch failed: {}", e),
+            Err(e) => return Err(e),
+        }
+        let Some(interval) = interval else { return Ok(()) };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn report(snapshot: &WeatherSnapshot, config: &Config, template: Option<&Template>) {
+    cli::print(snapshot, config.units, config, template);
+    println!();
+    summary::write_files(snapshot, config, template);
+}
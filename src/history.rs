@@ -0,0 +1,38 @@
+// Enough to back out of a few accidental clicks without keeping every change forever.
+const MAX_STEPS: usize = 50;
+
+/// Undo/redo stacks of whole-state snapshots. The state kept here (config
+/// and saved locations) is small, so storing copies is simpler than
+/// recording individual commands.
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> History<T> {
+    pub fn new() -> Self {
+        Self { undo: Vec::new(), redo: Vec::new() }
+    }
+
+    /// Records the state as it was before a change.
+    pub fn record(&mut self, before: T) {
+        if self.undo.len() == MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Returns the state to go back to, remembering `current` for redo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::mpsc::{self, Receiver};
+use eframe::egui;
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use crate::config::Config;
+
+/// Actions that can be triggered from anywhere on the desktop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HotkeyCommand {
+    Refresh,
+    /// Switches to the next saved location, wrapping round to the
+    /// detected one.
+    CycleLocation,
+}
+
+impl HotkeyCommand {
+    pub const ALL: [HotkeyCommand; 2] = [HotkeyCommand::Refresh, HotkeyCommand::CycleLocation];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyCommand::Refresh => "Refresh",
+            HotkeyCommand::CycleLocation => "Next location",
+        }
+    }
+
+    pub fn default_hotkey(self) -> &'static str {
+        match self {
+            HotkeyCommand::Refresh => "CmdOrCtrl+Alt+R",
+            HotkeyCommand::CycleLocation => "CmdOrCtrl+Alt+L",
+        }
+    }
+
+    /// The configured shortcut, the default when unset; empty turns it off.
+    pub fn hotkey(self, config: &Config) -> String {
+        let configured = match self {
+            HotkeyCommand::Refresh => config.refresh_hotkey(),
+            HotkeyCommand::CycleLocation => config.cycle_location_hotkey(),
+        };
+        configured.unwrap_or_else(|| self.default_hotkey().to_string())
+    }
+}
+
+/// Whether `text` is a shortcut that can be registered, e.g. `Alt+Shift+W`.
+pub fn validate(text: &str) -> Result<(), String> {
+    text.parse::<HotKey>().map(|_| ()).map_err(|e| e.to_string())
+}
+
+struct Registered {
+    // Hotkeys are unregistered when the manager is dropped.
+    _manager: GlobalHotKeyManager,
+    ids: Vec<(u32, HotkeyCommand)>,
+    events: Receiver<GlobalHotKeyEvent>,
+}
+
+/// System-wide hotkeys registered while the app is running.
+pub struct Hotkeys {
+    ctx: egui::Context,
+    // What was asked for, to tell when the settings change.
+    bindings: Vec<String>,
+    registered: Option<Registered>,
+}
+
+impl Hotkeys {
+    /// Registers the configured hotkeys. Must be called on the main thread.
+    pub fn register(ctx: &egui::Context, config: &Config) -> Self {
+        let bindings: Vec<String> = HotkeyCommand::ALL.iter().map(|command| command.hotkey(config)).collect();
+        let registered = register(ctx, &bindings);
+        Self { ctx: ctx.clone(), bindings, registered }
+    }
+
+    /// Registers the hotkeys again if the settings changed them.
+    pub fn update(&mut self, config: &Config) {
+        let bindings: Vec<String> = HotkeyCommand::ALL.iter().map(|command| command.hotkey(config)).collect();
+        if bindings != self.bindings {
+            // The old ones have to go first in case a key moved between actions.
+            self.registered = None;
+            self.registered = register(&self.ctx, &bindings);
+            self.bindings = bindings;
+        }
+    }
+
+    /// Commands triggered since the last call.
+    pub fn take_commands(&self) -> Vec<HotkeyCommand> {
+        let Some(ref registered) = self.registered else { return Vec::new() };
+        registered
+            .events
+            .try_iter()
+            .filter(|event| event.state == HotKeyState::Pressed)
+            .filter_map(|event| registered.ids.iter().find(|(id, _)| *id == event.id).map(|(_, command)| *command))
+            .collect()
+    }
+}
+
+/// `bindings` in the order of [`HotkeyCommand::ALL`]; empty ones are skipped.
+fn register(ctx: &egui::Context, bindings: &[String]) -> Option<Registered> {
+    let mut hotkeys = Vec::new();
+    for (command, binding) in HotkeyCommand::ALL.into_iter().zip(bindings) {
+        if binding.trim().is_empty() {
+            continue;
+        }
+        match binding.parse::<HotKey>() {
+            Ok(hotkey) => hotkeys.push((hotkey, command)),
+            Err(e) => tracing::warn!("Invalid {} hotkey {:?}: {}", command.label().to_lowercase(), binding, e),
+        }
+    }
+    if hotkeys.is_empty() {
+        return None;
+    }
+
+    let manager = match GlobalHotKeyManager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            tracing::warn!("Global hotkeys unavailable: {}", e);
+            return None;
+        }
+    };
+    let mut ids = Vec::new();
+    for (hotkey, command) in hotkeys {
+        match manager.register(hotkey) {
+            Ok(()) => ids.push((hotkey.id(), command)),
+            Err(e) => tracing::warn!("Unable to register the {} hotkey: {}", command.label().to_lowercase(), e),
+        }
+    }
+
+    // Forward events ourselves so a hotkey wakes the UI even when it's idle.
+    let (tx, events) = mpsc::channel();
+    let ctx = ctx.clone();
+    GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+        let _ = tx.send(event);
+        ctx.request_repaint();
+    }));
+
+    Some(Registered { _manager: manager, ids, events })
+}
@@ -0,0 +1,51 @@
+use eframe::egui;
+use crate::glyphs::condition_glyph;
+use crate::session;
+use crate::time_format;
+use crate::units::Units;
+use crate::Hourly;
+
+// One Call returns 48 hours.
+const HOURS_SHOWN: usize = 48;
+// Chance of precipitation worth drawing attention to.
+const WET_POP: f64 = 0.3;
+const WET_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 150, 230);
+
+/// Scrollable hour-by-hour strip of temperature, conditions and chance of
+/// precipitation, so it's clear when rain is due to start.
+pub fn show(ui: &mut egui::Ui, hourly: &[Hourly], units: Units) {
+    session::card(ui, "Hourly forecast", true, |ui| {
+        egui::ScrollArea::horizontal().id_source("hourly_forecast").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for hour in hourly.iter().take(HOURS_SHOWN) {
+                    show_hour(ui, hour, units);
+                }
+            });
+        });
+    });
+}
+
+fn show_hour(ui: &mut egui::Ui, hour: &Hourly, units: Units) {
+    egui::Frame::group(ui.style()).show(ui, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.set_width(48.0);
+            ui.label(time_format::local_hour(hour.dt));
+            let condition = hour.weather.first();
+            let glyph = ui.label(egui::RichText::new(condition.map_or("", |c| condition_glyph(c.id))).size(20.0));
+            if let Some(condition) = condition {
+                glyph.on_hover_text(&condition.description);
+            }
+            ui.label(units.format_temperature(hour.temp, 0));
+            let pop = format!("{:.0}%", (hour.pop.min(1.0) * 100.0).round());
+            if hour.pop >= WET_POP {
+                ui.colored_label(WET_COLOR, pop);
+            } else {
+                ui.small(pop);
+            }
+            let amount = hour.rain.iter().chain(&hour.snow).map(|p| p.one_hour).sum::<f64>();
+            if amount > 0.0 {
+                ui.small(units.format_precipitation(amount)).on_hover_text("Precipitation in the hour");
+            }
+        });
+    });
+}
@@ -0,0 +1,556 @@
+//! Translated labels and locale-aware numbers and dates. Catalogs are keyed
+//! by the English text, which is also what shows for anything untranslated.
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+use chrono::{DateTime, Locale, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// Whatever the locale (`LANGUAGE`, `LC_ALL`, `LC_MESSAGES` or `LANG`)
+    /// asks for, if it is one of the others.
+    #[default]
+    Auto,
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+// Set from the config like the clock, so that labels can be translated
+// wherever they are drawn.
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+impl Language {
+    #[cfg(feature = "gui")]
+    pub const ALL: [Language; 5] = [Language::Auto, Language::En, Language::De, Language::Fr, Language::Es];
+
+    /// Each language names itself.
+    #[cfg(feature = "gui")]
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Auto => "System default",
+            Language::En => "English",
+            Language::De => "Deutsch",
+            Language::Fr => "Français",
+            Language::Es => "Español",
+        }
+    }
+
+    /// Makes this the language every helper below uses.
+    pub fn apply(self) {
+        LANGUAGE.store(self as u8, Ordering::Relaxed);
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code.get(..2)?.to_ascii_lowercase().as_str() {
+            "en" => Some(Language::En),
+            "de" => Some(Language::De),
+            "fr" => Some(Language::Fr),
+            "es" => Some(Language::Es),
+            _ => None,
+        }
+    }
+}
+
+fn current() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        2 => Language::De,
+        3 => Language::Fr,
+        4 => Language::Es,
+        // `LANGUAGE` may list several, e.g. "de:en".
+        _ => ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .flat_map(|value| value.split(':').map(str::to_string).collect::<Vec<_>>())
+            .find_map(|code| Language::from_code(&code))
+            .unwrap_or(Language::En),
+    }
+}
+
+/// The OpenWeatherMap `lang` parameter, so descriptions come back translated.
+pub fn code() -> &'static str {
+    match current() {
+        Language::De => "de",
+        Language::Fr => "fr",
+        Language::Es => "es",
+        Language::En | Language::Auto => "en",
+    }
+}
+
+fn locale() -> Locale {
+    match current() {
+        Language::De => Locale::de_DE,
+        Language::Fr => Locale::fr_FR,
+        Language::Es => Locale::es_ES,
+        Language::En | Language::Auto => Locale::en_US,
+    }
+}
+
+/// `text` in the current language.
+pub fn tr(text: &'static str) -> &'static str {
+    let catalog = match current() {
+        Language::De => DE,
+        Language::Fr => FR,
+        Language::Es => ES,
+        Language::En | Language::Auto => return text,
+    };
+    catalog.iter().find(|(english, _)| *english == text).map_or(text, |(_, translated)| translated)
+}
+
+/// [`tr`] for a template with `{}` placeholders, which are filled with
+/// `args` in order.
+pub fn tr_args(template: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut text = String::new();
+    let mut rest = tr(template);
+    for arg in args {
+        let Some((before, after)) = rest.split_once("{}") else { break };
+        text.push_str(before);
+        text.push_str(&arg.to_string());
+        rest = after;
+    }
+    text.push_str(rest);
+    text
+}
+
+/// `value` to `precision` decimals, with a decimal comma where that is the
+/// convention.
+pub fn decimal(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+    match current() {
+        Language::De | Language::Fr | Language::Es => formatted.replacen('.', ",", 1),
+        Language::En | Language::Auto => formatted,
+    }
+}
+
+/// One of the 16 compass points, counting clockwise from north.
+pub fn cardinal(index: usize) -> &'static str {
+    let points = match current() {
+        Language::De => &DE_CARDINALS,
+        Language::Fr | Language::Es => &ROMANCE_CARDINALS,
+        Language::En | Language::Auto => &CARDINALS,
+    };
+    points[index % 16]
+}
+
+/// `date.format(fmt)` with day and month names in the current language.
+pub fn format_date(date: NaiveDate, fmt: &str) -> String {
+    date.format_localized(fmt, locale()).to_string()
+}
+
+/// [`format_date`] for anything with a time zone, e.g. `DateTime<Local>`.
+pub fn format_datetime<Tz: TimeZone>(t: &DateTime<Tz>, fmt: &str) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    t.format_localized(fmt, locale()).to_string()
+}
+
+const CARDINALS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+];
+const DE_CARDINALS: [&str; 16] = [
+    "N", "NNO", "NO", "ONO", "O", "OSO", "SO", "SSO", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+];
+const ROMANCE_CARDINALS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSO", "SO", "OSO", "O", "ONO", "NO", "NNO",
+];
+
+const DE: &[(&str, &str)] = &[
+    ("Today's weather for", "Wetter heute für"),
+    ("Tonight in", "Heute Nacht in"),
+    ("Tonight's low", "Tiefstwert heute Nacht"),
+    ("Chance of rain overnight", "Regenwahrscheinlichkeit heute Nacht"),
+    ("Tomorrow", "Morgen"),
+    ("Summary", "Zusammenfassung"),
+    ("Current weather", "Aktuelles Wetter"),
+    ("Temperature", "Temperatur"),
+    ("High", "Höchstwert"),
+    ("Low", "Tiefstwert"),
+    ("Humidity", "Luftfeuchtigkeit"),
+    ("Dew point", "Taupunkt"),
+    ("Wind", "Wind"),
+    ("Pressure", "Luftdruck"),
+    ("Visibility", "Sichtweite"),
+    ("UV Index", "UV-Index"),
+    ("Chance of rain today", "Regenwahrscheinlichkeit heute"),
+    ("Chance of rain tomorrow", "Regenwahrscheinlichkeit morgen"),
+    ("Sunrise", "Sonnenaufgang"),
+    ("Sunset", "Sonnenuntergang"),
+    ("Daylight", "Tageslicht"),
+    ("Trip planner", "Reiseplaner"),
+    ("Compare", "Vergleichen"),
+    ("Rules", "Regeln"),
+    ("Settings", "Einstellungen"),
+    ("Refresh", "Aktualisieren"),
+    ("Fetching weather data...", "Wetterdaten werden geladen..."),
+    ("Current location", "Aktueller Standort"),
+    ("Edit", "Bearbeiten"),
+    ("Flyable up to", "Fliegbar bis"),
+    ("Updated {}", "Aktualisiert {}"),
+    ("Cached, {} old — reconnecting...", "Zwischengespeichert, {} alt — neue Verbindung..."),
+    ("just now", "gerade eben"),
+    ("1 minute ago", "vor 1 Minute"),
+    ("{} minutes ago", "vor {} Minuten"),
+    ("{}h {}m ago", "vor {} h {} min"),
+    ("Provider", "Anbieter"),
+    ("Automatic", "Automatisch"),
+    ("OpenWeatherMap API key", "OpenWeatherMap-API-Schlüssel"),
+    ("Refresh every", "Aktualisieren alle"),
+    ("Low data", "Wenig Daten"),
+    ("For metered connections", "Für getaktete Verbindungen"),
+    ("Wind speed", "Windgeschwindigkeit"),
+    ("Precipitation", "Niederschlag"),
+    ("Times", "Uhrzeit"),
+    ("Language", "Sprache"),
+    ("System default", "Systemstandard"),
+    ("12-hour", "12 Stunden"),
+    ("24-hour", "24 Stunden"),
+    ("Theme", "Design"),
+    ("Day/night", "Tag/Nacht"),
+    ("Light", "Hell"),
+    ("Dark", "Dunkel"),
+    ("Interface scale", "Skalierung"),
+    ("System", "System"),
+    ("Whole", "Ganzzahlig"),
+    ("Custom", "Eigene"),
+    ("Skin type", "Hauttyp"),
+    ("Type {}", "Typ {}"),
+    ("Not set", "Nicht festgelegt"),
+    ("Current conditions", "Aktuelle Bedingungen"),
+    ("Hotkeys", "Tastenkürzel"),
+    ("Next location", "Nächster Ort"),
+    ("Off", "Aus"),
+    ("Notifications", "Benachrichtigungen"),
+    ("Send test notification", "Testbenachrichtigung senden"),
+    ("Sent at {}", "Gesendet um {}"),
+    ("Failed", "Fehlgeschlagen"),
+    ("Nothing sent yet.", "Noch nichts gesendet."),
+    ("Recent:", "Zuletzt:"),
+    ("shown", "angezeigt"),
+    ("failed: {}", "fehlgeschlagen: {}"),
+    ("View logs", "Protokoll anzeigen"),
+    ("Overridden by the environment: {}", "Durch die Umgebung überschrieben: {}"),
+    ("Alert rules", "Warnregeln"),
+    ("No rules yet.", "Noch keine Regeln."),
+    ("Remove", "Entfernen"),
+    ("Add rule", "Regel hinzufügen"),
+    ("below", "unter"),
+    ("above", "über"),
+    ("changes by", "ändert sich um"),
+    ("Down to", "Sinkt auf"),
+    ("Up to", "Steigt auf"),
+    ("now", "jetzt"),
+    ("today", "heute"),
+    ("tomorrow", "morgen"),
+    ("in the next 24 hours", "in den nächsten 24 Stunden"),
+    ("Gusts", "Böen"),
+    ("Chance of rain", "Regenwahrscheinlichkeit"),
+    ("UV index", "UV-Index"),
+    ("Road hazards", "Straßengefahren"),
+    ("River over flood stage", "Fluss über Hochwassermarke"),
+    ("Quake magnitude", "Erdbebenstärke"),
+    ("Quake distance", "Erdbebenentfernung"),
+    ("Compare locations", "Orte vergleichen"),
+    ("Save a few locations to compare them here.", "Speichere ein paar Orte, um sie hier zu vergleichen."),
+    ("High / low, chance of precipitation", "Höchst- / Tiefstwert, Niederschlagswahrscheinlichkeit"),
+    ("Today's Weather", "Wetter heute"),
+    ("data unavailable", "keine Daten"),
+    ("{} (feels like {})", "{} (gefühlt {})"),
+    ("{}, rising", "{}, steigend"),
+    ("{}, falling", "{}, fallend"),
+    ("{}, steady", "{}, gleichbleibend"),
+    ("High {}, low {}", "Höchstwert {}, Tiefstwert {}"),
+    ("High {} / Low {}", "Max. {} / Min. {}"),
+    ("Located by {}{}", "Standort: {}{}"),
+    (", approximate", ", ungefähr"),
+    ("location services", "Ortungsdienste"),
+    ("IP address (ip-api.com)", "IP-Adresse (ip-api.com)"),
+    ("IP address (ipwho.is)", "IP-Adresse (ipwho.is)"),
+    ("last known location", "letzter bekannter Standort"),
+    ("Refresh failed: {}", "Aktualisierung fehlgeschlagen: {}"),
+    ("The next few days at each saved location", "Die nächsten Tage an jedem gespeicherten Ort"),
+    ("Your own alert rules", "Eigene Warnregeln"),
+    ("A small window that stays on top (Ctrl+M)", "Ein kleines Fenster, das im Vordergrund bleibt (Strg+M)"),
+    ("Automatic uses OpenWeatherMap when there is an API key", "Automatisch nutzt OpenWeatherMap, wenn ein API-Schlüssel vorhanden ist"),
+    ("0 turns automatic refreshes off", "0 schaltet die automatische Aktualisierung aus"),
+    (
+        "Refreshes four times less often, turns off radar and webcams, and only loads other panels when asked",
+        "Aktualisiert viermal seltener, schaltet Radar und Webcams ab und lädt andere Bereiche nur auf Anfrage",
+    ),
+    ("Labels, numbers, dates and OpenWeatherMap's descriptions", "Beschriftungen, Zahlen, Daten und die Beschreibungen von OpenWeatherMap"),
+    ("Follow the scale the monitor reports", "Der vom Bildschirm gemeldeten Skalierung folgen"),
+    ("Round fractional scaling to a whole number for sharper text", "Gebrochene Skalierung für schärferen Text auf eine ganze Zahl runden"),
+    (
+        "Fitzpatrick type, from I (always burns) to VI (never burns), for the time to sunburn next to the UV index",
+        "Fitzpatrick-Typ, von I (immer Sonnenbrand) bis VI (nie Sonnenbrand), für die Zeit bis zum Sonnenbrand neben dem UV-Index",
+    ),
+    ("For example {}; leave it empty to turn it off", "Zum Beispiel {}; leer lassen zum Ausschalten"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("Today's weather for", "Météo du jour à"),
+    ("Tonight in", "Cette nuit à"),
+    ("Tonight's low", "Minimale cette nuit"),
+    ("Chance of rain overnight", "Risque de pluie cette nuit"),
+    ("Tomorrow", "Demain"),
+    ("Summary", "Résumé"),
+    ("Current weather", "Temps actuel"),
+    ("Temperature", "Température"),
+    ("High", "Maximale"),
+    ("Low", "Minimale"),
+    ("Humidity", "Humidité"),
+    ("Dew point", "Point de rosée"),
+    ("Wind", "Vent"),
+    ("Pressure", "Pression"),
+    ("Visibility", "Visibilité"),
+    ("UV Index", "Indice UV"),
+    ("Chance of rain today", "Risque de pluie aujourd'hui"),
+    ("Chance of rain tomorrow", "Risque de pluie demain"),
+    ("Sunrise", "Lever du soleil"),
+    ("Sunset", "Coucher du soleil"),
+    ("Daylight", "Durée du jour"),
+    ("Trip planner", "Planificateur de voyage"),
+    ("Compare", "Comparer"),
+    ("Rules", "Règles"),
+    ("Settings", "Paramètres"),
+    ("Refresh", "Actualiser"),
+    ("Fetching weather data...", "Chargement de la météo..."),
+    ("Current location", "Position actuelle"),
+    ("Edit", "Modifier"),
+    ("Flyable up to", "Volable jusqu'à"),
+    ("Updated {}", "Mis à jour {}"),
+    ("Cached, {} old — reconnecting...", "En cache depuis {} — reconnexion..."),
+    ("just now", "à l'instant"),
+    ("1 minute ago", "il y a 1 minute"),
+    ("{} minutes ago", "il y a {} minutes"),
+    ("{}h {}m ago", "il y a {} h {} min"),
+    ("Provider", "Fournisseur"),
+    ("Automatic", "Automatique"),
+    ("OpenWeatherMap API key", "Clé API OpenWeatherMap"),
+    ("Refresh every", "Actualiser toutes les"),
+    ("Low data", "Données réduites"),
+    ("For metered connections", "Pour les connexions limitées"),
+    ("Wind speed", "Vitesse du vent"),
+    ("Precipitation", "Précipitations"),
+    ("Times", "Heures"),
+    ("Language", "Langue"),
+    ("System default", "Valeur du système"),
+    ("12-hour", "12 heures"),
+    ("24-hour", "24 heures"),
+    ("Theme", "Thème"),
+    ("Day/night", "Jour/nuit"),
+    ("Light", "Clair"),
+    ("Dark", "Sombre"),
+    ("Interface scale", "Échelle de l'interface"),
+    ("System", "Système"),
+    ("Whole", "Entière"),
+    ("Custom", "Personnalisée"),
+    ("Skin type", "Type de peau"),
+    ("Type {}", "Type {}"),
+    ("Not set", "Non défini"),
+    ("Current conditions", "Conditions actuelles"),
+    ("Hotkeys", "Raccourcis clavier"),
+    ("Next location", "Lieu suivant"),
+    ("Off", "Désactivé"),
+    ("Notifications", "Notifications"),
+    ("Send test notification", "Envoyer une notification de test"),
+    ("Sent at {}", "Envoyée à {}"),
+    ("Failed", "Échec"),
+    ("Nothing sent yet.", "Rien n'a encore été envoyé."),
+    ("Recent:", "Récentes :"),
+    ("shown", "affichée"),
+    ("failed: {}", "échec : {}"),
+    ("View logs", "Voir les journaux"),
+    ("Overridden by the environment: {}", "Remplacé par l'environnement : {}"),
+    ("Alert rules", "Règles d'alerte"),
+    ("No rules yet.", "Aucune règle pour l'instant."),
+    ("Remove", "Supprimer"),
+    ("Add rule", "Ajouter la règle"),
+    ("below", "sous"),
+    ("above", "au-dessus de"),
+    ("changes by", "varie de"),
+    ("Down to", "Descend à"),
+    ("Up to", "Monte à"),
+    ("now", "maintenant"),
+    ("today", "aujourd'hui"),
+    ("tomorrow", "demain"),
+    ("in the next 24 hours", "dans les prochaines 24 heures"),
+    ("Gusts", "Rafales"),
+    ("Chance of rain", "Risque de pluie"),
+    ("UV index", "Indice UV"),
+    ("Road hazards", "Dangers sur la route"),
+    ("River over flood stage", "Rivière au-dessus de la cote de crue"),
+    ("Quake magnitude", "Magnitude du séisme"),
+    ("Quake distance", "Distance du séisme"),
+    ("Compare locations", "Comparer les lieux"),
+    ("Save a few locations to compare them here.", "Enregistrez quelques lieux pour les comparer ici."),
+    ("High / low, chance of precipitation", "Maximale / minimale, risque de précipitations"),
+    ("Today's Weather", "Météo du jour"),
+    ("data unavailable", "données indisponibles"),
+    ("{} (feels like {})", "{} (ressenti {})"),
+    ("{}, rising", "{}, en hausse"),
+    ("{}, falling", "{}, en baisse"),
+    ("{}, steady", "{}, stable"),
+    ("High {}, low {}", "Maximale {}, minimale {}"),
+    ("High {} / Low {}", "Max. {} / Min. {}"),
+    ("Located by {}{}", "Localisé via {}{}"),
+    (", approximate", ", approximatif"),
+    ("location services", "les services de localisation"),
+    ("IP address (ip-api.com)", "l'adresse IP (ip-api.com)"),
+    ("IP address (ipwho.is)", "l'adresse IP (ipwho.is)"),
+    ("last known location", "la dernière position connue"),
+    ("Refresh failed: {}", "Échec de l'actualisation : {}"),
+    ("The next few days at each saved location", "Les prochains jours à chaque lieu enregistré"),
+    ("Your own alert rules", "Vos propres règles d'alerte"),
+    ("A small window that stays on top (Ctrl+M)", "Une petite fenêtre toujours au premier plan (Ctrl+M)"),
+    ("Automatic uses OpenWeatherMap when there is an API key", "Automatique utilise OpenWeatherMap s'il y a une clé API"),
+    ("0 turns automatic refreshes off", "0 désactive l'actualisation automatique"),
+    (
+        "Refreshes four times less often, turns off radar and webcams, and only loads other panels when asked",
+        "Actualise quatre fois moins souvent, désactive le radar et les webcams, et ne charge les autres panneaux qu'à la demande",
+    ),
+    ("Labels, numbers, dates and OpenWeatherMap's descriptions", "Libellés, nombres, dates et descriptions d'OpenWeatherMap"),
+    ("Follow the scale the monitor reports", "Suivre l'échelle indiquée par l'écran"),
+    ("Round fractional scaling to a whole number for sharper text", "Arrondir une échelle fractionnaire à un entier pour un texte plus net"),
+    (
+        "Fitzpatrick type, from I (always burns) to VI (never burns), for the time to sunburn next to the UV index",
+        "Type de Fitzpatrick, de I (brûle toujours) à VI (ne brûle jamais), pour le temps avant coup de soleil à côté de l'indice UV",
+    ),
+    ("For example {}; leave it empty to turn it off", "Par exemple {} ; laisser vide pour désactiver"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("Today's weather for", "El tiempo hoy en"),
+    ("Tonight in", "Esta noche en"),
+    ("Tonight's low", "Mínima esta noche"),
+    ("Chance of rain overnight", "Probabilidad de lluvia esta noche"),
+    ("Tomorrow", "Mañana"),
+    ("Summary", "Resumen"),
+    ("Current weather", "Tiempo actual"),
+    ("Temperature", "Temperatura"),
+    ("High", "Máxima"),
+    ("Low", "Mínima"),
+    ("Humidity", "Humedad"),
+    ("Dew point", "Punto de rocío"),
+    ("Wind", "Viento"),
+    ("Pressure", "Presión"),
+    ("Visibility", "Visibilidad"),
+    ("UV Index", "Índice UV"),
+    ("Chance of rain today", "Probabilidad de lluvia hoy"),
+    ("Chance of rain tomorrow", "Probabilidad de lluvia mañana"),
+    ("Sunrise", "Amanecer"),
+    ("Sunset", "Atardecer"),
+    ("Daylight", "Horas de luz"),
+    ("Trip planner", "Planificador de viajes"),
+    ("Compare", "Comparar"),
+    ("Rules", "Reglas"),
+    ("Settings", "Ajustes"),
+    ("Refresh", "Actualizar"),
+    ("Fetching weather data...", "Obteniendo datos del tiempo..."),
+    ("Current location", "Ubicación actual"),
+    ("Edit", "Editar"),
+    ("Flyable up to", "Volable hasta"),
+    ("Updated {}", "Actualizado {}"),
+    ("Cached, {} old — reconnecting...", "En caché desde hace {} — reconectando..."),
+    ("just now", "justo ahora"),
+    ("1 minute ago", "hace 1 minuto"),
+    ("{} minutes ago", "hace {} minutos"),
+    ("{}h {}m ago", "hace {} h {} min"),
+    ("Provider", "Proveedor"),
+    ("Automatic", "Automático"),
+    ("OpenWeatherMap API key", "Clave API de OpenWeatherMap"),
+    ("Refresh every", "Actualizar cada"),
+    ("Low data", "Pocos datos"),
+    ("For metered connections", "Para conexiones medidas"),
+    ("Wind speed", "Velocidad del viento"),
+    ("Precipitation", "Precipitación"),
+    ("Times", "Horas"),
+    ("Language", "Idioma"),
+    ("System default", "Predeterminado del sistema"),
+    ("12-hour", "12 horas"),
+    ("24-hour", "24 horas"),
+    ("Theme", "Tema"),
+    ("Day/night", "Día/noche"),
+    ("Light", "Claro"),
+    ("Dark", "Oscuro"),
+    ("Interface scale", "Escala de la interfaz"),
+    ("System", "Sistema"),
+    ("Whole", "Entera"),
+    ("Custom", "Personalizada"),
+    ("Skin type", "Tipo de piel"),
+    ("Type {}", "Tipo {}"),
+    ("Not set", "Sin definir"),
+    ("Current conditions", "Condiciones actuales"),
+    ("Hotkeys", "Atajos de teclado"),
+    ("Next location", "Siguiente ubicación"),
+    ("Off", "Desactivado"),
+    ("Notifications", "Notificaciones"),
+    ("Send test notification", "Enviar notificación de prueba"),
+    ("Sent at {}", "Enviada a las {}"),
+    ("Failed", "Error"),
+    ("Nothing sent yet.", "Aún no se ha enviado nada."),
+    ("Recent:", "Recientes:"),
+    ("shown", "mostrada"),
+    ("failed: {}", "error: {}"),
+    ("View logs", "Ver registros"),
+    ("Overridden by the environment: {}", "Reemplazado por el entorno: {}"),
+    ("Alert rules", "Reglas de alerta"),
+    ("No rules yet.", "Aún no hay reglas."),
+    ("Remove", "Quitar"),
+    ("Add rule", "Añadir regla"),
+    ("below", "por debajo de"),
+    ("above", "por encima de"),
+    ("changes by", "cambia en"),
+    ("Down to", "Baja a"),
+    ("Up to", "Sube a"),
+    ("now", "ahora"),
+    ("today", "hoy"),
+    ("tomorrow", "mañana"),
+    ("in the next 24 hours", "en las próximas 24 horas"),
+    ("Gusts", "Rachas"),
+    ("Chance of rain", "Probabilidad de lluvia"),
+    ("UV index", "Índice UV"),
+    ("Road hazards", "Peligros en la carretera"),
+    ("River over flood stage", "Río sobre el nivel de inundación"),
+    ("Quake magnitude", "Magnitud del sismo"),
+    ("Quake distance", "Distancia del sismo"),
+    ("Compare locations", "Comparar ubicaciones"),
+    ("Save a few locations to compare them here.", "Guarda algunas ubicaciones para compararlas aquí."),
+    ("High / low, chance of precipitation", "Máxima / mínima, probabilidad de precipitación"),
+    ("Today's Weather", "El tiempo hoy"),
+    ("data unavailable", "datos no disponibles"),
+    ("{} (feels like {})", "{} (sensación de {})"),
+    ("{}, rising", "{}, subiendo"),
+    ("{}, falling", "{}, bajando"),
+    ("{}, steady", "{}, estable"),
+    ("High {}, low {}", "Máxima {}, mínima {}"),
+    ("High {} / Low {}", "Máx. {} / Mín. {}"),
+    ("Located by {}{}", "Localizado por {}{}"),
+    (", approximate", ", aproximada"),
+    ("location services", "servicios de ubicación"),
+    ("IP address (ip-api.com)", "dirección IP (ip-api.com)"),
+    ("IP address (ipwho.is)", "dirección IP (ipwho.is)"),
+    ("last known location", "última ubicación conocida"),
+    ("Refresh failed: {}", "Error al actualizar: {}"),
+    ("The next few days at each saved location", "Los próximos días en cada lugar guardado"),
+    ("Your own alert rules", "Tus propias reglas de alerta"),
+    ("A small window that stays on top (Ctrl+M)", "Una ventana pequeña siempre visible (Ctrl+M)"),
+    ("Automatic uses OpenWeatherMap when there is an API key", "Automático usa OpenWeatherMap si hay una clave de API"),
+    ("0 turns automatic refreshes off", "0 desactiva la actualización automática"),
+    (
+        "Refreshes four times less often, turns off radar and webcams, and only loads other panels when asked",
+        "Actualiza cuatro veces menos, desactiva el radar y las webcams, y solo carga otros paneles cuando se piden",
+    ),
+    ("Labels, numbers, dates and OpenWeatherMap's descriptions", "Etiquetas, números, fechas y descripciones de OpenWeatherMap"),
+    ("Follow the scale the monitor reports", "Seguir la escala que indica el monitor"),
+    ("Round fractional scaling to a whole number for sharper text", "Redondear la escala fraccionaria a un entero para un texto más nítido"),
+    (
+        "Fitzpatrick type, from I (always burns) to VI (never burns), for the time to sunburn next to the UV index",
+        "Tipo de Fitzpatrick, de I (siempre se quema) a VI (nunca se quema), para el tiempo hasta quemarse junto al índice UV",
+    ),
+    ("For example {}; leave it empty to turn it off", "Por ejemplo {}; déjalo vacío para desactivarlo"),
+];
@@ -0,0 +1,368 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use eframe::egui;
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use crate::config::Config;
+use crate::template::Template;
+use crate::units::Units;
+use crate::{current_conditions, glyphs, summary, WeatherSnapshot};
+
+// Bump when a field of the `/widget` response is removed or changes meaning;
+// adding fields is backwards compatible.
+const WIDGET_SCHEMA_VERSION: u32 = 1;
+// Browsers can't add a custom header to a cross-site request without a
+// CORS preflight, which this server never approves.
+const REFRESH_HEADER: &str = "x-weather-alerts";
+// Pause after a failed accept, e.g. out of file descriptors, so the loop
+// doesn't spin.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(500);
+// A client that sends its request slowly, or never finishes the headers,
+// is dropped rather than holding a task and its buffer open.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// What `GET /current` answers with, picked from the `Accept` header or a
+/// `?format=` query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Text,
+    Csv,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Format::Json),
+            "text" | "txt" => Some(Format::Text),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+
+    /// The first media type in the header that can be served, in the
+    /// client's order; quality values are ignored.
+    fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',').find_map(|media_type| {
+            match media_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+                "application/json" | "application/*" | "*/*" => Some(Format::Json),
+                "text/plain" => Some(Format::Text),
+                "text/csv" => Some(Format::Csv),
+                _ => None,
+            }
+        })
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Text => "text/plain; charset=utf-8",
+            Format::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+/// Pre-rendered bodies for `GET /current`.
+struct Current {
+    json: String,
+    text: String,
+    csv: String,
+}
+
+impl Current {
+    fn body(&self, format: Format) -> &str {
+        match format {
+            Format::Json => &self.json,
+            Format::Text => &self.text,
+            Format::Csv => &self.csv,
+        }
+    }
+}
+
+/// The headers the server looks at.
+#[derive(Debug, Default)]
+struct Headers {
+    accept: Option<String>,
+    host: Option<String>,
+    origin: Option<String>,
+    refresh_header: bool,
+}
+
+/// Why a request is turned away before routing: web pages can reach
+/// 127.0.0.1 too, through DNS rebinding or a cross-site form post.
+fn refuse(method: &str, headers: &Headers, port: u16) -> Option<&'static str> {
+    let host_allowed = headers.host.as_deref().is_some_and(|host| {
+        [format!("127.0.0.1:{}", port), format!("localhost:{}", port)]
+            .iter()
+            .any(|allowed| host.eq_ignore_ascii_case(allowed))
+    });
+    if !host_allowed {
+        return Some("unexpected Host header");
+    }
+    if method == "POST" && headers.origin.is_some() {
+        return Some("cross-origin requests are not allowed");
+    }
+    if method == "POST" && !headers.refresh_header {
+        return Some("POST requests need an X-Weather-Alerts header");
+    }
+    None
+}
+
+struct Shared {
+    port: u16,
+    current: Mutex<Option<Current>>,
+    widget: Mutex<Option<String>>,
+    refresh_requested: AtomicBool,
+    ctx: egui::Context,
+}
+
+/// Localhost-only HTTP endpoint that lets other local tools read the data the
+/// app already fetched (`GET /current`) or ask it to re-fetch (`POST /refresh`).
+pub struct LocalApi {
+    shared: Arc<Shared>,
+}
+
+impl LocalApi {
+    /// Listens on 127.0.0.1 at the configured port, unless that is 0.
+    pub fn start(ctx: &egui::Context, config: &Config) -> Option<Self> {
+        let port = config.local_api_port()?;
+
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = match std::net::TcpListener::bind(addr)
+            .and_then(|l| l.set_nonblocking(true).map(|_| l))
+            .and_then(TcpListener::from_std)
+        {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!("Local API disabled, unable to listen on {}: {}", addr, e);
+                return None;
+            }
+        };
+
+        let shared = Arc::new(Shared {
+            port,
+            current: Mutex::new(None),
+            widget: Mutex::new(None),
+            refresh_requested: AtomicBool::new(false),
+            ctx: ctx.clone(),
+        });
+        tokio::spawn(serve(listener, shared.clone()));
+        Some(Self { shared })
+    }
+
+    /// Makes a newly fetched forecast available to clients.
+    pub fn publish(&self, snapshot: &WeatherSnapshot, units: Units, template: Option<&Template>) {
+        // Serializes as `location`, `fetched_at` and `weather`.
+        let json = match serde_json::to_string(snapshot) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("Unable to serialize the forecast for the local API: {}", e);
+                return;
+            }
+        };
+        let csv = summary::to_csv(snapshot, units).unwrap_or_else(|e| {
+            tracing::warn!("Unable to write the forecast as CSV for the local API: {}", e);
+            String::new()
+        });
+        let text = summary::to_text(snapshot, units, template);
+        *self.shared.current.lock().unwrap() = Some(Current { json, text, csv });
+        *self.shared.widget.lock().unwrap() = Some(widget_body(snapshot, units).to_string());
+    }
+
+    /// Returns whether a client asked for a refresh since the last call.
+    pub fn take_refresh_request(&self) -> bool {
+        self.shared.refresh_requested.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Compact, versioned view for desktop panel widgets (KDE Plasma, GNOME Shell).
+fn widget_body(snapshot: &WeatherSnapshot, units: Units) -> serde_json::Value {
+    let weather = &snapshot.weather;
+    json!({
+        "version": WIDGET_SCHEMA_VERSION,
+        "text": units.format_temperature(weather.current.temp, 0),
+        "icon": glyphs::snapshot_icon(snapshot),
+        "tooltip": current_conditions::tooltip(snapshot, units),
+        "alerts": weather.alerts.len(),
+        "updated_at": snapshot.fetched_at,
+    })
+}
+
+async fn serve(listener: TcpListener, shared: Arc<Shared>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                tracing::warn!("Local API unable to accept a connection: {}", e);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, &shared).await {
+                tracing::warn!("Local API request failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(mut stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    let (request_line, headers) = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "request not received in time"))??;
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let requested = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("format="))
+        .map(|name| Format::from_name(name).ok_or(name))
+        .or_else(|| headers.accept.as_deref().map(|accept| Format::from_accept(accept).ok_or(accept)));
+    let mut format = Format::Json;
+    let (status, body) = if let Some(reason) = refuse(method, &headers, shared.port) {
+        tracing::warn!("Local API refused {} {}: {}", method, path, reason);
+        ("403 Forbidden", json!({ "error": reason }).to_string())
+    } else {
+        match (method, path) {
+            ("GET", "/current") => match requested {
+                Some(Err(unsupported)) => (
+                    "406 Not Acceptable",
+                    json!({ "error": format!("unsupported format {}", unsupported), "supported": ["application/json", "text/plain", "text/csv"] })
+                        .to_string(),
+                ),
+                _ => match shared.current.lock().unwrap().as_ref() {
+                    Some(current) => {
+                        format = requested.and_then(Result::ok).unwrap_or(Format::Json);
+                        ("200 OK", current.body(format).to_string())
+                    }
+                    None => ("503 Service Unavailable", json!({ "error": "no weather data yet" }).to_string()),
+                },
+            },
+            ("GET", "/widget") => match shared.widget.lock().unwrap().clone() {
+                Some(body) => ("200 OK", body),
+                None => ("503 Service Unavailable", json!({ "error": "no weather data yet" }).to_string()),
+            },
+            ("POST", "/refresh") => {
+                shared.refresh_requested.store(true, Ordering::Relaxed);
+                // The refresh itself starts on the next frame.
+                shared.ctx.request_repaint();
+                ("202 Accepted", json!({ "status": "refreshing" }).to_string())
+            }
+            (_, "/current") | (_, "/widget") | (_, "/refresh") => {
+                ("405 Method Not Allowed", json!({ "error": "method not allowed" }).to_string())
+            }
+            _ => ("404 Not Found", json!({ "error": "not found" }).to_string()),
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nVary: Accept\r\nConnection: close\r\n\r\n{}",
+        status,
+        format.content_type(),
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// The request line and the headers the endpoints look at. No endpoint
+/// takes a request body, so reading stops at the blank line.
+async fn read_request<R: AsyncRead + Unpin>(stream: R) -> std::io::Result<(String, Headers)> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut headers = Headers::default();
+    let finished = loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            break false;
+        }
+        if line.trim().is_empty() {
+            break true;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = Some(value.trim().to_string());
+            match name.trim().to_ascii_lowercase().as_str() {
+                "accept" => headers.accept = value,
+                "host" => headers.host = value,
+                "origin" => headers.origin = value,
+                REFRESH_HEADER => headers.refresh_header = true,
+                _ => {}
+            }
+        }
+    };
+    if !finished && reader.get_ref().limit() == 0 {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "request headers too long"));
+    }
+    Ok((request_line, headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORT: u16 = 8787;
+
+    fn headers(host: &str) -> Headers {
+        Headers { host: Some(host.to_string()), ..Headers::default() }
+    }
+
+    #[test]
+    fn accept_header_is_read_in_the_clients_order() {
+        assert_eq!(Format::from_accept("text/csv;q=0.5, application/json"), Some(Format::Csv));
+        assert_eq!(Format::from_accept("image/png, TEXT/PLAIN"), Some(Format::Text));
+        assert_eq!(Format::from_accept("text/html,*/*;q=0.8"), Some(Format::Json));
+        assert_eq!(Format::from_accept("application/*"), Some(Format::Json));
+        assert_eq!(Format::from_accept("image/png"), None);
+        assert_eq!(Format::from_accept(""), None);
+    }
+
+    #[test]
+    fn only_local_hosts_are_served() {
+        assert_eq!(refuse("GET", &headers("127.0.0.1:8787"), PORT), None);
+        assert_eq!(refuse("GET", &headers("LOCALHOST:8787"), PORT), None);
+        // A rebound name, the wrong port, or no Host at all.
+        assert!(refuse("GET", &headers("evil.example:8787"), PORT).is_some());
+        assert!(refuse("GET", &headers("127.0.0.1:80"), PORT).is_some());
+        assert!(refuse("GET", &Headers::default(), PORT).is_some());
+    }
+
+    #[test]
+    fn posts_need_the_header_and_no_origin() {
+        let mut post = headers("localhost:8787");
+        assert!(refuse("POST", &post, PORT).is_some());
+        post.refresh_header = true;
+        assert_eq!(refuse("POST", &post, PORT), None);
+        post.origin = Some("https://evil.example".to_string());
+        assert!(refuse("POST", &post, PORT).is_some());
+        // GETs only read, so where they come from doesn't matter.
+        let mut get = headers("localhost:8787");
+        get.origin = Some("https://evil.example".to_string());
+        assert_eq!(refuse("GET", &get, PORT), None);
+    }
+
+    #[tokio::test]
+    async fn reads_the_headers_it_needs() {
+        let request = b"POST /refresh HTTP/1.1\r\nHost: 127.0.0.1:7979\r\nX-Weather-Alerts: 1\r\n\r\n";
+        let (request_line, headers) = read_request(&request[..]).await.unwrap();
+        assert_eq!(request_line.trim_end(), "POST /refresh HTTP/1.1");
+        assert_eq!(headers.host.as_deref(), Some("127.0.0.1:7979"));
+        assert!(headers.refresh_header && headers.origin.is_none());
+    }
+
+    #[tokio::test]
+    async fn endless_headers_are_cut_off() {
+        let mut request = b"GET /current HTTP/1.1\r\n".to_vec();
+        request.extend(b"X-Padding: aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\r\n".repeat(1000));
+        request.extend(b"\r\n");
+        let result = read_request(&request[..]).await;
+        assert!(matches!(result, Err(e) if e.kind() == std::io::ErrorKind::InvalidData));
+    }
+}
@@ -0,0 +1,175 @@
+//! Reads saved locations in bulk from CSV, GPX waypoints or KML placemarks.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use crate::locations::SavedLocation;
+
+/// What a file yielded, and how many of its entries couldn't be read.
+pub struct Imported {
+    pub locations: Vec<SavedLocation>,
+    pub skipped: usize,
+}
+
+/// Picks the format from the extension, falling back to the contents for
+/// files without one.
+pub fn import_file(path: &Path) -> Result<Imported, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    let imported = match extension.as_str() {
+        "gpx" | "kml" => from_xml(&text)?,
+        "csv" | "txt" => from_csv(&text)?,
+        _ if text.trim_start().starts_with('<') => from_xml(&text)?,
+        _ => from_csv(&text)?,
+    };
+    if imported.locations.is_empty() {
+        return Err(format!("No locations found in {}", path.display()).into());
+    }
+    Ok(imported)
+}
+
+fn location(name: &str, lat: f64, lon: f64) -> Option<SavedLocation> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return None;
+    }
+    // Unnamed points get their coordinates as a name.
+    let name = match name.trim() {
+        "" => format!("{:.4}, {:.4}", lat, lon),
+        name => name.to_string(),
+    };
+    Some(SavedLocation { city: name.clone(), name, country_code: String::new(), coordinates: Some((lat, lon)) })
+}
+
+/// Columns are found by header (`name`, `lat`/`latitude`, `lon`/`lng`/
+/// `longitude`); without a recognisable header they are taken to be name,
+/// latitude, longitude in that order.
+fn from_csv(text: &str) -> Result<Imported, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().has_headers(false).flexible(true).trim(csv::Trim::All).from_reader(text.as_bytes());
+    let mut records = reader.records();
+    let Some(first) = records.next().transpose()? else {
+        return Ok(Imported { locations: Vec::new(), skipped: 0 });
+    };
+    let column = |names: &[&str]| first.iter().position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)));
+    let header = (
+        column(&["name", "title", "site", "label"]),
+        column(&["lat", "latitude"]),
+        column(&["lon", "lng", "long", "longitude"]),
+    );
+    let (columns, first) = match header {
+        (name, Some(lat), Some(lon)) => ((name, lat, lon), None),
+        _ => ((Some(0), 1, 2), Some(first)),
+    };
+
+    let mut imported = Imported { locations: Vec::new(), skipped: 0 };
+    for record in first.into_iter().map(Ok).chain(records) {
+        let Ok(record) = record else {
+            imported.skipped += 1;
+            continue;
+        };
+        let (name, lat, lon) = columns;
+        let parsed = (|| {
+            let lat = record.get(lat)?.parse().ok()?;
+            let lon = record.get(lon)?.parse().ok()?;
+            location(name.and_then(|i| record.get(i)).unwrap_or_default(), lat, lon)
+        })();
+        match parsed {
+            Some(location) => imported.locations.push(location),
+            None if record.iter().all(str::is_empty) => {}
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+/// GPX `<wpt lat=".." lon="..">` and KML `<Placemark>` points; tracks,
+/// routes and polygons are ignored.
+fn from_xml(text: &str) -> Result<Imported, Box<dyn Error>> {
+    let document = roxmltree::Document::parse(text)?;
+    let child_text = |node: roxmltree::Node, tag: &str| {
+        node.children().find(|c| c.tag_name().name() == tag).and_then(|c| c.text()).unwrap_or_default().to_string()
+    };
+
+    let mut imported = Imported { locations: Vec::new(), skipped: 0 };
+    for node in document.descendants().filter(|n| n.is_element()) {
+        let parsed = match node.tag_name().name() {
+            "wpt" => (|| {
+                let lat = node.attribute("lat")?.trim().parse().ok()?;
+                let lon = node.attribute("lon")?.trim().parse().ok()?;
+                location(&child_text(node, "name"), lat, lon)
+            })(),
+            "Placemark" => {
+                let Some(point) = node.descendants().find(|n| n.tag_name().name() == "Point") else { continue };
+                // KML puts longitude first: "lon,lat[,alt]".
+                (|| {
+                    let coordinates = child_text(point, "coordinates");
+                    let mut parts = coordinates.trim().split(',');
+                    let lon = parts.next()?.trim().parse().ok()?;
+                    let lat = parts.next()?.trim().parse().ok()?;
+                    location(&child_text(node, "name"), lat, lon)
+                })()
+            }
+            _ => continue,
+        };
+        match parsed {
+            Some(location) => imported.locations.push(location),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(imported: &Imported) -> Vec<(&str, Option<(f64, f64)>)> {
+        imported.locations.iter().map(|l| (l.name.as_str(), l.coordinates)).collect()
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_header() {
+        let imported = from_csv("Longitude, Latitude, Site\n-74.01, 40.71, New York\n2.35,48.86,Paris\n").unwrap();
+        assert_eq!(names(&imported), [("New York", Some((40.71, -74.01))), ("Paris", Some((48.86, 2.35)))]);
+        assert_eq!(imported.skipped, 0);
+    }
+
+    #[test]
+    fn csv_without_header_is_name_lat_lon() {
+        let imported = from_csv("Home,51.5,-0.12\n\n,10,20\n").unwrap();
+        assert_eq!(names(&imported), [("Home", Some((51.5, -0.12))), ("10.0000, 20.0000", Some((10.0, 20.0)))]);
+    }
+
+    #[test]
+    fn bad_csv_rows_are_counted() {
+        let imported = from_csv("name,lat,lon\nOk,1,2\nOff the map,91,0\nNo numbers,north,east\n,,\nShort,1\n").unwrap();
+        assert_eq!(names(&imported), [("Ok", Some((1.0, 2.0)))]);
+        // The blank row isn't counted.
+        assert_eq!(imported.skipped, 3);
+        assert!(from_csv("").unwrap().locations.is_empty());
+    }
+
+    #[test]
+    fn gpx_waypoints_are_read() {
+        let gpx = r#"<?xml version="1.0"?>
+            <gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1">
+              <wpt lat="46.55" lon="7.98"><name>Trailhead</name></wpt>
+              <wpt lat="bad" lon="7.9"><name>Broken</name></wpt>
+              <trk><trkseg><trkpt lat="1" lon="1"/></trkseg></trk>
+            </gpx>"#;
+        let imported = from_xml(gpx).unwrap();
+        assert_eq!(names(&imported), [("Trailhead", Some((46.55, 7.98)))]);
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[test]
+    fn kml_points_are_longitude_first() {
+        let kml = r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+              <Placemark><name>Summit</name><Point><coordinates> -105.6,40.25,4345 </coordinates></Point></Placemark>
+              <Placemark><name>Area</name><Polygon/></Placemark>
+            </Document></kml>"#;
+        let imported = from_xml(kml).unwrap();
+        assert_eq!(names(&imported), [("Summit", Some((40.25, -105.6)))]);
+        assert_eq!(imported.skipped, 0);
+        assert!(from_xml("<gpx><wpt").is_err());
+    }
+}
@@ -0,0 +1,348 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::fetch::Fetch;
+use crate::i18n;
+use crate::location_import;
+use crate::platform;
+use crate::provider::{PlaceMatch, Provider, WeatherProvider};
+use crate::Place;
+
+const SEARCH_LIMIT: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedLocation {
+    /// What the switcher shows, e.g. "Home".
+    pub name: String,
+    pub city: String,
+    #[serde(default)]
+    pub country_code: String,
+    // Set for locations picked from search results, so the exact match is
+    // kept rather than re-geocoding an ambiguous name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<(f64, f64)>,
+}
+
+async fn search_places(provider: Provider, query: String) -> Result<Vec<PlaceMatch>, WeatherError> {
+    provider.search(&query, SEARCH_LIMIT).await
+}
+
+/// Everything about saved locations that is persisted (and can be undone).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Store {
+    locations: Vec<SavedLocation>,
+    // Index into `locations`; `None` means the IP-detected location.
+    selected: Option<usize>,
+}
+
+/// The user's saved locations, persisted as JSON in the config directory, and
+/// the window for managing them.
+pub struct Locations {
+    store: Store,
+    pub manager_open: bool,
+    new_name: String,
+    new_city: String,
+    new_country: String,
+    query: String,
+    results: Option<Fetch<Vec<PlaceMatch>>>,
+    import_path: String,
+    // Outcome of the last import.
+    import_status: Option<String>,
+}
+
+fn store_path() -> Option<PathBuf> {
+    platform::config_dir().map(|dir| dir.join("locations.json"))
+}
+
+impl Locations {
+    pub fn load() -> Self {
+        let store = store_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            store,
+            manager_open: false,
+            new_name: String::new(),
+            new_city: String::new(),
+            new_country: String::new(),
+            query: String::new(),
+            results: None,
+            import_path: String::new(),
+            import_status: None,
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = store_path() else { return };
+        let result = serde_json::to_string_pretty(&self.store)
+            .map_err(std::io::Error::from)
+            .and_then(|text| {
+                path.parent().map_or(Ok(()), fs::create_dir_all)?;
+                fs::write(&path, text)
+            });
+        if let Err(e) = result {
+            tracing::warn!("Unable to save locations to {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Replaces the saved locations, e.g. when undoing. Returns whether the
+    /// selected location changed.
+    pub fn restore(&mut self, store: Store) -> bool {
+        let previous = self.selected();
+        self.store = store;
+        self.save();
+        self.selected() != previous
+    }
+
+    /// Place to fetch, or `None` to detect the location from the IP address.
+    pub fn selected(&self) -> Option<Place> {
+        let location = self.store.locations.get(self.store.selected?)?;
+        Some(Place {
+            city: location.city.clone(),
+            country_code: location.country_code.clone(),
+            coordinates: location.coordinates,
+        })
+    }
+
+    pub fn saved(&self) -> &[SavedLocation] {
+        &self.store.locations
+    }
+
+    /// Names in switcher order, starting with the IP-detected location, and
+    /// the position of the selected one.
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    pub fn names(&self) -> (Vec<String>, usize) {
+        let names = std::iter::once(None)
+            .chain((0..self.store.locations.len()).map(Some))
+            .map(|index| self.label(index).to_string())
+            .collect();
+        (names, self.store.selected.map_or(0, |i| i + 1))
+    }
+
+    /// Switches to a saved location, or to the IP-detected one with `None`.
+    /// Returns whether that changed anything.
+    pub fn select(&mut self, selected: Option<usize>) -> bool {
+        if self.store.selected == selected {
+            return false;
+        }
+        self.store.selected = selected;
+        self.save();
+        true
+    }
+
+    /// Moves to the next location in switcher order, wrapping round to the
+    /// IP-detected one. Returns false when nothing is saved.
+    pub fn cycle(&mut self) -> bool {
+        let next = match self.store.selected {
+            None => 0,
+            Some(i) => i + 1,
+        };
+        self.select(Some(next).filter(|&i| i < self.store.locations.len()))
+    }
+
+    fn label(&self, index: Option<usize>) -> &str {
+        match index.and_then(|i| self.store.locations.get(i)) {
+            Some(location) => &location.name,
+            None => i18n::tr("Current location"),
+        }
+    }
+
+    /// Dropdown for switching locations. Returns whether the selection changed.
+    pub fn show_switcher(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut selected = self.store.selected;
+        egui::ComboBox::from_id_source("location_switcher")
+            .selected_text(self.label(selected))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, self.label(None));
+                for (i, location) in self.store.locations.iter().enumerate() {
+                    ui.selectable_value(&mut selected, Some(i), &location.name);
+                }
+            });
+        if ui.small_button(i18n::tr("Edit")).on_hover_text("Manage saved locations").clicked() {
+            self.manager_open = true;
+        }
+        self.select(selected)
+    }
+
+    /// Appends the locations in `path`, leaving out ones already saved at
+    /// the same coordinates.
+    fn import(&mut self, path: &Path) {
+        let before = self.store.locations.len();
+        match location_import::import_file(path) {
+            Ok(imported) => {
+                let total = imported.locations.len();
+                for location in imported.locations {
+                    if !self.store.locations.iter().any(|saved| saved.coordinates == location.coordinates) {
+                        self.store.locations.push(location);
+                    }
+                }
+                let mut status = format!("Imported {} of {} locations", self.store.locations.len() - before, total);
+                if imported.skipped > 0 {
+                    status.push_str(&format!("; {} entries couldn't be read", imported.skipped));
+                }
+                self.import_status = Some(status);
+                self.import_path.clear();
+            }
+            Err(e) => self.import_status = Some(format!("Import failed: {}", e)),
+        }
+    }
+
+    /// Window for adding, removing and reordering locations. Returns whether
+    /// the selected location changed as a result.
+    pub fn show_manager(&mut self, ctx: &egui::Context, config: &Config) -> bool {
+        let mut open = self.manager_open;
+        let mut edit = None;
+        egui::Window::new("Saved locations").open(&mut open).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.query).hint_text("Search for a city"));
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let can_search = !self.query.trim().is_empty();
+                if ui.add_enabled(can_search, egui::Button::new("Search")).clicked() || (submitted && can_search) {
+                    let (provider, query) = (Provider::from_config(config), self.query.trim().to_string());
+                    self.results = Some(Fetch::spawn(ui.ctx(), async move { search_places(provider?, query).await }));
+                }
+            });
+            if let Some(results) = &mut self.results {
+                results.poll();
+                match results {
+                    Fetch::Pending(_) => {
+                        ui.spinner();
+                    }
+                    Fetch::Failed(e) => {
+                        ui.label(format!("Search failed: {}", e));
+                    }
+                    Fetch::Ready(candidates) if candidates.is_empty() => {
+                        ui.label("No matches");
+                    }
+                    Fetch::Ready(candidates) => {
+                        for candidate in candidates.iter() {
+                            ui.horizontal(|ui| {
+                                ui.label(candidate.describe());
+                                if ui.small_button("Pin").on_hover_text("Save and switch to this location").clicked() {
+                                    edit = Some(Edit::Pin(candidate.clone()));
+                                }
+                            });
+                        }
+                    }
+                }
+            }
+            ui.separator();
+
+            let count = self.store.locations.len();
+            egui::Grid::new("saved_locations").striped(true).show(ui, |ui| {
+                for (i, location) in self.store.locations.iter().enumerate() {
+                    ui.label(&location.name);
+                    if location.country_code.is_empty() {
+                        ui.label(&location.city);
+                    } else {
+                        ui.label(format!("{}, {}", location.city, location.country_code));
+                    }
+                    if ui.add_enabled(i > 0, egui::Button::new("⏶").small()).clicked() {
+                        edit = Some(Edit::Swap(i - 1, i));
+                    }
+                    if ui.add_enabled(i + 1 < count, egui::Button::new("⏷").small()).clicked() {
+                        edit = Some(Edit::Swap(i, i + 1));
+                    }
+                    if ui.small_button("Remove").clicked() {
+                        edit = Some(Edit::Remove(i));
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.new_name).hint_text("Name").desired_width(80.0));
+                ui.add(egui::TextEdit::singleline(&mut self.new_city).hint_text("City").desired_width(120.0));
+                ui.add(egui::TextEdit::singleline(&mut self.new_country).hint_text("Country").desired_width(60.0));
+                let can_add = !self.new_city.trim().is_empty();
+                if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                    edit = Some(Edit::Add);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.import_path)
+                        .hint_text("CSV, GPX or KML file")
+                        .desired_width(200.0),
+                )
+                .on_hover_text("Or drop the file on this window");
+                let can_import = !self.import_path.trim().is_empty();
+                if ui.add_enabled(can_import, egui::Button::new("Import")).clicked() {
+                    edit = Some(Edit::Import(PathBuf::from(self.import_path.trim())));
+                }
+            });
+            if let Some(ref status) = self.import_status {
+                ui.small(status);
+            }
+            if let Some(path) = ui.input(|i| i.raw.dropped_files.iter().find_map(|f| f.path.clone())) {
+                edit = Some(Edit::Import(path));
+            }
+        });
+        self.manager_open = open;
+
+        let previous = self.selected();
+        match edit {
+            Some(Edit::Swap(a, b)) => {
+                self.store.locations.swap(a, b);
+                // Keep the same place selected, wherever it moved to.
+                self.store.selected = self.store.selected.map(|s| if s == a { b } else if s == b { a } else { s });
+            }
+            Some(Edit::Remove(i)) => {
+                self.store.locations.remove(i);
+                self.store.selected = match self.store.selected {
+                    Some(s) if s == i => None,
+                    Some(s) if s > i => Some(s - 1),
+                    selected => selected,
+                };
+            }
+            Some(Edit::Add) => {
+                let city = self.new_city.trim().to_string();
+                let name = match self.new_name.trim() {
+                    "" => city.clone(),
+                    name => name.to_string(),
+                };
+                self.store.locations.push(SavedLocation {
+                    name,
+                    city,
+                    country_code: self.new_country.trim().to_uppercase(),
+                    coordinates: None,
+                });
+                self.new_name.clear();
+                self.new_city.clear();
+                self.new_country.clear();
+            }
+            Some(Edit::Pin(candidate)) => {
+                self.store.locations.push(SavedLocation {
+                    name: candidate.describe(),
+                    city: candidate.name,
+                    country_code: candidate.country_code,
+                    coordinates: Some((candidate.lat, candidate.lon)),
+                });
+                self.store.selected = Some(self.store.locations.len() - 1);
+                self.results = None;
+                self.query.clear();
+            }
+            Some(Edit::Import(path)) => self.import(&path),
+            None => return false,
+        }
+        self.save();
+        self.selected() != previous
+    }
+}
+
+enum Edit {
+    Swap(usize, usize),
+    Remove(usize),
+    Add,
+    Pin(PlaceMatch),
+    Import(PathBuf),
+}
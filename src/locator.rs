@@ -0,0 +1,175 @@
+//! Works out where the user is, trying the most precise source first: the
+//! operating system's location services, then IP geolocation, then wherever
+//! the last forecast was for.
+
+use std::time::Duration;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::recording;
+use crate::{platform, Location};
+
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+const REVERSE_GEOCODE_URL: &str = "https://api.openweathermap.org/geo/1.0/reverse";
+
+/// Where a detected location came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LocationSource {
+    /// Core Location, Windows Geolocation or GeoClue.
+    System,
+    IpApi,
+    IpWho,
+    /// Every lookup failed, so the previous location was kept.
+    LastKnown,
+}
+
+impl LocationSource {
+    #[cfg(feature = "gui")]
+    pub fn describe(self) -> &'static str {
+        match self {
+            LocationSource::System => "location services",
+            LocationSource::IpApi => "IP address (ip-api.com)",
+            LocationSource::IpWho => "IP address (ipwho.is)",
+            LocationSource::LastKnown => "last known location",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub city: String,
+    pub country_code: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub source: LocationSource,
+    /// Metres; `None` when the source doesn't say, as with IP lookups.
+    pub accuracy_m: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IpApiResponse {
+    status: String,
+    #[serde(default)]
+    city: String,
+    #[serde(default)]
+    country_code: String,
+    #[serde(default)]
+    lat: f64,
+    #[serde(default)]
+    lon: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IpWhoResponse {
+    success: bool,
+    #[serde(default)]
+    city: String,
+    #[serde(default)]
+    country_code: String,
+    #[serde(default)]
+    latitude: f64,
+    #[serde(default)]
+    longitude: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReverseGeocode {
+    name: String,
+    #[serde(default)]
+    country: String,
+}
+
+/// The first source that answers, or `last_known` when none do.
+#[tracing::instrument(name = "locate", skip_all)]
+pub async fn locate(config: &Config, last_known: Option<&Location>) -> Result<Fix, WeatherError> {
+    let client = Client::builder().timeout(LOOKUP_TIMEOUT).build()?;
+
+    if config.location_services() && !recording::active() {
+        if let Some(fix) = system_fix(&client, config.api_key()).await {
+            return Ok(fix);
+        }
+    }
+    // The free ip-api.com endpoint is HTTP only, so it is only asked with a
+    // key for the HTTPS one.
+    if let Some(key) = config.ip_api_key() {
+        match ip_api(&client, &key).await {
+            Ok(fix) => return Ok(fix),
+            Err(e) => tracing::warn!("ip-api.com lookup failed: {}", e),
+        }
+    }
+    match ipwho(&client).await {
+        Ok(fix) => return Ok(fix),
+        Err(e) => tracing::warn!("ipwho.is lookup failed: {}", e),
+    }
+    let last_known = last_known.ok_or(WeatherError::GeolocationFailed)?;
+    Ok(Fix {
+        city: last_known.city.clone(),
+        country_code: last_known.country_code.clone(),
+        lat: last_known.lat,
+        lon: last_known.lon,
+        source: LocationSource::LastKnown,
+        accuracy_m: None,
+    })
+}
+
+// The OS only knows coordinates. They are named with OpenWeatherMap's
+// reverse geocoding when there is a key and left unnamed otherwise, so they
+// go nowhere the forecast request doesn't; a failed lookup still leaves a
+// usable fix.
+async fn system_fix(client: &Client, api_key: Option<String>) -> Option<Fix> {
+    let (lat, lon, accuracy_m) = tokio::task::spawn_blocking(platform::system_location).await.ok()??;
+    let place = match api_key {
+        Some(key) => reverse_geocode(client, lat, lon, &key).await,
+        None => None,
+    };
+    let (city, country_code) = match place {
+        Some(place) if !place.name.is_empty() => (place.name, place.country),
+        _ => ("Current location".to_string(), String::new()),
+    };
+    Some(Fix { city, country_code, lat, lon, source: LocationSource::System, accuracy_m })
+}
+
+async fn reverse_geocode(client: &Client, lat: f64, lon: f64, api_key: &str) -> Option<ReverseGeocode> {
+    let request = client.get(REVERSE_GEOCODE_URL).query(&[
+        ("lat", lat.to_string()),
+        ("lon", lon.to_string()),
+        ("limit", "1".to_string()),
+        ("appid", api_key.to_string()),
+    ]);
+    let body = recording::send("openweathermap-reverse", request).await.ok()?;
+    serde_json::from_str::<Vec<ReverseGeocode>>(&body).ok()?.into_iter().next()
+}
+
+async fn ip_api(client: &Client, key: &str) -> Result<Fix, WeatherError> {
+    let url = format!("https://pro.ip-api.com/json/?key={}", key.trim());
+    let response: IpApiResponse = serde_json::from_str(&recording::send("ip-api", client.get(&url)).await?)?;
+    if response.status != "success" {
+        return Err(WeatherError::GeolocationFailed);
+    }
+    Ok(Fix {
+        city: response.city,
+        country_code: response.country_code,
+        lat: response.lat,
+        lon: response.lon,
+        source: LocationSource::IpApi,
+        accuracy_m: None,
+    })
+}
+
+async fn ipwho(client: &Client) -> Result<Fix, WeatherError> {
+    let response: IpWhoResponse = serde_json::from_str(&recording::send("ipwho", client.get("https://ipwho.is/")).await?)?;
+    if !response.success {
+        return Err(WeatherError::GeolocationFailed);
+    }
+    Ok(Fix {
+        city: response.city,
+        country_code: response.country_code,
+        lat: response.latitude,
+        lon: response.longitude,
+        source: LocationSource::IpWho,
+        accuracy_m: None,
+    })
+}
@@ -0,0 +1,75 @@
+use eframe::egui;
+use tracing::Level;
+use crate::logging::{self, Entry};
+
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+/// The latest log entries, for when something went wrong and there is no
+/// terminal to read stderr from.
+pub struct LogWindow {
+    pub open: bool,
+    // The least severe level shown.
+    level: Level,
+    search: String,
+}
+
+impl LogWindow {
+    pub fn new() -> Self {
+        Self { open: false, level: Level::INFO, search: String::new() }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("Logs").open(&mut open).default_width(560.0).show(ctx, |ui| {
+            let search = self.search.to_lowercase();
+            let entries: Vec<Entry> = logging::recent()
+                .into_iter()
+                .filter(|entry| entry.level <= self.level)
+                .filter(|entry| search.is_empty() || entry.message.to_lowercase().contains(&search))
+                .collect();
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("log_level")
+                    .selected_text(self.level.as_str())
+                    .show_ui(ui, |ui| {
+                        for level in LEVELS {
+                            ui.selectable_value(&mut self.level, level, level.as_str());
+                        }
+                    });
+                ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search").desired_width(160.0));
+                if ui.button("Copy").on_hover_text("Copy the entries shown").clicked() {
+                    let text: Vec<String> = entries.iter().map(format_entry).collect();
+                    ui.output_mut(|o| o.copied_text = text.join("\n"));
+                }
+            });
+            if let Some(dir) = logging::log_dir() {
+                ui.small(format!("Full logs, as JSON lines: {}", dir.display()));
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().stick_to_bottom(true).auto_shrink([false, false]).show(ui, |ui| {
+                if entries.is_empty() {
+                    ui.label("Nothing logged yet.");
+                }
+                for entry in &entries {
+                    let text = egui::RichText::new(format_entry(entry)).monospace();
+                    match entry.level {
+                        Level::ERROR => ui.colored_label(ui.visuals().error_fg_color, text),
+                        Level::WARN => ui.colored_label(ui.visuals().warn_fg_color, text),
+                        _ => ui.label(text),
+                    };
+                }
+            });
+        });
+        self.open = open;
+        if self.open {
+            // Entries logged from other threads don't wake the window.
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+    }
+}
+
+fn format_entry(entry: &Entry) -> String {
+    let spans = if entry.spans.is_empty() { String::new() } else { format!("{}: ", entry.spans) };
+    format!("{} {:>5} {}{}", entry.at.format("%H:%M:%S"), entry.level, spans, entry.message)
+}
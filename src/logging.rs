@@ -0,0 +1,303 @@
+//! Structured logs. `tracing` events and spans are written as JSON lines to
+//! a file per day in the platform log directory, and the last week of files
+//! is kept. Warnings and errors also go to stderr, as does everything else
+//! with `--verbose`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(feature = "gui")]
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+#[cfg(feature = "gui")]
+use chrono::DateTime;
+use chrono::{Local, NaiveDate, SecondsFormat};
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+use crate::platform;
+
+const FILE_PREFIX: &str = "weather_alerts.";
+const KEEP_FILES: usize = 7;
+// Kept in memory for the log window.
+#[cfg(feature = "gui")]
+const RECENT_ENTRIES: usize = 500;
+// Libraries only get a say at this level and above; their debug output
+// would drown ours.
+const LIBRARY_LEVEL: Level = Level::WARN;
+
+/// One line of the log as the log window shows it.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub at: DateTime<Local>,
+    pub level: Level,
+    // Enclosing spans, outermost first, e.g. "fetch › geocode".
+    pub spans: String,
+    pub message: String,
+}
+
+#[cfg(feature = "gui")]
+static RECENT: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    // Spans entered on this thread, innermost last.
+    static STACK: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+struct SpanData {
+    name: &'static str,
+    target: &'static str,
+    level: Level,
+    fields: Map<String, Value>,
+    started: Instant,
+    // Handles to the span; it closes when the last one is dropped.
+    refs: usize,
+}
+
+struct DailyFile {
+    dir: PathBuf,
+    date: NaiveDate,
+    file: File,
+}
+
+impl DailyFile {
+    fn open(dir: PathBuf, date: NaiveDate) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}{}.log", FILE_PREFIX, date.format("%Y-%m-%d")));
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        prune(&dir);
+        Ok(Self { dir, date, file })
+    }
+
+    fn write(&mut self, line: &str) {
+        let today = Local::now().date_naive();
+        if today != self.date {
+            match Self::open(self.dir.clone(), today) {
+                Ok(next) => *self = next,
+                Err(e) => eprintln!("Unable to start a new log file in {}: {}", self.dir.display(), e),
+            }
+        }
+        let _ = writeln!(self.file, "{}", line);
+    }
+}
+
+/// Deletes all but the newest `KEEP_FILES` logs; the date in the name sorts.
+fn prune(dir: &PathBuf) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(FILE_PREFIX)))
+        .collect();
+    logs.sort();
+    let excess = logs.len().saturating_sub(KEEP_FILES);
+    for path in logs.into_iter().take(excess) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+struct JsonLog {
+    max_level: Level,
+    verbose: bool,
+    file: Option<Mutex<DailyFile>>,
+    spans: Mutex<HashMap<u64, SpanData>>,
+    next_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct Fields(Map<String, Value>);
+
+impl Visit for Fields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+impl JsonLog {
+    fn span_names(&self) -> Vec<&'static str> {
+        let spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+        STACK.with(|stack| stack.borrow().iter().filter_map(|id| spans.get(id).map(|span| span.name)).collect())
+    }
+
+    /// Writes one line everywhere it should go.
+    fn emit(&self, level: Level, target: &str, spans: Vec<&'static str>, message: String, fields: Map<String, Value>) {
+        let at = Local::now();
+        let mut line = Map::new();
+        line.insert("timestamp".into(), at.to_rfc3339_opts(SecondsFormat::Millis, false).into());
+        line.insert("level".into(), level.as_str().into());
+        line.insert("target".into(), target.into());
+        if !spans.is_empty() {
+            line.insert("spans".into(), spans.iter().map(|&s| Value::from(s)).collect::<Vec<_>>().into());
+        }
+        line.insert("message".into(), message.clone().into());
+        let details: Vec<String> = fields.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        line.extend(fields);
+        if let Some(ref file) = self.file {
+            file.lock().unwrap_or_else(|e| e.into_inner()).write(&Value::Object(line).to_string());
+        }
+
+        let message = if details.is_empty() { message } else { format!("{} {}", message, details.join(" ")) };
+        if self.verbose {
+            eprintln!("{:>5} {}: {}", level, target, message);
+        } else if level <= Level::WARN {
+            eprintln!("{}", message);
+        }
+        #[cfg(feature = "gui")]
+        {
+            let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+            if recent.len() == RECENT_ENTRIES {
+                recent.pop_front();
+            }
+            recent.push_back(Entry { at, level, spans: spans.join(" › "), message });
+        }
+    }
+}
+
+impl Subscriber for JsonLog {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let max = if metadata.target().starts_with(env!("CARGO_PKG_NAME")) { self.max_level } else { LIBRARY_LEVEL };
+        *metadata.level() <= max
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(self.max_level))
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut fields = Fields::default();
+        attributes.record(&mut fields);
+        let metadata = attributes.metadata();
+        let span = SpanData {
+            name: metadata.name(),
+            target: metadata.target(),
+            level: *metadata.level(),
+            fields: fields.0,
+            started: Instant::now(),
+            refs: 1,
+        };
+        self.spans.lock().unwrap_or_else(|e| e.into_inner()).insert(id, span);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(span) = self.spans.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&span.into_u64()) {
+            let mut fields = Fields(std::mem::take(&mut span.fields));
+            values.record(&mut fields);
+            span.fields = fields.0;
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = match fields.0.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        let metadata = event.metadata();
+        self.emit(*metadata.level(), metadata.target(), self.span_names(), message, fields.0);
+    }
+
+    fn enter(&self, span: &Id) {
+        STACK.with(|stack| stack.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &Id) {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(i) = stack.iter().rposition(|&id| id == span.into_u64()) {
+                stack.remove(i);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(span) = self.spans.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&span.into_u64()) {
+            span.refs += 1;
+        }
+        span.clone()
+    }
+
+    /// Logs how long the span was open, which is what the spans are for.
+    fn try_close(&self, span: Id) -> bool {
+        let closed = {
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(data) = spans.get_mut(&span.into_u64()) else { return false };
+            data.refs -= 1;
+            if data.refs > 0 {
+                return false;
+            }
+            spans.remove(&span.into_u64())
+        };
+        if let Some(mut data) = closed {
+            let elapsed_ms = data.started.elapsed().as_secs_f64() * 1000.0;
+            data.fields.insert("elapsed_ms".into(), ((elapsed_ms * 10.0).round() / 10.0).into());
+            let mut spans = self.span_names();
+            spans.push(data.name);
+            self.emit(data.level, data.target, spans, format!("{} done", data.name), data.fields);
+        }
+        true
+    }
+}
+
+/// Where the log files go: the platform's state directory where it has one,
+/// e.g. `~/.local/state/weather_alerts`, and a `logs` folder next to the
+/// data otherwise.
+pub fn log_dir() -> Option<PathBuf> {
+    platform::state_dir().or_else(|| platform::data_dir().map(|dir| dir.join("logs")))
+}
+
+/// Starts logging: info and up, debug with one `--verbose` and everything
+/// with two.
+pub fn init(verbose: u8) {
+    let max_level = match verbose {
+        0 => Level::INFO,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    let file = log_dir().and_then(|dir| match DailyFile::open(dir.clone(), Local::now().date_naive()) {
+        Ok(file) => Some(Mutex::new(file)),
+        Err(e) => {
+            eprintln!("Logging to stderr only, unable to write to {}: {}", dir.display(), e);
+            None
+        }
+    });
+    let log = JsonLog { max_level, verbose: verbose > 0, file, spans: Mutex::new(HashMap::new()), next_id: AtomicU64::new(0) };
+    let _ = tracing::subscriber::set_global_default(log);
+}
+
+/// The most recent entries, oldest first.
+#[cfg(feature = "gui")]
+pub fn recent() -> Vec<Entry> {
+    RECENT.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
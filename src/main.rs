@@ -1,101 +1,289 @@
-use std::env;
-use serde::Deserialize;
+#[cfg(feature = "air-quality")]
+mod air_quality;
+mod alerts;
+mod beaufort;
+#[cfg(feature = "gui")]
+mod app;
+#[cfg(feature = "gui")]
+mod cache;
+#[cfg(feature = "gui")]
+mod card_image;
+mod cli;
+#[cfg(feature = "gui")]
+mod comparison;
+mod config;
+mod current_conditions;
+#[cfg(feature = "gui")]
+mod daily_panel;
+#[cfg(feature = "gui")]
+mod debug_hud;
+#[cfg(feature = "earthquakes")]
+mod earthquakes;
+mod error;
+mod export;
+#[cfg(feature = "gui")]
+mod fetch;
+#[cfg(feature = "gui")]
+mod forecast_changes;
+mod geo;
+mod geocode_cache;
+#[cfg(feature = "gui")]
+mod glyphs;
+#[cfg(not(feature = "gui"))]
+mod headless;
+#[cfg(feature = "gui")]
+mod history;
+mod i18n;
+#[cfg(feature = "gui")]
+mod hotkeys;
+#[cfg(feature = "gui")]
+mod hourly_panel;
+#[cfg(feature = "gui")]
+mod local_api;
+#[cfg(feature = "gui")]
+mod locations;
+#[cfg(feature = "gui")]
+mod location_import;
+#[cfg(feature = "gui")]
+mod log_window;
+mod logging;
+mod locator;
+mod metrics;
+#[cfg(feature = "gui")]
+mod mini_mode;
+mod minutely;
+#[cfg(feature = "gui")]
+mod notifications;
+#[cfg(feature = "gui")]
+mod nws;
+mod open_meteo;
+#[cfg(feature = "gui")]
+mod panels;
+#[cfg(feature = "gui")]
+mod past_weather_panel;
+#[cfg(feature = "gui")]
+mod personal_station;
+mod platform;
+#[cfg(feature = "gui")]
+mod pressure_panel;
+mod provider;
+#[cfg(feature = "radar")]
+mod radar_tiles;
+mod recording;
+#[cfg(feature = "gui")]
+mod region_grid;
+#[cfg(feature = "river-gauges")]
+mod river_gauges;
+#[cfg(feature = "gui")]
+mod road_conditions;
+#[cfg(feature = "gui")]
+mod rules;
+#[cfg(feature = "scripting")]
+mod scripting;
+#[cfg(feature = "gui")]
+mod session;
+#[cfg(feature = "gui")]
+mod settings_window;
+#[cfg(feature = "spotter-reports")]
+mod spotter_reports;
+#[cfg(feature = "gui")]
+mod station_check;
+mod summary;
+mod sun_exposure;
+mod template;
+mod theme;
+#[cfg(feature = "tides")]
+mod tides;
+mod time_format;
+#[cfg(feature = "gui")]
+mod timemachine;
+#[cfg(feature = "gui")]
+mod trip_planner;
+#[cfg(all(feature = "tray", target_os = "linux"))]
+mod tray;
+mod units;
+#[cfg(feature = "gui")]
+mod watchdog;
+#[cfg(feature = "webcams")]
+mod webcams;
+mod weekend;
+#[cfg(feature = "wildfires")]
+mod wildfires;
+#[cfg(feature = "gui")]
+mod wind_panel;
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("enable a TLS backend with the `native-tls` or `rustls` feature");
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 use reqwest::Client;
-use eframe::{egui, App, Frame};
+use clap::Parser;
+use config::Config;
+use error::WeatherError;
+use locator::{Fix, LocationSource};
 use dotenv::dotenv;
-use serde_json;
-
-struct WeatherApp {
-    weather_data: Option<String>,
-    daily_weather_description: Option<String>,
-    location: Option<String>
-}
-
-impl App for WeatherApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
-        let _ = frame;
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let heading_text = if let (Some(ref location), Some(ref desc)) = (&self.location, &self.daily_weather_description) {
-                format!("Today's weather for {} - {}", location, desc)
-            } else {
-                "Today's Weather".to_string()
-            };
-            ui.heading(heading_text);
-            if let Some(ref data) = self.weather_data {
-                ui.separator();
-                ui.label(data);
-            } else {
-                ui.spinner();
-                ui.label("Fetching weather data...");
-            }
-        });
+use provider::{Provider, WeatherProvider};
+use sun_exposure::SkinType;
+use units::Units;
+
+#[cfg(not(feature = "gui"))]
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+    let args = cli::Args::parse();
+    logging::init(args.verbose);
+    args.start_recording();
+    if args.cli {
+        cli::run(args).await;
+        return Ok(());
     }
+    Ok(headless::run().await?)
 }
 
+#[cfg(feature = "gui")]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
+    let args = cli::Args::parse();
+    logging::init(args.verbose);
+    args.start_recording();
+    if args.cli {
+        cli::run(args).await;
+        return Ok(());
+    }
 
-    // Fetch weather data
-    let (weather_data, daily_weather_description, city) = fetch_weather_data().await?;
-
-    // Create the app instance
-    let app = WeatherApp {
-        weather_data: Some(weather_data),
-        daily_weather_description: Some(daily_weather_description),
-        location: Some(city),
-    };
+    // Don't block the window on the network: show the last fetch, if any,
+    // while the app refreshes in the background.
+    let cached = cache::load();
 
     // Run the GUI application
     let native_options = eframe::NativeOptions::default();
-    let _ = eframe::run_native(
+    let result = eframe::run_native(
         "Weather Alerts",         // Application title
         native_options,           // Native options
-        Box::new(|_cc| Box::new(app)), // App creator closure
+        Box::new(move |cc| {
+            // Create the app instance
+            Box::new(app::WeatherApp::new(&cc.egui_ctx, cached))
+        }), // App creator closure
     );
 
+    if let Err(e) = result {
+        platform::show_error("Weather Alerts", &format!("Unable to open the window: {}", e));
+        return Err(e.into());
+    }
     Ok(())
 }
 
-async fn fetch_weather_data() -> Result<(String, String, String), Box<dyn std::error::Error>> {
-    // Load environment variables (no longer needed for city and country)
-    let api_key = env::var("OPENWEATHERMAP_API_KEY")?;
-
-    // Get user's location
-    let (city, country_code) = get_user_location().await?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Location {
+    city: String,
+    country_code: String,
+    lat: f64,
+    lon: f64,
+    // How the location was found; `None` when the user picked it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<LocationSource>,
+    // Metres, when the source reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accuracy_m: Option<f64>,
+}
 
-    let client = Client::new();
+// Fixes closer than this to the last location count as not having moved.
+const SAME_PLACE_KM: f64 = 1.0;
+
+/// Fetches the forecast for wherever the [`locator`] finds the user.
+///
+/// With a `last_known` location, its forecast is requested while the lookup
+/// runs and is used as-is when the user hasn't moved. Its coordinates are
+/// kept too, so small differences between fixes don't count as moving.
+#[tracing::instrument(name = "fetch", skip_all)]
+async fn fetch_weather_data(config: Config, last_known: Option<Location>) -> Result<WeatherSnapshot, WeatherError> {
+    let provider = Provider::from_config(&config)?;
+    let Some(last_known) = last_known else {
+        return fetch_weather_for(&provider, locator::locate(&config, None).await?).await;
+    };
 
-    // Get coordinates
-    let (lat, lon) = get_coordinates(&client, &city, &country_code, &api_key).await?;
+    let (fix, weather) = tokio::join!(
+        locator::locate(&config, Some(&last_known)),
+        provider.fetch_forecast(last_known.lat, last_known.lon),
+    );
+    let fix = fix?;
+
+    let same_place = fix.source == LocationSource::LastKnown
+        || (fix.city == last_known.city && fix.country_code == last_known.country_code)
+        || geo::distance_km(fix.lat, fix.lon, last_known.lat, last_known.lon) < SAME_PLACE_KM;
+    if same_place {
+        if let Ok(weather) = weather {
+            let location = Location { source: Some(fix.source), accuracy_m: fix.accuracy_m, ..last_known };
+            return Ok(WeatherSnapshot { weather, location, fetched_at: Local::now() });
+        }
+    }
+    fetch_weather_for(&provider, fix).await
+}
 
-    // Get weather data
-    let weather_data = get_weather_data(&client, lat, lon, &api_key).await?;
+async fn fetch_weather_for(provider: &Provider, fix: Fix) -> Result<WeatherSnapshot, WeatherError> {
+    let place = Place { city: fix.city, country_code: fix.country_code, coordinates: Some((fix.lat, fix.lon)) };
+    let mut snapshot = fetch_weather_with(provider, place).await?;
+    snapshot.location.source = Some(fix.source);
+    snapshot.location.accuracy_m = fix.accuracy_m;
+    Ok(snapshot)
+}
 
-    // Format weather data and get daily_weather_description
-    let (weather_string, daily_weather_description) = format_weather_data(&weather_data);
+/// A place to fetch the forecast for. Known coordinates skip geocoding.
+#[derive(Debug, Clone, PartialEq)]
+struct Place {
+    city: String,
+    country_code: String,
+    coordinates: Option<(f64, f64)>,
+}
 
-    Ok((weather_string, daily_weather_description, city))
+#[tracing::instrument(name = "fetch", skip_all, fields(city = %place.city))]
+async fn fetch_weather_at(config: Config, place: Place) -> Result<WeatherSnapshot, WeatherError> {
+    fetch_weather_with(&Provider::from_config(&config)?, place).await
 }
 
-async fn get_user_location() -> Result<(String, String), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
+async fn fetch_weather_with(provider: &Provider, place: Place) -> Result<WeatherSnapshot, WeatherError> {
+    // Get coordinates
+    let (lat, lon) = match place.coordinates {
+        Some(coordinates) => coordinates,
+        None => geocode(provider, &place.city, &place.country_code).await?,
+    };
 
-    // Set a reasonable timeout
-    let res = client.get("http://ip-api.com/json/")
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await?;
+    // Get weather data
+    let weather_data = provider.fetch_forecast(lat, lon).await?;
+
+    let location = Location {
+        city: place.city,
+        country_code: place.country_code,
+        lat,
+        lon,
+        source: None,
+        accuracy_m: None,
+    };
 
-    if res.status().is_success() {
-        let json: serde_json::Value = res.json().await?;
-        let city = json["city"].as_str().unwrap_or("Unknown City").to_string();
-        let country_code = json["countryCode"].as_str().unwrap_or("US").to_string();
+    Ok(WeatherSnapshot {
+        weather: weather_data,
+        location,
+        fetched_at: Local::now(),
+    })
+}
 
-        Ok((city, country_code))
+/// Coordinates for a city, from the geocode cache when it has them.
+#[tracing::instrument(skip(provider))]
+async fn geocode(provider: &Provider, city: &str, country_code: &str) -> Result<(f64, f64), WeatherError> {
+    // The country is optional; providers pick the most prominent match without it.
+    let query = if country_code.is_empty() {
+        city.to_string()
     } else {
-        Err("Failed to get user location".into())
+        format!("{},{}", city, country_code)
+    };
+    if let Some(coordinates) = geocode_cache::lookup(&query) {
+        return Ok(coordinates);
     }
+    let coordinates = provider.geocode(city, country_code).await?;
+    geocode_cache::insert(&query, coordinates);
+    Ok(coordinates)
 }
 
 #[derive(Debug, Deserialize)]
@@ -104,41 +292,139 @@ struct GeoResponse {
     lon: f64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct Weather {
+    #[serde(default)]
+    id: u16,
     description: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct Current {
     temp: f64,
-    feels_like: f64,
+    // Not every provider reports it; see `Current::feels_like`.
+    #[serde(default)]
+    feels_like: Option<f64>,
     humidity: u8,
     wind_speed: f64,
     wind_deg: u16,
+    #[serde(default)]
+    uvi: Option<f64>,
+    // Sea level, hPa.
+    #[serde(default)]
+    pressure: Option<f64>,
+    // °F; see `Current::dew_point`.
+    #[serde(default)]
+    dew_point: Option<f64>,
+    // Metres.
+    #[serde(default)]
+    visibility: Option<f64>,
+    // Unix timestamps; absent in polar day and night.
+    #[serde(default)]
+    sunrise: Option<i64>,
+    #[serde(default)]
+    sunset: Option<i64>,
+    #[serde(default)]
     weather: Vec<Weather>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Current {
+    /// The reported dew point, or one computed from temperature and humidity.
+    fn dew_point(&self) -> f64 {
+        self.dew_point
+            .unwrap_or_else(|| metrics::dew_point(self.temp, f64::from(self.humidity)))
+    }
+
+    /// The reported feels-like temperature, or one computed from temperature,
+    /// humidity and wind when the provider left it out.
+    fn feels_like(&self) -> f64 {
+        self.feels_like.unwrap_or_else(|| {
+            metrics::apparent_temperature(self.temp, f64::from(self.humidity), self.wind_speed)
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct Daily {
+    #[serde(default)]
+    dt: i64,
     #[serde(default)]
     pop: f64,
     #[serde(default)]
     summary: String,
     temp: DailyTemp,
+    #[serde(default)]
     weather: Vec<Weather>,
+    #[serde(default)]
+    humidity: Option<u8>,
+    #[serde(default)]
+    wind_speed: Option<f64>,
+    #[serde(default)]
+    wind_deg: Option<u16>,
+    #[serde(default)]
+    uvi: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+struct Hourly {
+    dt: i64,
+    temp: f64,
+    #[serde(default)]
+    pop: f64,
+    wind_speed: f64,
+    #[serde(default)]
+    wind_gust: Option<f64>,
+    wind_deg: u16,
+    // Sea level, hPa.
+    #[serde(default)]
+    pressure: Option<f64>,
+    #[serde(default)]
+    weather: Vec<Weather>,
+    rain: Option<Precipitation>,
+    snow: Option<Precipitation>,
+}
+
+/// One minute of the next hour; only One Call has these.
+#[derive(Debug, Serialize, Deserialize)]
+struct Minutely {
+    dt: i64,
+    // mm/h, whatever the requested units.
+    #[serde(default)]
+    precipitation: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Precipitation {
+    #[serde(rename = "1h")]
+    one_hour: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct DailyTemp {
     min: f64,
     max: f64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct WeatherResponse {
     current: Current,
+    #[serde(default)]
+    minutely: Vec<Minutely>,
+    #[serde(default)]
+    hourly: Vec<Hourly>,
+    #[serde(default)]
     daily: Vec<Daily>,
+    // Omitted by One Call when nothing is in effect.
+    #[serde(default)]
+    alerts: Vec<alerts::Alert>,
+}
+
+/// One successful fetch: the forecast, where it is for, and when it was made.
+#[derive(Debug, Serialize, Deserialize)]
+struct WeatherSnapshot {
+    weather: WeatherResponse,
+    location: Location,
+    fetched_at: DateTime<Local>,
 }
 
 async fn get_coordinates(
@@ -146,19 +432,24 @@ async fn get_coordinates(
     city: &str,
     country_code: &str,
     api_key: &str,
-) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+) -> Result<(f64, f64), WeatherError> {
+    let query = if country_code.is_empty() {
+        city.to_string()
+    } else {
+        format!("{},{}", city, country_code)
+    };
     let geo_url = format!(
-        "http://api.openweathermap.org/geo/1.0/direct?q={},{}&limit=1&appid={}",
-        city, country_code, api_key
+        "https://api.openweathermap.org/geo/1.0/direct?q={}&limit=1&appid={}",
+        query, api_key
     );
 
-    let res = client.get(&geo_url).send().await?;
-    let geo_data: Vec<GeoResponse> = res.json().await?;
+    let text = recording::send("openweathermap-geocoding", client.get(&geo_url)).await?;
+    let geo_data: Vec<GeoResponse> = serde_json::from_str(&text)?;
 
     if let Some(location) = geo_data.first() {
         Ok((location.lat, location.lon))
     } else {
-        Err("Unable to get location coordinates.".into())
+        Err(WeatherError::GeocodingEmpty(query))
     }
 }
 
@@ -167,72 +458,181 @@ async fn get_weather_data(
     lat: f64,
     lon: f64,
     api_key: &str,
-) -> Result<WeatherResponse, Box<dyn std::error::Error>> {
+    schema_check: SchemaCheck,
+) -> Result<WeatherResponse, WeatherError> {
     let weather_url = format!(
-        "https://api.openweathermap.org/data/3.0/onecall?lat={}&lon={}&units=imperial&exclude=minutely,hourly,alerts&appid={}",
-        lat, lon, api_key
+        "https://api.openweathermap.org/data/3.0/onecall?lat={}&lon={}&units=imperial&lang={}&appid={}",
+        lat,
+        lon,
+        i18n::code(),
+        api_key
     );
 
-    let res = client.get(&weather_url).send().await?;
-    let text = res.text().await?;
+    let text = recording::send("openweathermap-onecall", client.get(&weather_url)).await?;
+    parse_onecall(&text, schema_check)
+}
 
-    let weather_data: WeatherResponse = serde_json::from_str(&text)?;
-    Ok(weather_data)
+fn parse_onecall(text: &str, schema_check: SchemaCheck) -> Result<WeatherResponse, WeatherError> {
+    if schema_check != SchemaCheck::Off {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+        let drift = check_schema(&value);
+        for problem in &drift {
+            tracing::warn!("One Call schema drift: {}", problem);
+        }
+        if schema_check == SchemaCheck::Strict && !drift.is_empty() {
+            return Err(WeatherError::Deserialize(format!("failed strict schema check, {} problems", drift.len())));
+        }
+    }
+    Ok(serde_json::from_str(text)?)
 }
 
-fn format_weather_data(weather_data: &WeatherResponse) -> (String, String) {
-    let current = &weather_data.current;
-    let today = &weather_data.daily[0];
-    let tomorrow = weather_data.daily.get(1);
+// Fields documented for the One Call 3.0 response. Anything outside these
+// lists means OpenWeatherMap has added or renamed something.
+const ONECALL_FIELDS: &[&str] = &[
+    "lat", "lon", "timezone", "timezone_offset", "current", "minutely", "hourly", "daily", "alerts",
+];
+const CURRENT_FIELDS: &[&str] = &[
+    "dt", "sunrise", "sunset", "temp", "feels_like", "pressure", "humidity", "dew_point", "uvi",
+    "clouds", "visibility", "wind_speed", "wind_deg", "wind_gust", "weather", "rain", "snow",
+];
+const DAILY_FIELDS: &[&str] = &[
+    "dt", "sunrise", "sunset", "moonrise", "moonset", "moon_phase", "summary", "temp", "feels_like",
+    "pressure", "humidity", "dew_point", "wind_speed", "wind_deg", "wind_gust", "weather", "clouds",
+    "pop", "rain", "snow", "uvi",
+];
+const DAILY_TEMP_FIELDS: &[&str] = &["day", "min", "max", "night", "eve", "morn"];
+const WEATHER_FIELDS: &[&str] = &["id", "main", "description", "icon"];
+const ALERT_FIELDS: &[&str] = &["sender_name", "event", "start", "end", "description", "tags"];
+
+// Fields the app actually reads; a missing one silently turns into a default.
+const CURRENT_REQUIRED: &[&str] = &["temp", "feels_like", "humidity", "wind_speed", "wind_deg", "weather"];
+const DAILY_REQUIRED: &[&str] = &["pop", "summary", "temp", "weather"];
+const DAILY_TEMP_REQUIRED: &[&str] = &["min", "max"];
+const WEATHER_REQUIRED: &[&str] = &["description"];
+const ALERT_REQUIRED: &[&str] = &["event", "start", "end"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SchemaCheck {
+    Off,
+    Warn,
+    Strict,
+}
 
-    let weather_description = &current.weather[0].description;
-    let temp = current.temp;
-    let feels_like = current.feels_like;
-    let humidity = current.humidity;
-    let wind_speed = current.wind_speed;
-    let wind_deg = current.wind_deg;
+impl SchemaCheck {
+    fn from_config(config: &Config) -> Self {
+        match config.owm_schema_check().as_deref() {
+            Some("warn") => SchemaCheck::Warn,
+            Some("strict") => SchemaCheck::Strict,
+            _ => SchemaCheck::Off,
+        }
+    }
+}
 
-    let wind_direction = degrees_to_cardinal(wind_deg);
+fn check_schema(value: &serde_json::Value) -> Vec<String> {
+    let mut drift = Vec::new();
+    check_object("", value, ONECALL_FIELDS, &["current", "daily"], &mut drift);
 
-    // Ensure pop is within 0.0 to 1.0
-    let chance_of_rain_today = (today.pop.min(1.0) * 100.0).round();
-    let daily_weather_description = capitalize_first_letter(&today.weather[0].description);
+    if let Some(current) = value.get("current") {
+        check_object("current", current, CURRENT_FIELDS, CURRENT_REQUIRED, &mut drift);
+        check_weather_list("current.weather", current.get("weather"), &mut drift);
+    }
 
-    let today_summary = &today.summary;
+    if let Some(daily) = value.get("daily").and_then(|d| d.as_array()) {
+        for (i, day) in daily.iter().enumerate() {
+            let path = format!("daily[{}]", i);
+            check_object(&path, day, DAILY_FIELDS, DAILY_REQUIRED, &mut drift);
+            if let Some(temp) = day.get("temp") {
+                check_object(&format!("{}.temp", path), temp, DAILY_TEMP_FIELDS, DAILY_TEMP_REQUIRED, &mut drift);
+            }
+            check_weather_list(&format!("{}.weather", path), day.get("weather"), &mut drift);
+        }
+    }
 
-    let chance_of_rain_tomorrow = if let Some(tomorrow) = tomorrow {
-        (tomorrow.pop.min(1.0) * 100.0).round()
-    } else {
-        0.0
+    if let Some(alerts) = value.get("alerts").and_then(|a| a.as_array()) {
+        for (i, alert) in alerts.iter().enumerate() {
+            check_object(&format!("alerts[{}]", i), alert, ALERT_FIELDS, ALERT_REQUIRED, &mut drift);
+        }
+    }
+
+    drift
+}
+
+fn check_weather_list(path: &str, value: Option<&serde_json::Value>, drift: &mut Vec<String>) {
+    if let Some(list) = value.and_then(|w| w.as_array()) {
+        for (i, entry) in list.iter().enumerate() {
+            check_object(&format!("{}[{}]", path, i), entry, WEATHER_FIELDS, WEATHER_REQUIRED, drift);
+        }
+    }
+}
+
+fn check_object(
+    path: &str,
+    value: &serde_json::Value,
+    known: &[&str],
+    required: &[&str],
+    drift: &mut Vec<String>,
+) {
+    let Some(object) = value.as_object() else {
+        drift.push(format!("`{}` is not an object", path));
+        return;
     };
+    let field_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
 
-    let temp_min = today.temp.min;
-    let temp_max = today.temp.max;
-
-    let formatted_data = format!(
-        r"Summary: {}
-        Current weather: {}
-        Temperature: {:.1}°F (Feels like {:.1}°F)
-        High: {:.1}°F
-        Low: {:.1}°F
-        Humidity: {}%
-        Wind: {:.1} mph {}
-        Chance of Rain Today: {:.0}%
-        Chance of Rain Tomorrow: {:.0}% ",
-        today_summary,
-        weather_description,
-        temp,
-        feels_like,
-        temp_max,
-        temp_min,
-        humidity,
-        wind_speed,
-        wind_direction,
-        chance_of_rain_today,
-        chance_of_rain_tomorrow,
-    );
+    for key in object.keys() {
+        if !known.contains(&key.as_str()) {
+            drift.push(format!("unknown field `{}`", field_path(key)));
+        }
+    }
+    for key in required {
+        if !object.contains_key(*key) {
+            drift.push(format!("missing field `{}`", field_path(key)));
+        }
+    }
+}
 
-    (formatted_data, daily_weather_description)
+const DATA_UNAVAILABLE: &str = "data unavailable";
+
+fn format_chance_of_rain(day: Option<&Daily>) -> String {
+    match day {
+        // Ensure pop is within 0.0 to 1.0
+        Some(day) => format!("{:.0}%", (day.pop.min(1.0) * 100.0).round()),
+        None => i18n::tr(DATA_UNAVAILABLE).to_string(),
+    }
+}
+
+fn format_uv_index(uvi: Option<f64>, skin_type: Option<SkinType>) -> String {
+    let Some(uvi) = uvi else {
+        return i18n::tr(DATA_UNAVAILABLE).to_string();
+    };
+    let risk = uv_risk(uvi);
+    match skin_type.and_then(|skin| skin.burn_minutes(uvi).map(|minutes| (skin, minutes))) {
+        Some((skin, minutes)) => format!(
+            "{:.1} {} (burn in ~{:.0} min for skin type {})",
+            uvi,
+            risk,
+            minutes,
+            skin.roman()
+        ),
+        None => format!("{:.1} {}", uvi, risk),
+    }
+}
+
+/// WHO exposure category for a UV index.
+fn uv_risk(uvi: f64) -> &'static str {
+    match uvi {
+        uvi if uvi < 3.0 => "Low",
+        uvi if uvi < 6.0 => "Moderate",
+        uvi if uvi < 8.0 => "High",
+        uvi if uvi < 11.0 => "Very high",
+        _ => "Extreme",
+    }
+}
+
+fn format_temperature(temp: Option<f64>, units: Units) -> String {
+    match temp {
+        Some(temp) => units.format_temperature(temp, 1),
+        None => i18n::tr(DATA_UNAVAILABLE).to_string(),
+    }
 }
 
 fn capitalize_first_letter(s: &str) -> String {
@@ -244,11 +644,285 @@ fn capitalize_first_letter(s: &str) -> String {
 }
 
 fn degrees_to_cardinal(degrees: u16) -> &'static str {
-    let dirs = [
-        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE",
-        "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
-    ];
     let index = (((degrees as f32 + 11.25) / 22.5) as usize) % 16;
-    dirs[index]
+    i18n::cardinal(index)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::current_conditions::DisplayModel;
+    use crate::units::PressureUnit;
+
+    // A One Call response cut down to the fields the app reads.
+    pub(crate) const ONECALL: &str = r#"{
+        "lat": 40.71, "lon": -74.01, "timezone": "America/New_York", "timezone_offset": -14400,
+        "current": {
+            "dt": 1760700000, "sunrise": 1760698800, "sunset": 1760738400, "temp": 61.5,
+            "feels_like": 60.2, "pressure": 1016, "humidity": 72, "dew_point": 52.3, "uvi": 3.4,
+            "clouds": 40, "visibility": 10000, "wind_speed": 8.1, "wind_deg": 230,
+            "weather": [{"id": 802, "main": "Clouds", "description": "scattered clouds", "icon": "03d"}]
+        },
+        "daily": [{
+            "dt": 1760716800, "summary": "Cloudy in the morning", "pop": 0.35, "uvi": 4.1,
+            "temp": {"day": 64.0, "min": 55.2, "max": 66.8, "night": 57.0, "eve": 62.0, "morn": 56.0},
+            "humidity": 70, "wind_speed": 9.3, "wind_deg": 240,
+            "weather": [{"id": 500, "main": "Rain", "description": "light rain", "icon": "10d"}]
+        }]
+    }"#;
+
+    pub(crate) fn snapshot(weather: WeatherResponse) -> WeatherSnapshot {
+        WeatherSnapshot {
+            weather,
+            location: Location {
+                city: "New York".to_string(),
+                country_code: "US".to_string(),
+                lat: 40.71,
+                lon: -74.01,
+                source: None,
+                accuracy_m: None,
+            },
+            fetched_at: Local::now(),
+        }
+    }
+
+    fn row<'a>(model: &'a DisplayModel, label: &str) -> Option<&'a str> {
+        model.rows.iter().find(|(l, _)| *l == label).map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn parses_full_response() {
+        let weather: WeatherResponse = serde_json::from_str(ONECALL).unwrap();
+        assert_eq!(weather.daily.len(), 1);
+        assert_eq!(weather.current.weather[0].description, "scattered clouds");
+        assert!(weather.alerts.is_empty());
+    }
+
+    #[test]
+    fn truncated_response_is_an_error() {
+        for end in [0, 1, 40, ONECALL.len() / 2, ONECALL.len() - 1] {
+            let result: Result<WeatherResponse, WeatherError> =
+                serde_json::from_str(&ONECALL[..end]).map_err(WeatherError::from);
+            assert!(matches!(result, Err(WeatherError::Deserialize(_))), "cut at {}", end);
+        }
+    }
+
+    #[test]
+    fn missing_required_field_is_an_error() {
+        let without_temp = ONECALL.replacen(r#""temp": 61.5,"#, "", 1);
+        assert!(serde_json::from_str::<WeatherResponse>(&without_temp).is_err());
+    }
+
+    #[test]
+    fn missing_lists_default_to_empty() {
+        let weather: WeatherResponse =
+            serde_json::from_str(r#"{"current": {"temp": 50.0, "humidity": 80, "wind_speed": 3.0, "wind_deg": 90}}"#)
+                .unwrap();
+        assert!(weather.current.weather.is_empty());
+        assert!(weather.daily.is_empty() && weather.hourly.is_empty() && weather.minutely.is_empty());
+    }
+
+    #[test]
+    fn sparse_response_shows_placeholders() {
+        i18n::Language::En.apply();
+        let weather: WeatherResponse =
+            serde_json::from_str(r#"{"current": {"temp": 50.0, "humidity": 80, "wind_speed": 3.0, "wind_deg": 90}, "daily": []}"#)
+                .unwrap();
+        let snapshot = snapshot(weather);
+        let model = DisplayModel::new(&snapshot, Units::IMPERIAL, PressureUnit::default(), None);
+        assert_eq!(row(&model, "Current weather"), Some("Data unavailable"));
+        assert_eq!(row(&model, "Chance of rain tomorrow"), Some(DATA_UNAVAILABLE));
+        assert_eq!(row(&model, "UV Index"), Some(DATA_UNAVAILABLE));
+        assert_eq!(row(&model, "Sunrise"), Some(DATA_UNAVAILABLE));
+        // The other text outputs read the same fields.
+        let text = summary::to_text(&snapshot, Units::IMPERIAL, None);
+        assert!(text.contains(DATA_UNAVAILABLE));
+    }
+
+    fn drift(json: &str) -> Vec<String> {
+        check_schema(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn documented_response_has_no_drift() {
+        assert_eq!(drift(ONECALL), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_unknown_and_missing_fields() {
+        let changed = ONECALL
+            .replacen(r#""clouds": 40,"#, r#""clouds": 40, "cloud_base": 1200,"#, 1)
+            .replacen(r#""pop": 0.35,"#, "", 1);
+        assert_eq!(drift(&changed), ["unknown field `current.cloud_base`", "missing field `daily[0].pop`"]);
+    }
+
+    #[test]
+    fn missing_feels_like_is_drift() {
+        let without = ONECALL.replacen(r#""feels_like": 60.2,"#, "", 1);
+        assert_eq!(drift(&without), ["missing field `current.feels_like`"]);
+        // It can still be worked out, so only strict mode refuses it.
+        assert!(parse_onecall(&without, SchemaCheck::Warn).is_ok());
+        assert!(parse_onecall(&without, SchemaCheck::Strict).is_err());
+    }
+
+    #[test]
+    fn reports_values_of_the_wrong_shape() {
+        assert_eq!(drift("[]"), ["`` is not an object"]);
+        assert_eq!(drift(r#"{"current": 5, "daily": []}"#), ["`current` is not an object"]);
+        let problems = drift(r#"{"current": {"temp": 1, "feels_like": 1, "humidity": 1, "wind_speed": 1, "wind_deg": 1, "weather": ["rain"]}, "daily": {}}"#);
+        assert_eq!(problems, ["`current.weather[0]` is not an object"]);
+    }
+
+    // Replaces every value in the fixture, one at a time, with each kind of
+    // JSON value. Neither the check nor the parser may panic, and strict
+    // mode has to refuse anything that doesn't parse.
+    #[test]
+    fn survives_mutated_payloads() {
+        fn paths(value: &serde_json::Value, path: String, out: &mut Vec<String>) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    for (key, child) in map {
+                        let child_path = format!("{}/{}", path, key);
+                        out.push(child_path.clone());
+                        paths(child, child_path, out);
+                    }
+                }
+                serde_json::Value::Array(items) => {
+                    for (i, child) in items.iter().enumerate() {
+                        let child_path = format!("{}/{}", path, i);
+                        out.push(child_path.clone());
+                        paths(child, child_path, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let original: serde_json::Value = serde_json::from_str(ONECALL).unwrap();
+        let mut pointers = Vec::new();
+        paths(&original, String::new(), &mut pointers);
+        let replacements = [
+            serde_json::json!(null),
+            serde_json::json!("x"),
+            serde_json::json!(-1),
+            serde_json::json!([]),
+            serde_json::json!({}),
+        ];
+        for pointer in &pointers {
+            for replacement in &replacements {
+                let mut mutated = original.clone();
+                *mutated.pointer_mut(pointer).unwrap() = replacement.clone();
+                let text = mutated.to_string();
+                let lenient = parse_onecall(&text, SchemaCheck::Off);
+                let strict = parse_onecall(&text, SchemaCheck::Strict);
+                assert!(lenient.is_ok() || strict.is_err(), "{} = {}", pointer, replacement);
+            }
+        }
+    }
+
+    #[test]
+    fn strict_mode_refuses_drift() {
+        let changed = ONECALL.replacen(r#""clouds": 40,"#, r#""clouds": 40, "cloud_base": 1200,"#, 1);
+        assert!(parse_onecall(&changed, SchemaCheck::Warn).is_ok());
+        assert!(matches!(parse_onecall(&changed, SchemaCheck::Strict), Err(WeatherError::Deserialize(_))));
+        assert!(parse_onecall(ONECALL, SchemaCheck::Strict).is_ok());
+    }
+
+    // Heap use is counted per thread so tests running alongside don't count.
+    pub(crate) mod heap {
+        use std::alloc::{GlobalAlloc, Layout, System};
+        use std::cell::Cell;
+
+        thread_local! {
+            static IN_USE: Cell<isize> = const { Cell::new(0) };
+            static PEAK: Cell<isize> = const { Cell::new(0) };
+        }
+
+        struct Counting;
+
+        fn track(change: isize) {
+            let _ = IN_USE.try_with(|in_use| {
+                in_use.set(in_use.get() + change);
+                let _ = PEAK.try_with(|peak| peak.set(peak.get().max(in_use.get())));
+            });
+        }
+
+        unsafe impl GlobalAlloc for Counting {
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                let ptr = unsafe { System.alloc(layout) };
+                if !ptr.is_null() {
+                    track(layout.size() as isize);
+                }
+                ptr
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                unsafe { System.dealloc(ptr, layout) };
+                track(-(layout.size() as isize));
+            }
+
+            unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+                let new = unsafe { System.realloc(ptr, layout, new_size) };
+                if !new.is_null() {
+                    track(new_size as isize - layout.size() as isize);
+                }
+                new
+            }
+        }
+
+        #[global_allocator]
+        static ALLOCATOR: Counting = Counting;
+
+        /// The most extra heap `f` held at once, in bytes.
+        pub fn peak_during(f: impl FnOnce()) -> usize {
+            let start = IN_USE.with(Cell::get);
+            PEAK.with(|peak| peak.set(start));
+            f();
+            (PEAK.with(Cell::get) - start).max(0) as usize
+        }
+    }
+
+    // What the headless daemon does after each fetch, for a full-size
+    // response: 60 minutes, 48 hours, 8 days and two long alerts.
+    #[test]
+    fn refresh_stays_under_memory_ceiling() {
+        const CEILING_BYTES: usize = 512 * 1024;
+        let mut value: serde_json::Value = serde_json::from_str(ONECALL).unwrap();
+        let hour = serde_json::json!({
+            "dt": 1760700000, "temp": 60.0, "feels_like": 59.0, "pressure": 1015, "humidity": 70,
+            "dew_point": 50.0, "uvi": 2.0, "clouds": 40, "visibility": 10000, "wind_speed": 7.0,
+            "wind_deg": 220, "wind_gust": 12.0, "pop": 0.2, "rain": {"1h": 0.3},
+            "weather": [{"id": 500, "main": "Rain", "description": "light rain", "icon": "10d"}]
+        });
+        let alert = serde_json::json!({
+            "sender_name": "NWS Upton NY", "event": "Wind Advisory", "start": 1760700000,
+            "end": 1760740000, "description": "West winds 20 to 30 mph. ".repeat(80), "tags": ["Wind"]
+        });
+        let day = value["daily"][0].clone();
+        value["minutely"] = (0..60).map(|i| serde_json::json!({"dt": 1760700000 + i * 60, "precipitation": 0.1})).collect();
+        value["hourly"] = vec![hour; 48].into();
+        value["daily"] = vec![day; 8].into();
+        value["alerts"] = vec![alert; 2].into();
+        let text = value.to_string();
+        let config = Config::default();
+
+        let peak = heap::peak_during(|| {
+            let snapshot = snapshot(parse_onecall(&text, SchemaCheck::Strict).unwrap());
+            let printed = export::to_text(&snapshot, Units::IMPERIAL, &config, None);
+            let summary = summary::to_text(&snapshot, Units::IMPERIAL, None);
+            let json = summary::to_json(&snapshot, Units::IMPERIAL).to_string();
+            assert!(!printed.is_empty() && !summary.is_empty() && !json.is_empty());
+        });
+        assert!(peak < CEILING_BYTES, "peak heap {} bytes", peak);
+    }
+
+    #[test]
+    fn chance_of_rain_is_clamped() {
+        i18n::Language::En.apply();
+        let mut weather: WeatherResponse = serde_json::from_str(ONECALL).unwrap();
+        assert_eq!(format_chance_of_rain(weather.daily.first()), "35%");
+        weather.daily[0].pop = 1.7;
+        assert_eq!(format_chance_of_rain(weather.daily.first()), "100%");
+        assert_eq!(format_chance_of_rain(None), DATA_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,61 @@
+//! Derived weather quantities for when a provider doesn't supply them. All
+//! inputs and outputs are in the imperial units the forecast is fetched in.
+
+/// Apparent ("feels like") temperature in °F: the NWS heat index in hot
+/// weather, wind chill in cold wind, and the air temperature in between.
+pub fn apparent_temperature(temp_f: f64, humidity: f64, wind_mph: f64) -> f64 {
+    if temp_f >= 80.0 {
+        heat_index(temp_f, humidity)
+    } else if temp_f <= 50.0 && wind_mph > 3.0 {
+        wind_chill(temp_f, wind_mph)
+    } else {
+        temp_f
+    }
+}
+
+// Rothfusz regression with the NWS adjustments for very dry and very humid
+// air; the simple formula is used where the regression doesn't apply.
+fn heat_index(t: f64, rh: f64) -> f64 {
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return simple;
+    }
+    let mut hi = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+    }
+    hi
+}
+
+// NWS wind chill formula (2001), valid at or below 50°F with wind above 3 mph.
+fn wind_chill(t: f64, v: f64) -> f64 {
+    let v = v.powf(0.16);
+    35.74 + 0.6215 * t - 35.75 * v + 0.4275 * t * v
+}
+
+/// Dew point in °F from the Magnus formula, good to a fraction of a degree
+/// in everyday conditions.
+pub fn dew_point(temp_f: f64, humidity: f64) -> f64 {
+    const B: f64 = 17.62;
+    const C: f64 = 243.12;
+    let temp_c = (temp_f - 32.0) * 5.0 / 9.0;
+    let gamma = (humidity.max(1.0) / 100.0).ln() + B * temp_c / (C + temp_c);
+    C * gamma / (B - gamma) * 9.0 / 5.0 + 32.0
+}
+
+/// Pressure at `elevation_m` above sea level given the sea-level pressure,
+/// using the standard-atmosphere lapse rate and the current temperature.
+#[cfg(feature = "gui")]
+pub fn station_pressure(sea_level_hpa: f64, elevation_m: f64, temp_f: f64) -> f64 {
+    let temp_k = (temp_f - 32.0) * 5.0 / 9.0 + 273.15;
+    let lapse = 0.0065 * elevation_m;
+    sea_level_hpa * (1.0 - lapse / (temp_k + lapse)).powf(5.257)
+}
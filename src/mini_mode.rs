@@ -0,0 +1,104 @@
+//! A compact layout for keeping the weather in a corner of the screen: the
+//! temperature, the conditions and the next alert, in a small frameless
+//! window that stays on top.
+
+use chrono::Local;
+use eframe::{egui, Frame};
+use crate::alerts;
+use crate::glyphs::condition_glyph;
+use crate::units::Units;
+use crate::{capitalize_first_letter, WeatherSnapshot};
+
+const MINI_SIZE: egui::Vec2 = egui::vec2(260.0, 120.0);
+// Below either, the full layout doesn't fit and the compact one is used
+// whether or not mini mode is on.
+const COMPACT_WIDTH: f32 = 360.0;
+const COMPACT_HEIGHT: f32 = 240.0;
+
+pub struct MiniMode {
+    pub on: bool,
+    // What the window was last set up for.
+    applied: bool,
+    // Restored when mini mode is turned off.
+    full_size: Option<egui::Vec2>,
+}
+
+impl MiniMode {
+    pub fn new() -> Self {
+        Self { on: false, applied: false, full_size: None }
+    }
+
+    /// Turns mini mode on or off with Ctrl+M (Cmd+M on macOS), then brings
+    /// the window in line with it.
+    pub fn sync(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::M)) {
+            self.on = !self.on;
+        }
+        if self.on == self.applied {
+            return;
+        }
+        self.applied = self.on;
+        frame.set_decorations(!self.on);
+        frame.set_always_on_top(self.on);
+        if self.on {
+            self.full_size = Some(frame.info().window_info.size);
+            frame.set_window_size(MINI_SIZE);
+        } else if let Some(size) = self.full_size.take() {
+            frame.set_window_size(size);
+        }
+    }
+
+    /// Whether to draw the compact layout instead of the full one.
+    pub fn compact(&self, frame: &Frame) -> bool {
+        let size = frame.info().window_info.size;
+        self.on || size.x < COMPACT_WIDTH || size.y < COMPACT_HEIGHT
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, frame: &mut Frame, snapshot: Option<&WeatherSnapshot>, units: Units) {
+        // Without decorations the window is moved by dragging anywhere in it.
+        if self.on {
+            let background = ui.interact(ui.max_rect(), egui::Id::new("mini_mode_drag"), egui::Sense::click_and_drag());
+            if background.drag_started() {
+                frame.drag_window();
+            }
+            if background.double_clicked() {
+                self.on = false;
+            }
+        }
+
+        let Some(snapshot) = snapshot else {
+            ui.spinner();
+            return;
+        };
+        let current = &snapshot.weather.current;
+        ui.horizontal(|ui| {
+            if let Some(condition) = current.weather.first() {
+                ui.label(egui::RichText::new(condition_glyph(condition.id)).size(28.0));
+            }
+            ui.heading(units.format_temperature(current.temp, 0));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                let (icon, hint) = if self.on { ("⤢", "Back to the full window") } else { ("⤡", "Mini mode (Ctrl+M)") };
+                if ui.small_button(icon).on_hover_text(hint).clicked() {
+                    self.on = !self.on;
+                }
+            });
+        });
+        let description = current.weather.first().map_or(String::new(), |w| capitalize_first_letter(&w.description));
+        ui.label(format!("{} · {}", snapshot.location.city, description));
+
+        let now = Local::now().timestamp();
+        let next = snapshot.weather.alerts.iter().filter(|alert| alert.end > now).min_by_key(|alert| alert.start);
+        match next {
+            Some(alert) => {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ {} · {}", alert.event, alerts::countdown(alert, now)),
+                );
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+            }
+            None => {
+                ui.small("No alerts");
+            }
+        }
+    }
+}
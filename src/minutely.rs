@@ -0,0 +1,99 @@
+//! The next hour of precipitation, minute by minute, from One Call's
+//! `minutely` array.
+
+use chrono::Local;
+#[cfg(feature = "gui")]
+use eframe::egui;
+use crate::{Current, Minutely};
+
+// mm/h. Below this a minute counts as dry; the others are the usual
+// boundaries between light, moderate and heavy.
+const WET_MM_H: f64 = 0.1;
+const MODERATE_MM_H: f64 = 2.5;
+const HEAVY_MM_H: f64 = 7.6;
+#[cfg(feature = "gui")]
+const BAR_HEIGHT: f32 = 36.0;
+#[cfg(feature = "gui")]
+const LABEL_HEIGHT: f32 = 13.0;
+#[cfg(feature = "gui")]
+const RAIN_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 150, 230);
+
+/// The minutes from now on, dropping any a cached forecast has already passed.
+fn upcoming(minutely: &[Minutely]) -> &[Minutely] {
+    let now = Local::now().timestamp();
+    let start = minutely.iter().position(|m| m.dt + 60 > now).unwrap_or(minutely.len());
+    &minutely[start..]
+}
+
+fn is_wet(minute: &Minutely) -> bool {
+    minute.precipitation >= WET_MM_H
+}
+
+/// "Light rain starting in 12 min, stopping in 45 min", or `None` when the
+/// next hour is dry or there is no minute-by-minute data.
+pub fn headline(minutely: &[Minutely], current: &Current) -> Option<String> {
+    let minutes = upcoming(minutely);
+    let start = minutes.iter().position(is_wet)?;
+    let stop = minutes[start..].iter().position(|m| !is_wet(m)).map(|len| start + len);
+    let peak = minutes[start..stop.unwrap_or(minutes.len())]
+        .iter()
+        .map(|m| m.precipitation)
+        .fold(0.0, f64::max);
+    let intensity = match peak {
+        p if p >= HEAVY_MM_H => "Heavy",
+        p if p >= MODERATE_MM_H => "Moderate",
+        _ => "Light",
+    };
+    let kind = match current.weather.first().map(|w| w.id) {
+        Some(600..=699) => "snow",
+        _ => "rain",
+    };
+    let headline = match (start, stop) {
+        (0, Some(stop)) => format!("{} {} stopping in {} min", intensity, kind, stop),
+        (0, None) => format!("{} {} for the next hour", intensity, kind),
+        (start, Some(stop)) => format!("{} {} starting in {} min, stopping in {} min", intensity, kind, start, stop),
+        (start, None) => format!("{} {} starting in {} min", intensity, kind, start),
+    };
+    Some(headline)
+}
+
+/// The headline over a bar per minute. Nothing is shown for providers
+/// without minute-by-minute data.
+#[cfg(feature = "gui")]
+pub fn show(ui: &mut egui::Ui, minutely: &[Minutely], current: &Current) {
+    let minutes = upcoming(minutely);
+    if minutes.is_empty() {
+        return;
+    }
+    ui.separator();
+    match headline(minutely, current) {
+        Some(headline) => ui.colored_label(RAIN_COLOR, headline),
+        None => ui.label("No rain expected in the next hour"),
+    };
+
+    let width = ui.available_width().min(480.0);
+    let (area, _) = ui.allocate_exact_size(egui::vec2(width, BAR_HEIGHT + LABEL_HEIGHT), egui::Sense::hover());
+    let rect = egui::Rect::from_min_size(area.min, egui::vec2(width, BAR_HEIGHT));
+    let painter = ui.painter_at(area);
+    painter.rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    let slot = rect.width() / 60.0;
+    for (i, minute) in minutes.iter().take(60).enumerate() {
+        if !is_wet(minute) {
+            continue;
+        }
+        // Anything heavy fills the bar; square root so drizzle still shows.
+        let fraction = (minute.precipitation / HEAVY_MM_H).min(1.0).sqrt() as f32;
+        let left = rect.left() + i as f32 * slot;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, rect.bottom() - fraction * rect.height()),
+            egui::pos2(left + slot, rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, RAIN_COLOR);
+    }
+    let font = egui::FontId::proportional(LABEL_HEIGHT - 2.0);
+    let color = ui.visuals().weak_text_color();
+    for (minute, align) in [(0.0, egui::Align2::LEFT_TOP), (30.0, egui::Align2::CENTER_TOP), (60.0, egui::Align2::RIGHT_TOP)] {
+        let text = if minute == 0.0 { "Now".to_string() } else { format!("{:.0} min", minute) };
+        painter.text(egui::pos2(rect.left() + minute * slot, rect.bottom() + 1.0), align, text, font.clone(), color);
+    }
+}
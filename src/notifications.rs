@@ -0,0 +1,298 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{Local, Timelike};
+use eframe::egui;
+use notify_rust::Notification;
+#[cfg(feature = "air-quality")]
+use crate::air_quality::{aqi_category, AqiSample};
+use crate::alerts::{self, Severity};
+use crate::app::format_duration;
+use crate::config::Config;
+use crate::forecast_changes::Change;
+use crate::rules::{Readings, Rule};
+use crate::time_format;
+use crate::units::Units;
+use crate::{capitalize_first_letter, WeatherSnapshot};
+
+// How far ahead the rain and temperature triggers look.
+const RAIN_LOOKAHEAD_HOURS: usize = 12;
+const TEMPERATURE_LOOKAHEAD_HOURS: usize = 24;
+const LOG_LEN: usize = 20;
+
+/// One attempt at showing a notification.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub at: i64,
+    pub summary: String,
+    pub test: bool,
+    /// The notification server's error when it refused the notification.
+    pub result: Result<(), String>,
+}
+
+/// Raises OS notifications when a fetch brings something new: an alert, a
+/// likely chance of rain, a temperature past the configured extremes, or
+/// air quality worse than the configured AQI. Optionally it also says when
+/// the forecast itself has stopped updating. User-defined rules notify the
+/// same way.
+/// Each condition notifies once when it starts, not on every refresh.
+pub struct Notifier {
+    // How serious an alert has to be to notify; `None` turns them off.
+    min_severity: Option<Severity>,
+    rain_percent: Option<f64>,
+    // In °F, like the forecast.
+    heat_f: Option<f64>,
+    cold_f: Option<f64>,
+    #[cfg(feature = "air-quality")]
+    aqi_threshold: Option<f64>,
+    notify_stale: bool,
+    // Local hours, start inclusive and end exclusive; may wrap past midnight.
+    quiet_hours: Option<(u32, u32)>,
+    seen_alerts: HashSet<(String, i64)>,
+    // Rules that matched on the last check, by description.
+    matched_rules: HashSet<String>,
+    // The same for points of the region grid.
+    grid_breaches: HashSet<String>,
+    rain_expected: bool,
+    heat_expected: bool,
+    cold_expected: bool,
+    #[cfg(feature = "air-quality")]
+    aqi_exceeded: bool,
+    stale: bool,
+    // Newest first, shared with the threads that show the notifications.
+    log: Arc<Mutex<VecDeque<Delivery>>>,
+    ctx: egui::Context,
+}
+
+impl Notifier {
+    pub fn new(ctx: &egui::Context, config: &Config) -> Self {
+        let min_severity = match config.notify_alerts().as_deref() {
+            Some("off") | Some("") => None,
+            Some("advisory") | Some("all") => Some(Severity::Advisory),
+            Some("watch") => Some(Severity::Watch),
+            _ => Some(Severity::Warning),
+        };
+        Self {
+            min_severity,
+            rain_percent: config.notify_rain_percent(),
+            heat_f: config.notify_heat_f(),
+            cold_f: config.notify_cold_f(),
+            #[cfg(feature = "air-quality")]
+            aqi_threshold: config.notify_aqi(),
+            notify_stale: config.notify_stale(),
+            quiet_hours: config.notify_quiet_hours(),
+            seen_alerts: HashSet::new(),
+            matched_rules: HashSet::new(),
+            grid_breaches: HashSet::new(),
+            rain_expected: false,
+            heat_expected: false,
+            cold_expected: false,
+            #[cfg(feature = "air-quality")]
+            aqi_exceeded: false,
+            stale: false,
+            log: Arc::new(Mutex::new(VecDeque::new())),
+            ctx: ctx.clone(),
+        }
+    }
+
+    /// Compares a fresh fetch with the previous one and notifies about what
+    /// is new. With `silent` the state still advances, so nothing pops up
+    /// later for conditions the user already saw in the window.
+    pub fn check(&mut self, snapshot: &WeatherSnapshot, units: Units, silent: bool) {
+        // Leave the state alone during quiet hours so that anything still
+        // going on afterwards notifies then.
+        if self.is_quiet_hour(Local::now().hour()) {
+            return;
+        }
+        let weather = &snapshot.weather;
+        let city = &snapshot.location.city;
+        let mut pending = Vec::new();
+
+        let alerts: HashSet<(String, i64)> = weather.alerts.iter().map(|a| (a.event.clone(), a.start)).collect();
+        if let Some(min_severity) = self.min_severity {
+            for alert in &weather.alerts {
+                let key = (alert.event.clone(), alert.start);
+                if Severity::of(alert) >= min_severity && !self.seen_alerts.contains(&key) {
+                    pending.push((format!("{} for {}", alert.event, city), alerts::format_period(alert)));
+                }
+            }
+        }
+        // Forget alerts that ended, so a reissued one notifies again.
+        self.seen_alerts = alerts;
+
+        let rain_percent = self.rain_percent;
+        let rain = weather.hourly.iter().take(RAIN_LOOKAHEAD_HOURS).find(|hour| {
+            rain_percent.is_some_and(|threshold| hour.pop * 100.0 >= threshold)
+        });
+        if let Some(hour) = rain.filter(|_| !self.rain_expected) {
+            let description = hour
+                .weather
+                .first()
+                .map_or("Rain".to_string(), |w| capitalize_first_letter(&w.description));
+            pending.push((
+                format!("{} likely in {}", description, city),
+                format!("{:.0}% chance around {}", (hour.pop.min(1.0) * 100.0).round(), time_format::local_time(hour.dt)),
+            ));
+        }
+        self.rain_expected = rain.is_some();
+
+        let temps = weather.hourly.iter().take(TEMPERATURE_LOOKAHEAD_HOURS).map(|hour| hour.temp);
+        let high = temps.clone().fold(f64::NEG_INFINITY, f64::max);
+        let low = temps.fold(f64::INFINITY, f64::min);
+        let heat = self.heat_f.is_some_and(|limit| high >= limit);
+        if heat && !self.heat_expected {
+            pending.push((format!("Heat in {}", city), format!("Up to {} in the next day", units.format_temperature(high, 0))));
+        }
+        self.heat_expected = heat;
+        let cold = self.cold_f.is_some_and(|limit| low <= limit);
+        if cold && !self.cold_expected {
+            pending.push((format!("Cold in {}", city), format!("Down to {} in the next day", units.format_temperature(low, 0))));
+        }
+        self.cold_expected = cold;
+
+        if !silent {
+            for (summary, body) in pending {
+                self.notify(summary, body, false);
+            }
+        }
+    }
+
+    /// Whether air quality readings are wanted at all.
+    #[cfg(feature = "air-quality")]
+    pub fn wants_aqi(&self) -> bool {
+        self.aqi_threshold.is_some()
+    }
+
+    /// Notifies once when the AQI reaches the threshold, then again only
+    /// after it has dropped back below it.
+    #[cfg(feature = "air-quality")]
+    pub fn check_aqi(&mut self, reading: &AqiSample, city: &str, silent: bool) {
+        let Some(threshold) = self.aqi_threshold else { return };
+        if self.is_quiet_hour(Local::now().hour()) {
+            return;
+        }
+        let exceeded = reading.aqi >= threshold;
+        if exceeded && !self.aqi_exceeded && !silent {
+            self.notify(
+                format!("{} air in {}", aqi_category(reading.aqi), city),
+                format!("AQI {:.0}, mostly {}", reading.aqi, reading.dominant),
+                false,
+            );
+        }
+        self.aqi_exceeded = exceeded;
+    }
+
+    /// Notifies for each enabled rule that starts matching the snapshot or
+    /// `readings`, or for "changes by" rules, the forecast `changes` of this
+    /// refresh.
+    pub fn check_rules(
+        &mut self,
+        rules: &[Rule],
+        snapshot: &WeatherSnapshot,
+        readings: &Readings,
+        changes: &[Change],
+        units: Units,
+        silent: bool,
+    ) {
+        if self.is_quiet_hour(Local::now().hour()) {
+            return;
+        }
+        let mut matched = HashSet::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let Some(status) = rule.status(&snapshot.weather, readings, changes, units) else { continue };
+            // Descriptions are kept in °F so that a units switch isn't a new
+            // match, while every new change is one.
+            let mut key = rule.describe(Units::IMPERIAL);
+            if let Some(change) = rule.check_changes(changes) {
+                key.push_str(&format!(" {:?}", change));
+            }
+            if !self.matched_rules.contains(&key) && !silent {
+                self.notify(format!("{} in {}", rule.describe(units), snapshot.location.city), status, false);
+            }
+            matched.insert(key);
+        }
+        self.matched_rules = matched;
+    }
+
+    /// Notifies for region grid points that newly break a rule; see
+    /// [`crate::region_grid::RegionGrid::breaches`].
+    pub fn check_grid(&mut self, breaches: &[(String, String)], silent: bool) {
+        if self.is_quiet_hour(Local::now().hour()) {
+            return;
+        }
+        let new: Vec<_> = breaches.iter().filter(|(summary, _)| !self.grid_breaches.contains(summary)).collect();
+        if !silent {
+            // A front crossing the region can break a rule at dozens of points.
+            match new[..] {
+                [] => {}
+                [(summary, body)] => self.notify(summary.clone(), body.clone(), false),
+                [(first, _), ..] => {
+                    self.notify(format!("Rules broken at {} grid points", new.len()), first.clone(), false)
+                }
+            }
+        }
+        self.grid_breaches = breaches.iter().map(|(summary, _)| summary.clone()).collect();
+    }
+
+    /// Notifies once when the data goes stale; `age` is `None` while it is
+    /// fresh.
+    pub fn check_stale(&mut self, age: Option<Duration>, city: &str, silent: bool) {
+        if !self.notify_stale || self.is_quiet_hour(Local::now().hour()) {
+            return;
+        }
+        if let Some(age) = age.filter(|_| !self.stale && !silent) {
+            self.notify(
+                format!("Weather for {} is out of date", city),
+                format!("No update for {}; alerts issued since then are not shown.", format_duration(age)),
+                false,
+            );
+        }
+        self.stale = age.is_some();
+    }
+
+    /// Shows a notification right away, ignoring quiet hours, so the user
+    /// can see that they come through.
+    pub fn send_test(&self) {
+        self.notify(
+            "Test notification".to_string(),
+            "Weather alerts will look like this.".to_string(),
+            true,
+        );
+    }
+
+    /// Recent notifications and whether each was shown, newest first.
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        self.log.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn notify(&self, summary: String, body: String, test: bool) {
+        let log = Arc::clone(&self.log);
+        let ctx = self.ctx.clone();
+        // Showing a notification is a blocking D-Bus call on Linux.
+        tokio::task::spawn_blocking(move || {
+            let result = Notification::new()
+                .appname("weather_alerts")
+                .summary(&summary)
+                .body(&body)
+                .icon("weather-severe-alert")
+                .show()
+                .map(|_| ())
+                .map_err(|e| e.to_string());
+            if let Err(ref e) = result {
+                tracing::warn!("Unable to show notification: {}", e);
+            }
+            let mut log = log.lock().unwrap();
+            log.push_front(Delivery { at: Local::now().timestamp(), summary, test, result });
+            log.truncate(LOG_LEN);
+            ctx.request_repaint();
+        });
+    }
+
+    fn is_quiet_hour(&self, hour: u32) -> bool {
+        match self.quiet_hours {
+            Some((start, end)) if start <= end => (start..end).contains(&hour),
+            Some((start, end)) => hour >= start || hour < end,
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+
+const NWS_API_URL: &str = "https://api.weather.gov";
+// api.weather.gov rejects requests without an identifying User-Agent.
+const USER_AGENT: &str = "weather_alerts (https://github.com/jargray85/weather_alerts)";
+
+#[derive(Debug, Deserialize)]
+struct Point {
+    properties: PointProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointProperties {
+    observation_stations: String,
+    // Zone URLs such as ".../zones/county/COC059"; the last segment is the UGC code.
+    county: Option<String>,
+    forecast_zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Zone {
+    properties: ZoneProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZoneProperties {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertCollection {
+    features: Vec<AlertFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertFeature {
+    properties: AlertProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertProperties {
+    event: String,
+    #[serde(default)]
+    area_desc: String,
+    #[serde(default)]
+    geocode: Geocode,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Geocode {
+    #[serde(rename = "UGC", default)]
+    ugc: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StationCollection {
+    features: Vec<StationFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StationFeature {
+    properties: StationProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StationProperties {
+    station_identifier: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Observation {
+    properties: ObservationProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObservationProperties {
+    temperature: Measurement,
+}
+
+#[derive(Debug, Deserialize)]
+struct Measurement {
+    // Degrees Celsius; null when the sensor didn't report.
+    value: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct StationObservation {
+    pub station_id: String,
+    pub station_name: String,
+    pub temp_f: f64,
+}
+
+/// The counties and zones one active CAP alert covers.
+#[derive(Debug)]
+pub struct AlertArea {
+    pub event: String,
+    /// `areaDesc` split up, e.g. ["Adams", "Arapahoe", "Denver"].
+    pub areas: Vec<String>,
+    /// UGC codes such as "COC001" (county) or "COZ039" (forecast zone).
+    pub ugc: Vec<String>,
+}
+
+/// Active alerts for a point, with the county and forecast zone the point
+/// itself is in, for telling whether an alert includes it.
+#[derive(Debug)]
+pub struct AlertAreas {
+    pub alerts: Vec<AlertArea>,
+    /// UGC codes of the point's own county and forecast zone.
+    pub own_codes: Vec<String>,
+    /// Names of the same, e.g. "Jefferson" and "Central Jefferson County".
+    pub own_names: Vec<String>,
+}
+
+impl AlertAreas {
+    /// The entry for the One Call alert named `event`, if the NWS has it.
+    pub fn find(&self, event: &str) -> Option<&AlertArea> {
+        self.alerts.iter().find(|alert| alert.event.eq_ignore_ascii_case(event))
+    }
+
+    pub fn includes_own(&self, alert: &AlertArea) -> bool {
+        alert.ugc.iter().any(|code| self.own_codes.contains(code))
+    }
+}
+
+/// Affected areas of the alerts active at a point (US only).
+pub async fn get_alert_areas(client: &Client, lat: f64, lon: f64) -> Result<AlertAreas, WeatherError> {
+    let point: Point = get_json(client, &format!("{}/points/{:.4},{:.4}", NWS_API_URL, lat, lon)).await?;
+    let active: AlertCollection =
+        get_json(client, &format!("{}/alerts/active?point={:.4},{:.4}", NWS_API_URL, lat, lon)).await?;
+
+    let (mut own_codes, mut own_names) = (Vec::new(), Vec::new());
+    for url in [point.properties.county, point.properties.forecast_zone].into_iter().flatten() {
+        // A missing zone name only loses the highlight, not the list.
+        match get_json::<Zone>(client, &url).await {
+            Ok(zone) => {
+                own_codes.push(zone.properties.id);
+                own_names.push(zone.properties.name);
+            }
+            Err(_) => own_codes.extend(url.rsplit('/').next().map(str::to_string)),
+        }
+    }
+
+    let alerts = active
+        .features
+        .into_iter()
+        .map(|feature| {
+            let properties = feature.properties;
+            AlertArea {
+                event: properties.event,
+                areas: properties
+                    .area_desc
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|area| !area.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                ugc: properties.geocode.ugc,
+            }
+        })
+        .collect();
+    Ok(AlertAreas { alerts, own_codes, own_names })
+}
+
+/// Latest temperature from the closest NWS observation station (US only).
+pub async fn get_nearest_observation(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+) -> Result<StationObservation, WeatherError> {
+    let point: Point = get_json(client, &format!("{}/points/{:.4},{:.4}", NWS_API_URL, lat, lon)).await?;
+    let stations: StationCollection = get_json(client, &point.properties.observation_stations).await?;
+    let station = stations
+        .features
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::Service("No NWS observation stations near this location.".to_string()))?
+        .properties;
+
+    let observation: Observation = get_json(
+        client,
+        &format!("{}/stations/{}/observations/latest", NWS_API_URL, station.station_identifier),
+    )
+    .await?;
+    let temp_c = observation
+        .properties
+        .temperature
+        .value
+        .ok_or_else(|| WeatherError::Service(format!("Station {} has no recent temperature.", station.station_identifier)))?;
+
+    Ok(StationObservation {
+        station_id: station.station_identifier,
+        station_name: station.name,
+        temp_f: temp_c * 9.0 / 5.0 + 32.0,
+    })
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    url: &str,
+) -> Result<T, WeatherError> {
+    let res = client
+        .get(url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .header(reqwest::header::ACCEPT, "application/geo+json")
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(res.json().await?)
+}
@@ -0,0 +1,613 @@
+#[cfg(feature = "gui")]
+use chrono::NaiveDate;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use crate::alerts::Alert;
+use crate::error::WeatherError;
+#[cfg(feature = "gui")]
+use crate::provider::PlaceMatch;
+use crate::provider::WeatherProvider;
+use crate::recording;
+#[cfg(feature = "gui")]
+use crate::timemachine::PastHour;
+use crate::{Current, Daily, DailyTemp, Hourly, Precipitation, Weather};
+
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+#[cfg(feature = "gui")]
+const ELEVATION_URL: &str = "https://api.open-meteo.com/v1/elevation";
+/// Furthest day ahead Open-Meteo's forecast endpoint serves.
+#[cfg(feature = "gui")]
+pub const MAX_FORECAST_DAYS: i64 = 16;
+// Matches what One Call returns.
+const HOURLY_FORECAST_HOURS: u32 = 48;
+const DAILY_FORECAST_DAYS: u32 = 8;
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    daily: DailySeries,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct DailySeries {
+    time: Vec<String>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug)]
+pub struct DayForecast {
+    pub date: NaiveDate,
+    pub temp_max: Option<f64>,
+    pub temp_min: Option<f64>,
+    // Percent, 0-100.
+    pub precipitation_probability: Option<f64>,
+    pub weather_code: Option<u8>,
+}
+
+/// Open-Meteo daily forecast (°F) for a single date up to 16 days out. Needs no API key.
+#[cfg(feature = "gui")]
+pub async fn get_day_forecast(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    date: NaiveDate,
+) -> Result<DayForecast, WeatherError> {
+    let date_param = date.format("%Y-%m-%d").to_string();
+    let res = client
+        .get(FORECAST_URL)
+        .query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            (
+                "daily",
+                "temperature_2m_max,temperature_2m_min,precipitation_probability_max,weather_code".to_string(),
+            ),
+            ("temperature_unit", "fahrenheit".to_string()),
+            ("timezone", "auto".to_string()),
+            ("start_date", date_param.clone()),
+            ("end_date", date_param),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    let forecast: ForecastResponse = res.json().await?;
+    let daily = forecast.daily;
+
+    if daily.time.is_empty() {
+        return Err(WeatherError::Service("Open-Meteo returned no forecast for that date.".to_string()));
+    }
+    Ok(DayForecast {
+        date,
+        temp_max: daily.temperature_2m_max.first().copied().flatten(),
+        temp_min: daily.temperature_2m_min.first().copied().flatten(),
+        precipitation_probability: daily.precipitation_probability_max.first().copied().flatten(),
+        weather_code: daily.weather_code.first().copied().flatten(),
+    })
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct GridResponse {
+    daily: GridSeries,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct GridSeries {
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+}
+
+// A request for several locations gets an array back, and for one an object.
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// Today's forecast (°F) at one point of a [`get_grid`] request.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct GridPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub temp_max: Option<f64>,
+    pub temp_min: Option<f64>,
+    // Percent, 0-100.
+    pub precipitation_probability: Option<f64>,
+}
+
+/// Today's forecast at every point in `points`, in one request.
+#[cfg(feature = "gui")]
+pub async fn get_grid(client: &Client, points: &[(f64, f64)]) -> Result<Vec<GridPoint>, WeatherError> {
+    let join = |coordinate: fn(&(f64, f64)) -> f64| {
+        points.iter().map(|p| format!("{:.4}", coordinate(p))).collect::<Vec<_>>().join(",")
+    };
+    let res = client
+        .get(FORECAST_URL)
+        .query(&[
+            ("latitude", join(|p| p.0)),
+            ("longitude", join(|p| p.1)),
+            (
+                "daily",
+                "temperature_2m_max,temperature_2m_min,precipitation_probability_max".to_string(),
+            ),
+            ("temperature_unit", "fahrenheit".to_string()),
+            ("timezone", "auto".to_string()),
+            ("forecast_days", "1".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    let responses = match res.json().await? {
+        OneOrMany::One(response) => vec![response],
+        OneOrMany::Many(responses) => responses,
+    };
+    if responses.len() != points.len() {
+        return Err(WeatherError::Service(format!("Open-Meteo returned {} of {} grid points", responses.len(), points.len())));
+    }
+    Ok(points
+        .iter()
+        .zip(responses)
+        .map(|(&(lat, lon), response): (&(f64, f64), GridResponse)| GridPoint {
+            lat,
+            lon,
+            temp_max: at(&response.daily.temperature_2m_max, 0),
+            temp_min: at(&response.daily.temperature_2m_min, 0),
+            precipitation_probability: at(&response.daily.precipitation_probability_max, 0),
+        })
+        .collect())
+}
+
+/// The next `days` days of forecast (°F) at every point in `points`, in one
+/// request.
+#[cfg(feature = "gui")]
+pub async fn get_days(
+    client: &Client,
+    points: &[(f64, f64)],
+    days: u32,
+) -> Result<Vec<Vec<DayForecast>>, WeatherError> {
+    let join = |coordinate: fn(&(f64, f64)) -> f64| {
+        points.iter().map(|p| format!("{:.4}", coordinate(p))).collect::<Vec<_>>().join(",")
+    };
+    let res = client
+        .get(FORECAST_URL)
+        .query(&[
+            ("latitude", join(|p| p.0)),
+            ("longitude", join(|p| p.1)),
+            (
+                "daily",
+                "temperature_2m_max,temperature_2m_min,precipitation_probability_max,weather_code".to_string(),
+            ),
+            ("temperature_unit", "fahrenheit".to_string()),
+            ("timezone", "auto".to_string()),
+            ("forecast_days", days.to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    let responses = match res.json().await? {
+        OneOrMany::One(response) => vec![response],
+        OneOrMany::Many(responses) => responses,
+    };
+    if responses.len() != points.len() {
+        return Err(WeatherError::Service(format!("Open-Meteo returned {} of {} locations", responses.len(), points.len())));
+    }
+    Ok(responses
+        .into_iter()
+        .map(|response: ForecastResponse| {
+            let daily = response.daily;
+            daily
+                .time
+                .iter()
+                .enumerate()
+                .filter_map(|(i, date)| {
+                    Some(DayForecast {
+                        date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+                        temp_max: at(&daily.temperature_2m_max, i),
+                        temp_min: at(&daily.temperature_2m_min, i),
+                        precipitation_probability: at(&daily.precipitation_probability_max, i),
+                        weather_code: at(&daily.weather_code, i),
+                    })
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct PastResponse {
+    hourly: PastSeries,
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct PastSeries {
+    time: Vec<i64>,
+    temperature_2m: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+}
+
+/// Hourly temperature (°F) and precipitation (mm) from the start of the day
+/// `past_days` ago until the end of today, for past weather without a key.
+#[cfg(feature = "gui")]
+pub async fn get_past_hours(client: &Client, lat: f64, lon: f64, past_days: u32) -> Result<Vec<PastHour>, WeatherError> {
+    let res = client
+        .get(FORECAST_URL)
+        .query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("hourly", "temperature_2m,precipitation".to_string()),
+            ("temperature_unit", "fahrenheit".to_string()),
+            ("timeformat", "unixtime".to_string()),
+            ("past_days", past_days.to_string()),
+            ("forecast_days", "1".to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    let response: PastResponse = res.json().await?;
+    let hourly = response.hourly;
+    Ok(hourly
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &dt)| {
+            Some(PastHour {
+                dt,
+                temp: at(&hourly.temperature_2m, i)?,
+                precipitation: at(&hourly.precipitation, i).unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct ElevationResponse {
+    elevation: Vec<f64>,
+}
+
+/// Ground elevation in metres from Open-Meteo's 90 m digital elevation model.
+#[cfg(feature = "gui")]
+pub async fn get_elevation(client: &Client, lat: f64, lon: f64) -> Result<f64, WeatherError> {
+    let res = client
+        .get(ELEVATION_URL)
+        .query(&[("latitude", lat), ("longitude", lon)])
+        .send()
+        .await?
+        .error_for_status()?;
+    let response: ElevationResponse = res.json().await?;
+    response
+        .elevation
+        .first()
+        .copied()
+        .ok_or_else(|| WeatherError::Service("Open-Meteo returned no elevation.".to_string()))
+}
+
+/// Plain-language description of a WMO weather interpretation code.
+pub fn describe_weather_code(code: u8) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 | 63 | 65 => "Rain",
+        66 | 67 => "Freezing rain",
+        71 | 73 | 75 | 77 => "Snow",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown conditions",
+    }
+}
+
+/// One Call condition code closest to a WMO weather code, so icons and
+/// glyphs work the same for both providers.
+fn condition_id(code: u8) -> u16 {
+    match code {
+        0 => 800,
+        1 => 801,
+        2 => 802,
+        3 => 804,
+        45 | 48 => 741,
+        51 => 300,
+        53 => 301,
+        55 => 302,
+        56 | 57 | 66 | 67 => 511,
+        61 => 500,
+        63 => 501,
+        65 => 502,
+        71 | 77 => 600,
+        73 => 601,
+        75 => 602,
+        80 => 520,
+        81 => 521,
+        82 => 522,
+        85 => 620,
+        86 => 622,
+        95 => 211,
+        96 | 99 => 202,
+        _ => 0,
+    }
+}
+
+fn weather(code: Option<u8>) -> Vec<Weather> {
+    code.map(|code| Weather {
+        id: condition_id(code),
+        // One Call descriptions are lower case, e.g. "light rain".
+        description: describe_weather_code(code).to_lowercase(),
+    })
+    .into_iter()
+    .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentResponse {
+    current: CurrentValues,
+    daily: SunValues,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentValues {
+    temperature_2m: f64,
+    apparent_temperature: Option<f64>,
+    relative_humidity_2m: f64,
+    wind_speed_10m: f64,
+    wind_direction_10m: f64,
+    weather_code: Option<u8>,
+    uv_index: Option<f64>,
+    pressure_msl: Option<f64>,
+    dew_point_2m: Option<f64>,
+    visibility: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SunValues {
+    sunrise: Vec<Option<i64>>,
+    sunset: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyResponse {
+    hourly: HourlyValues,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyValues {
+    time: Vec<i64>,
+    temperature_2m: Vec<Option<f64>>,
+    precipitation_probability: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    wind_gusts_10m: Vec<Option<f64>>,
+    wind_direction_10m: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+    rain: Vec<Option<f64>>,
+    snowfall: Vec<Option<f64>>,
+    pressure_msl: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyResponse {
+    daily: DailyValues,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyValues {
+    time: Vec<i64>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+    relative_humidity_2m_mean: Vec<Option<f64>>,
+    wind_speed_10m_max: Vec<Option<f64>>,
+    wind_direction_10m_dominant: Vec<Option<f64>>,
+    uv_index_max: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResult {
+    #[cfg(feature = "gui")]
+    #[serde(default)]
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    country_code: String,
+    // State or province.
+    #[cfg(feature = "gui")]
+    admin1: Option<String>,
+}
+
+// Series entries are null past the end of a model run.
+fn at<T: Copy>(series: &[Option<T>], i: usize) -> Option<T> {
+    series.get(i).copied().flatten()
+}
+
+/// Open-Meteo forecasts and geocoding. Free for non-commercial use and needs
+/// no API key, but has no weather alerts.
+pub struct OpenMeteo {
+    client: Client,
+}
+
+impl OpenMeteo {
+    pub fn new() -> Self {
+        Self { client: Client::new() }
+    }
+
+    /// `name` tells the request apart from the other sections in recordings.
+    async fn forecast<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        lat: f64,
+        lon: f64,
+        query: &[(&str, String)],
+    ) -> Result<T, WeatherError> {
+        let request = self
+            .client
+            .get(FORECAST_URL)
+            .query(&[
+                ("latitude", lat.to_string()),
+                ("longitude", lon.to_string()),
+                ("temperature_unit", "fahrenheit".to_string()),
+                ("wind_speed_unit", "mph".to_string()),
+                ("timeformat", "unixtime".to_string()),
+                ("timezone", "auto".to_string()),
+            ])
+            .query(query);
+        Ok(serde_json::from_str(&recording::send(name, request).await?)?)
+    }
+}
+
+impl WeatherProvider for OpenMeteo {
+    async fn geocode(&self, city: &str, country_code: &str) -> Result<(f64, f64), WeatherError> {
+        let request = self.client.get(GEOCODING_URL).query(&[("name", city), ("count", "10"), ("format", "json")]);
+        let geocoding: GeocodingResponse = serde_json::from_str(&recording::send("open-meteo-geocoding", request).await?)?;
+        geocoding
+            .results
+            .into_iter()
+            .find(|place| country_code.is_empty() || place.country_code.eq_ignore_ascii_case(country_code))
+            .map(|place| (place.latitude, place.longitude))
+            .ok_or_else(|| WeatherError::GeocodingEmpty(city.to_string()))
+    }
+
+    #[cfg(feature = "gui")]
+    async fn search(&self, query: &str, limit: u8) -> Result<Vec<PlaceMatch>, WeatherError> {
+        let request = self.client.get(GEOCODING_URL).query(&[("name", query), ("count", &limit.to_string()), ("format", "json")]);
+        let geocoding: GeocodingResponse = serde_json::from_str(&recording::send("open-meteo-search", request).await?)?;
+        Ok(geocoding
+            .results
+            .into_iter()
+            .map(|place| PlaceMatch {
+                name: place.name,
+                lat: place.latitude,
+                lon: place.longitude,
+                country_code: place.country_code,
+                region: place.admin1,
+            })
+            .collect())
+    }
+
+    async fn fetch_current(&self, lat: f64, lon: f64) -> Result<Current, WeatherError> {
+        let fields = "temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m,wind_direction_10m,weather_code,uv_index,pressure_msl,dew_point_2m,visibility";
+        let query = [
+            ("current", fields.to_string()),
+            ("daily", "sunrise,sunset".to_string()),
+            ("forecast_days", "1".to_string()),
+        ];
+        let response: CurrentResponse = self.forecast("open-meteo-current", lat, lon, &query).await?;
+        let (current, sun) = (response.current, response.daily);
+        Ok(Current {
+            temp: current.temperature_2m,
+            feels_like: current.apparent_temperature,
+            humidity: current.relative_humidity_2m.round() as u8,
+            wind_speed: current.wind_speed_10m,
+            wind_deg: current.wind_direction_10m.round() as u16,
+            uvi: current.uv_index,
+            pressure: current.pressure_msl,
+            dew_point: current.dew_point_2m,
+            visibility: current.visibility,
+            sunrise: at(&sun.sunrise, 0),
+            sunset: at(&sun.sunset, 0),
+            weather: weather(current.weather_code),
+        })
+    }
+
+    async fn fetch_hourly(&self, lat: f64, lon: f64) -> Result<Vec<Hourly>, WeatherError> {
+        let fields = "temperature_2m,precipitation_probability,wind_speed_10m,wind_gusts_10m,wind_direction_10m,weather_code,rain,snowfall,pressure_msl";
+        let response: HourlyResponse = self
+            .forecast(
+                "open-meteo-hourly",
+                lat,
+                lon,
+                &[("hourly", fields.to_string()), ("forecast_hours", HOURLY_FORECAST_HOURS.to_string())],
+            )
+            .await?;
+        let hourly = response.hourly;
+        let hours = hourly
+            .time
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &dt)| {
+                Some(Hourly {
+                    dt,
+                    temp: at(&hourly.temperature_2m, i)?,
+                    pop: at(&hourly.precipitation_probability, i).unwrap_or(0.0) / 100.0,
+                    wind_speed: at(&hourly.wind_speed_10m, i).unwrap_or(0.0),
+                    wind_gust: at(&hourly.wind_gusts_10m, i),
+                    wind_deg: at(&hourly.wind_direction_10m, i).unwrap_or(0.0).round() as u16,
+                    pressure: at(&hourly.pressure_msl, i),
+                    weather: weather(at(&hourly.weather_code, i)),
+                    // Millimetres, like One Call; snowfall comes in centimetres.
+                    rain: at(&hourly.rain, i).filter(|mm| *mm > 0.0).map(|mm| Precipitation { one_hour: mm }),
+                    snow: at(&hourly.snowfall, i)
+                        .filter(|cm| *cm > 0.0)
+                        .map(|cm| Precipitation { one_hour: cm * 10.0 }),
+                })
+            })
+            .collect();
+        Ok(hours)
+    }
+
+    async fn fetch_daily(&self, lat: f64, lon: f64) -> Result<Vec<Daily>, WeatherError> {
+        let fields = "temperature_2m_max,temperature_2m_min,precipitation_probability_max,weather_code,\
+                      relative_humidity_2m_mean,wind_speed_10m_max,wind_direction_10m_dominant,uv_index_max";
+        let response: DailyResponse = self
+            .forecast(
+                "open-meteo-daily",
+                lat,
+                lon,
+                &[("daily", fields.to_string()), ("forecast_days", DAILY_FORECAST_DAYS.to_string())],
+            )
+            .await?;
+        let daily = response.daily;
+        let days = daily
+            .time
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &dt)| {
+                Some(Daily {
+                    dt,
+                    pop: at(&daily.precipitation_probability_max, i).unwrap_or(0.0) / 100.0,
+                    // Open-Meteo has no written summary.
+                    summary: String::new(),
+                    temp: DailyTemp {
+                        min: at(&daily.temperature_2m_min, i)?,
+                        max: at(&daily.temperature_2m_max, i)?,
+                    },
+                    weather: weather(at(&daily.weather_code, i)),
+                    humidity: at(&daily.relative_humidity_2m_mean, i).map(|h| h.round() as u8),
+                    wind_speed: at(&daily.wind_speed_10m_max, i),
+                    wind_deg: at(&daily.wind_direction_10m_dominant, i).map(|deg| deg.round() as u16),
+                    uvi: at(&daily.uv_index_max, i),
+                })
+            })
+            .collect();
+        Ok(days)
+    }
+
+    async fn fetch_alerts(&self, _lat: f64, _lon: f64) -> Result<Vec<Alert>, WeatherError> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{Local, TimeZone};
+use eframe::egui;
+use egui::plot::{HLine, Line, Plot, PlotPoints};
+use reqwest::Client;
+use crate::air_quality::{aqi_advisory, aqi_category, aqi_color, get_air_quality, get_air_quality_forecast, AqiSample};
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::i18n;
+use crate::time_format;
+use crate::Location;
+use super::Panel;
+
+// Start of the "Unhealthy for sensitive groups" band.
+const UNHEALTHY_AQI: f64 = 101.0;
+
+/// Current AQI with its health advice, over a chart of the air pollution
+/// forecast from OWM or, without a key, Open-Meteo.
+pub struct AirQualityPanel {
+    // Open-Meteo is used without one.
+    api_key: Option<String>,
+    current: Option<Fetch<AqiSample>>,
+    forecast: Option<Fetch<Vec<AqiSample>>>,
+}
+
+impl AirQualityPanel {
+    pub fn new(config: &Config) -> Self {
+        Self { api_key: config.api_key(), current: None, forecast: None }
+    }
+}
+
+impl Panel for AirQualityPanel {
+    fn title(&self) -> &str {
+        "Air quality"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        let (lat, lon) = (location.lat, location.lon);
+        let api_key = self.api_key.clone();
+        self.current = Some(Fetch::spawn(ctx, async move {
+            get_air_quality(&Client::new(), lat, lon, api_key.as_deref()).await
+        }));
+        let api_key = self.api_key.clone();
+        self.forecast = Some(Fetch::spawn(ctx, async move {
+            get_air_quality_forecast(&Client::new(), lat, lon, api_key.as_deref()).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        if let Some(current) = &mut self.current {
+            current.poll();
+            match current {
+                Fetch::Ready(reading) => show_current(ui, reading),
+                // The forecast below reports the same failure.
+                Fetch::Pending(_) | Fetch::Failed(_) => {}
+            }
+        }
+        let Some(forecast) = &mut self.forecast else { return };
+        forecast.poll();
+
+        match forecast {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load air quality forecast: {}", e));
+            }
+            Fetch::Ready(samples) if samples.is_empty() => {
+                ui.label("No air quality forecast available.");
+            }
+            Fetch::Ready(samples) => show_chart(ui, samples),
+        }
+    }
+}
+
+/// "AQI 72 · Moderate · mostly PM2.5" on the EPA color for the category.
+fn show_current(ui: &mut egui::Ui, reading: &AqiSample) {
+    egui::Frame::group(ui.style()).fill(aqi_color(reading.aqi)).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        // Every band is light enough for dark text except the top two.
+        let text = if reading.aqi > 200.0 { egui::Color32::WHITE } else { egui::Color32::BLACK };
+        ui.colored_label(
+            text,
+            egui::RichText::new(format!(
+                "AQI {:.0} · {} · mostly {}",
+                reading.aqi,
+                aqi_category(reading.aqi),
+                reading.dominant
+            ))
+            .strong(),
+        );
+        ui.colored_label(text, aqi_advisory(reading.aqi));
+    });
+}
+
+fn show_chart(ui: &mut egui::Ui, samples: &[AqiSample]) {
+    if let Some(peak) = samples.iter().max_by(|a, b| a.aqi.total_cmp(&b.aqi)) {
+        let when = time_format::local_weekday_time(peak.dt);
+        ui.label(format!(
+            "Peak AQI {:.0} ({}, mostly {}) on {}",
+            peak.aqi,
+            aqi_category(peak.aqi),
+            peak.dominant,
+            when
+        ));
+    }
+
+    let points: PlotPoints = samples.iter().map(|s| [s.dt as f64, s.aqi]).collect();
+    Plot::new("aqi_forecast")
+        .height(160.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .include_y(UNHEALTHY_AQI + 20.0)
+        .x_axis_formatter(|x, _range| {
+            Local
+                .timestamp_opt(x as i64, 0)
+                .single()
+                .map(|t| i18n::format_datetime(&t, "%a %Hh"))
+                .unwrap_or_default()
+        })
+        .show(ui, |plot_ui| {
+            plot_ui.hline(HLine::new(UNHEALTHY_AQI).name("Unhealthy for sensitive groups"));
+            plot_ui.line(Line::new(points).name("AQI"));
+        });
+}
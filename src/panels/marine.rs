@@ -0,0 +1,73 @@
+use chrono::Local;
+use eframe::egui;
+use reqwest::Client;
+use crate::fetch::Fetch;
+use crate::tides::{get_tides, TideForecast};
+use crate::time_format;
+use crate::Location;
+use super::Panel;
+
+// Tide events listed per station (roughly the next day).
+const TIDE_EVENTS_SHOWN: usize = 4;
+
+/// Coastal conditions from NOAA CO-OPS tide predictions.
+pub struct MarinePanel {
+    tides: Option<Fetch<Option<TideForecast>>>,
+}
+
+impl MarinePanel {
+    pub fn new() -> Self {
+        Self { tides: None }
+    }
+}
+
+impl Panel for MarinePanel {
+    fn title(&self) -> &str {
+        "Marine"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        // NOAA CO-OPS only covers US waters.
+        if location.country_code != "US" {
+            return;
+        }
+        let (lat, lon) = (location.lat, location.lon);
+        self.tides = Some(Fetch::spawn(ctx, async move { get_tides(&Client::new(), lat, lon).await }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        let Some(tides) = &mut self.tides else {
+            ui.label("Tide predictions are only available for US locations.");
+            return;
+        };
+        tides.poll();
+
+        match tides {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load tides: {}", e));
+            }
+            Fetch::Ready(None) => {
+                ui.label("No tide station nearby.");
+            }
+            Fetch::Ready(Some(forecast)) => show_tides(ui, forecast),
+        }
+    }
+}
+
+fn show_tides(ui: &mut egui::Ui, forecast: &TideForecast) {
+    ui.label(format!(
+        "Tides at {} ({:.0} km away)",
+        forecast.station.name, forecast.distance_km
+    ));
+    egui::Grid::new("tides").striped(true).show(ui, |ui| {
+        for event in forecast.events.iter().take(TIDE_EVENTS_SHOWN) {
+            ui.label(if event.high { "High" } else { "Low" });
+            ui.label(time_format::weekday_time(event.time.with_timezone(&Local).naive_local()));
+            ui.label(format!("{:.1} ft", event.height_ft));
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,117 @@
+//! Optional data panels shown below the forecast.
+//!
+//! Each panel is a [`Panel`] behind its own cargo feature, so a new one only
+//! needs a module here, a feature in `Cargo.toml`, and an entry in
+//! [`Panels::registered`].
+
+#[cfg(feature = "air-quality")]
+mod air_quality;
+#[cfg(feature = "tides")]
+mod marine;
+#[cfg(feature = "earthquakes")]
+mod quake;
+#[cfg(feature = "radar")]
+mod radar;
+#[cfg(feature = "river-gauges")]
+mod river;
+#[cfg(feature = "spotter-reports")]
+mod spotter;
+#[cfg(feature = "webcams")]
+mod webcam;
+#[cfg(feature = "wildfires")]
+mod wildfire;
+
+use eframe::egui;
+use crate::config::Config;
+use crate::session;
+use crate::Location;
+
+pub trait Panel {
+    /// Header of the panel's collapsible section.
+    fn title(&self) -> &str;
+
+    /// Starts loading the panel's data for `location`. Called the first time
+    /// the panel is expanded, so collapsed panels never hit the network.
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location);
+
+    /// Draws the panel body. Called every frame while the panel is expanded.
+    fn render(&mut self, ui: &mut egui::Ui, location: &Location);
+
+    /// Whether the panel downloads enough (map tiles, images) to be turned
+    /// off in low data mode.
+    fn heavy(&self) -> bool {
+        false
+    }
+}
+
+struct Slot {
+    panel: Box<dyn Panel>,
+    fetched: bool,
+}
+
+/// The panels compiled into this build, in display order.
+pub struct Panels {
+    slots: Vec<Slot>,
+}
+
+impl Panels {
+    #[cfg_attr(
+        not(any(
+            feature = "earthquakes",
+            feature = "radar",
+            feature = "air-quality",
+            feature = "wildfires",
+            feature = "webcams",
+            feature = "spotter-reports"
+        )),
+        allow(unused_variables)
+    )]
+    pub fn registered(config: &Config) -> Self {
+        let panels: Vec<Box<dyn Panel>> = vec![
+            #[cfg(feature = "tides")]
+            Box::new(marine::MarinePanel::new()),
+            #[cfg(feature = "river-gauges")]
+            Box::new(river::RiverPanel::new()),
+            #[cfg(feature = "earthquakes")]
+            Box::new(quake::QuakePanel::new(config)),
+            #[cfg(feature = "radar")]
+            Box::new(radar::RadarPanel::new(config)),
+            #[cfg(feature = "air-quality")]
+            Box::new(air_quality::AirQualityPanel::new(config)),
+            #[cfg(feature = "wildfires")]
+            Box::new(wildfire::WildfirePanel::new(config)),
+            #[cfg(feature = "webcams")]
+            Box::new(webcam::WebcamPanel::new(config)),
+            #[cfg(feature = "spotter-reports")]
+            Box::new(spotter::SpotterPanel::new(config)),
+        ];
+
+        let slots = panels
+            .into_iter()
+            .map(|panel| Slot { panel, fetched: false })
+            .collect();
+        Self { slots }
+    }
+
+    /// In low data mode heavy panels stay empty, and the rest wait for a
+    /// click even if they were left open last time.
+    pub fn show(&mut self, ui: &mut egui::Ui, location: &Location, low_data: bool) {
+        for slot in &mut self.slots {
+            let title = slot.panel.title().to_owned();
+            session::card(ui, &title, false, |ui| {
+                if low_data && slot.panel.heavy() {
+                    ui.label("Off in low data mode.");
+                    return;
+                }
+                if !slot.fetched {
+                    if low_data && !ui.button("Load").on_hover_text("Low data mode is on").clicked() {
+                        return;
+                    }
+                    slot.fetched = true;
+                    slot.panel.fetch(ui.ctx(), location);
+                }
+                slot.panel.render(ui, location);
+            });
+        }
+    }
+}
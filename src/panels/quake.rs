@@ -0,0 +1,84 @@
+use chrono::Local;
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::earthquakes::{get_earthquakes, Earthquake, DEFAULT_MIN_MAGNITUDE, DEFAULT_RADIUS_KM};
+use crate::fetch::Fetch;
+use crate::time_format;
+use crate::Location;
+use super::Panel;
+
+// Quakes at or above this are drawn in the warning color.
+const STRONG_MAGNITUDE: f64 = 5.0;
+const STRONG_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 60, 60);
+
+/// Recent USGS earthquakes near the location.
+pub struct QuakePanel {
+    radius_km: f64,
+    min_magnitude: f64,
+    quakes: Option<Fetch<Vec<Earthquake>>>,
+}
+
+impl QuakePanel {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            radius_km: config.quake_radius_km().unwrap_or(DEFAULT_RADIUS_KM),
+            min_magnitude: config.quake_min_magnitude().unwrap_or(DEFAULT_MIN_MAGNITUDE),
+            quakes: None,
+        }
+    }
+
+}
+
+impl Panel for QuakePanel {
+    fn title(&self) -> &str {
+        "Earthquakes"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        let (lat, lon) = (location.lat, location.lon);
+        let (radius_km, min_magnitude) = (self.radius_km, self.min_magnitude);
+        self.quakes = Some(Fetch::spawn(ctx, async move {
+            get_earthquakes(&Client::new(), lat, lon, radius_km, min_magnitude).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        ui.label(format!(
+            "Past week, magnitude {:.1}+ within {:.0} km",
+            self.min_magnitude, self.radius_km
+        ));
+
+        let Some(quakes) = &mut self.quakes else { return };
+        quakes.poll();
+
+        match quakes {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load earthquakes: {}", e));
+            }
+            Fetch::Ready(quakes) if quakes.is_empty() => {
+                ui.label("No recent earthquakes nearby.");
+            }
+            Fetch::Ready(quakes) => show_quakes(ui, quakes),
+        }
+    }
+}
+
+fn show_quakes(ui: &mut egui::Ui, quakes: &[Earthquake]) {
+    egui::Grid::new("earthquakes").striped(true).show(ui, |ui| {
+        for quake in quakes {
+            let magnitude = format!("M{:.1}", quake.magnitude);
+            if quake.magnitude >= STRONG_MAGNITUDE {
+                ui.colored_label(STRONG_COLOR, magnitude);
+            } else {
+                ui.label(magnitude);
+            }
+            ui.label(&quake.place);
+            ui.label(time_format::weekday_time(quake.time.with_timezone(&Local).naive_local()));
+            ui.end_row();
+        }
+    });
+}
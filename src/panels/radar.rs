@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::radar_tiles::{get_tile, tile_position, TileId, TileLayer, TILE_SIZE, WEATHER_MAX_AGE};
+use crate::Location;
+use super::Panel;
+
+const DEFAULT_ZOOM: u8 = 7;
+const MIN_ZOOM: u8 = 3;
+const MAX_ZOOM: u8 = 10;
+// The map is this many tiles across and down.
+const TILES_ACROSS: f32 = 3.0;
+// Textures kept before the ones scrolled out of view are dropped.
+const MAX_TILES: usize = 150;
+const MARKER_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 40, 40);
+
+enum Tile {
+    Loading(Fetch<egui::ColorImage>),
+    Ready(egui::TextureHandle),
+    Failed,
+}
+
+/// OpenWeatherMap precipitation or cloud tiles over an OpenStreetMap base
+/// map, centered on the location.
+pub struct RadarPanel {
+    // Without one only the base map is shown.
+    api_key: Option<String>,
+    layer: TileLayer,
+    zoom: u8,
+    tiles: HashMap<(TileLayer, TileId), Tile>,
+    // When the weather tiles in `tiles` were first requested, to fetch
+    // newer ones once OpenWeatherMap has updated them.
+    weather_since: Instant,
+}
+
+impl RadarPanel {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            api_key: config.api_key(),
+            layer: TileLayer::Precipitation,
+            zoom: DEFAULT_ZOOM,
+            tiles: HashMap::new(),
+            weather_since: Instant::now(),
+        }
+    }
+
+    /// Forgets the weather tiles, and any tile that failed, so they are
+    /// fetched again.
+    fn reload(&mut self) {
+        self.tiles.retain(|(layer, _), tile| *layer == TileLayer::Base && !matches!(tile, Tile::Failed));
+        self.weather_since = Instant::now();
+    }
+
+    /// The texture for a tile, starting to fetch it if it isn't loaded.
+    fn tile(&mut self, ctx: &egui::Context, layer: TileLayer, tile: TileId, api_key: &str) -> Option<&egui::TextureHandle> {
+        let entry = self.tiles.entry((layer, tile)).or_insert_with(|| {
+            let api_key = api_key.to_string();
+            Tile::Loading(Fetch::spawn(ctx, async move { get_tile(&Client::new(), layer, tile, &api_key).await }))
+        });
+        if let Tile::Loading(fetch) = entry {
+            fetch.poll();
+            match fetch {
+                Fetch::Pending(_) => {}
+                Fetch::Failed(e) => {
+                    tracing::warn!("Unable to load map tile: {}", e);
+                    *entry = Tile::Failed;
+                }
+                Fetch::Ready(image) => {
+                    let name = format!("map_tile_{:?}_{}_{}_{}", layer, tile.zoom, tile.x, tile.y);
+                    let texture = ctx.load_texture(name, std::mem::take(image), egui::TextureOptions::LINEAR);
+                    *entry = Tile::Ready(texture);
+                }
+            }
+        }
+        match entry {
+            Tile::Ready(texture) => Some(texture),
+            Tile::Loading(_) | Tile::Failed => None,
+        }
+    }
+
+    fn show_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.layer, TileLayer::Precipitation, "Precipitation");
+            ui.selectable_value(&mut self.layer, TileLayer::Clouds, "Clouds");
+            ui.separator();
+            if ui.add_enabled(self.zoom > MIN_ZOOM, egui::Button::new("−")).on_hover_text("Zoom out").clicked() {
+                self.zoom -= 1;
+            }
+            ui.label(format!("Zoom {}", self.zoom));
+            if ui.add_enabled(self.zoom < MAX_ZOOM, egui::Button::new("+")).on_hover_text("Zoom in").clicked() {
+                self.zoom += 1;
+            }
+            if ui.button("Reload").on_hover_text("Fetch the latest weather tiles").clicked() {
+                self.reload();
+            }
+        });
+    }
+
+    fn show_map(&mut self, ui: &mut egui::Ui, location: &Location, api_key: Option<&str>) {
+        let tile_px = (ui.available_width() / TILES_ACROSS).min(TILE_SIZE);
+        let side = tile_px * TILES_ACROSS;
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(side, side), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let (x, y) = tile_position(location.lat, location.lon, self.zoom);
+        let count = 1i64 << self.zoom;
+        let half = f64::from(TILES_ACROSS) / 2.0;
+        let layers = [Some(TileLayer::Base), api_key.map(|_| self.layer)];
+        for layer in layers.into_iter().flatten() {
+            for row in (y - half).floor() as i64..=(y + half).floor() as i64 {
+                if !(0..count).contains(&row) {
+                    continue;
+                }
+                for column in (x - half).floor() as i64..=(x + half).floor() as i64 {
+                    // Longitude wraps around; latitude doesn't.
+                    let tile = TileId { zoom: self.zoom, x: column.rem_euclid(count) as u32, y: row as u32 };
+                    let Some(texture) = self.tile(ui.ctx(), layer, tile, api_key.unwrap_or_default()) else { continue };
+                    let min = rect.center()
+                        + egui::vec2((column as f64 - x) as f32 * tile_px, (row as f64 - y) as f32 * tile_px);
+                    let tile_rect = egui::Rect::from_min_size(min, egui::vec2(tile_px, tile_px));
+                    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                    painter.image(texture.id(), tile_rect, uv, egui::Color32::WHITE);
+                }
+            }
+        }
+        painter.circle(rect.center(), 5.0, MARKER_COLOR, egui::Stroke::new(1.5, egui::Color32::WHITE));
+        response.on_hover_text(&location.city);
+    }
+}
+
+impl Panel for RadarPanel {
+    fn title(&self) -> &str {
+        "Radar"
+    }
+
+    fn fetch(&mut self, _ctx: &egui::Context, _location: &Location) {
+        self.reload();
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, location: &Location) {
+        if self.weather_since.elapsed() > WEATHER_MAX_AGE {
+            self.reload();
+        }
+        if self.tiles.len() > MAX_TILES {
+            self.tiles.clear();
+        }
+        let api_key = self.api_key.clone();
+        self.show_controls(ui);
+        self.show_map(ui, location, api_key.as_deref());
+        if api_key.is_none() {
+            ui.label("Add an OpenWeatherMap API key in Settings to show precipitation and clouds.");
+        }
+        ui.small("Weather layers © OpenWeatherMap, map © OpenStreetMap contributors");
+    }
+
+    fn heavy(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,77 @@
+use eframe::egui;
+use reqwest::Client;
+use crate::fetch::Fetch;
+use crate::river_gauges::{get_river_gauges, RiverGauge};
+use crate::Location;
+use super::Panel;
+
+const FLOOD_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 60, 60);
+
+/// Nearby USGS stream gauges.
+pub struct RiverPanel {
+    gauges: Option<Fetch<Vec<RiverGauge>>>,
+}
+
+impl RiverPanel {
+    pub fn new() -> Self {
+        Self { gauges: None }
+    }
+}
+
+impl Panel for RiverPanel {
+    fn title(&self) -> &str {
+        "Rivers"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        // USGS water services only cover the US.
+        if location.country_code != "US" {
+            return;
+        }
+        let (lat, lon) = (location.lat, location.lon);
+        self.gauges = Some(Fetch::spawn(ctx, async move {
+            get_river_gauges(&Client::new(), lat, lon).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        let Some(gauges) = &mut self.gauges else {
+            ui.label("River gauges are only available for US locations.");
+            return;
+        };
+        gauges.poll();
+
+        match gauges {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load river gauges: {}", e));
+            }
+            Fetch::Ready(gauges) if gauges.is_empty() => {
+                ui.label("No river gauges nearby.");
+            }
+            Fetch::Ready(gauges) => show_gauges(ui, gauges),
+        }
+    }
+}
+
+fn show_gauges(ui: &mut egui::Ui, gauges: &[RiverGauge]) {
+    egui::Grid::new("river_gauges").striped(true).show(ui, |ui| {
+        for gauge in gauges {
+            ui.label(&gauge.site_name);
+            ui.label(format!("{:.0} km", gauge.distance_km));
+            let stage = format!("{:.2} ft", gauge.stage_ft);
+            if gauge.is_flooding() {
+                ui.colored_label(FLOOD_COLOR, stage);
+            } else {
+                ui.label(stage);
+            }
+            match gauge.flood_stage_ft {
+                Some(flood) => ui.label(format!("flood stage {:.1} ft", flood)),
+                None => ui.label("no flood stage"),
+            };
+            ui.end_row();
+        }
+    });
+}
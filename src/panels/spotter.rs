@@ -0,0 +1,171 @@
+use chrono::Local;
+use eframe::egui;
+use egui::plot::{Plot, Points};
+use reqwest::Client;
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::spotter_reports::{get_reports, submit_report, SpotterReport, REPORT_TYPES, SEARCH_RADIUS_KM};
+use crate::time_format;
+use crate::Location;
+use super::Panel;
+
+const REPORTS_LISTED: usize = 8;
+
+/// Public mPING ground reports near the location, with a way to add one.
+/// Needs an mPING API token.
+pub struct SpotterPanel {
+    token: Option<String>,
+    reports: Option<Fetch<Vec<SpotterReport>>>,
+    // Index into `REPORT_TYPES`.
+    report_type: usize,
+    submission: Option<Fetch<()>>,
+}
+
+impl SpotterPanel {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            token: config.mping_api_token(),
+            reports: None,
+            report_type: 0,
+            submission: None,
+        }
+    }
+
+    fn submit(&mut self, ctx: &egui::Context, location: &Location) {
+        let Some(token) = self.token.clone() else { return };
+        let (lat, lon) = (location.lat, location.lon);
+        let report_type = REPORT_TYPES[self.report_type];
+        self.submission = Some(Fetch::spawn(ctx, async move {
+            submit_report(&Client::new(), lat, lon, report_type, &token).await
+        }));
+    }
+
+    fn show_submit(&mut self, ui: &mut egui::Ui, location: &Location) {
+        ui.horizontal(|ui| {
+            ui.label("Report what you see here:");
+            let (category, description) = REPORT_TYPES[self.report_type];
+            egui::ComboBox::from_id_source("spotter_report_type")
+                .selected_text(format!("{}: {}", category, description))
+                .width(260.0)
+                .show_ui(ui, |ui| {
+                    for (i, (category, description)) in REPORT_TYPES.iter().enumerate() {
+                        ui.selectable_value(&mut self.report_type, i, format!("{}: {}", category, description));
+                    }
+                });
+            let sending = matches!(self.submission, Some(Fetch::Pending(_)));
+            if ui.add_enabled(!sending, egui::Button::new("Submit")).clicked() {
+                self.submit(ui.ctx(), location);
+            }
+        });
+
+        let Some(submission) = &mut self.submission else { return };
+        let was_pending = matches!(submission, Fetch::Pending(_));
+        submission.poll();
+        match submission {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to send the report: {}", e));
+            }
+            Fetch::Ready(()) => {
+                ui.label("Report sent. Thank you!");
+                // Reload so it shows up among the others.
+                if was_pending {
+                    self.fetch(ui.ctx(), location);
+                }
+            }
+        }
+    }
+}
+
+impl Panel for SpotterPanel {
+    fn title(&self) -> &str {
+        "Spotter reports"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        let Some(token) = self.token.clone() else { return };
+        let (lat, lon) = (location.lat, location.lon);
+        self.reports = Some(Fetch::spawn(ctx, async move {
+            get_reports(&Client::new(), lat, lon, &token).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, location: &Location) {
+        let Some(reports) = &mut self.reports else {
+            ui.label("Set MPING_API_TOKEN to show and send nearby mPING reports.");
+            return;
+        };
+        reports.poll();
+
+        match reports {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load reports: {}", e));
+            }
+            Fetch::Ready(reports) if reports.is_empty() => {
+                ui.label(format!("No reports within {:.0} km in the last 6 hours.", SEARCH_RADIUS_KM));
+            }
+            Fetch::Ready(reports) => show_reports(ui, reports, location),
+        }
+        ui.separator();
+        self.show_submit(ui, location);
+    }
+}
+
+fn category_color(category: &str) -> egui::Color32 {
+    match category {
+        "Rain/Snow" => egui::Color32::from_rgb(70, 130, 220),
+        "Hail" => egui::Color32::from_rgb(150, 90, 200),
+        "Wind Damage" => egui::Color32::from_rgb(220, 140, 40),
+        "Tornado" => egui::Color32::from_rgb(200, 50, 50),
+        "Flood" | "Mudslide" => egui::Color32::from_rgb(40, 160, 140),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+fn show_reports(ui: &mut egui::Ui, reports: &[SpotterReport], location: &Location) {
+    ui.label(format!("{} reports within {:.0} km in the last 6 hours", reports.len(), SEARCH_RADIUS_KM));
+    let hover_reports = reports.to_vec();
+    Plot::new("spotter_reports")
+        .height(220.0)
+        // Degrees of longitude shrink towards the poles.
+        .data_aspect((1.0 / location.lat.to_radians().cos().max(0.1)) as f32)
+        .label_formatter(move |_name, pointer| {
+            let nearest = hover_reports.iter().min_by(|a, b| {
+                let distance = |r: &SpotterReport| (r.lon - pointer.x).powi(2) + (r.lat - pointer.y).powi(2);
+                distance(a).total_cmp(&distance(b))
+            });
+            match nearest {
+                Some(report) => format!(
+                    "{}\n{}",
+                    report.description,
+                    time_format::weekday_time(report.observed.with_timezone(&Local).naive_local())
+                ),
+                None => String::new(),
+            }
+        })
+        .show(ui, |plot_ui| {
+            for report in reports {
+                plot_ui.points(
+                    Points::new(vec![[report.lon, report.lat]])
+                        .radius(5.0)
+                        .color(category_color(&report.category))
+                        .name(&report.category),
+                );
+            }
+            plot_ui.points(Points::new(vec![[location.lon, location.lat]]).radius(4.0).color(egui::Color32::BLACK).name(&location.city));
+        });
+
+    egui::Grid::new("spotter_reports_list").striped(true).show(ui, |ui| {
+        for report in reports.iter().take(REPORTS_LISTED) {
+            ui.colored_label(category_color(&report.category), &report.description);
+            ui.label(format!("{:.0} km away", report.distance_km));
+            ui.label(time_format::weekday_time(report.observed.with_timezone(&Local).naive_local()));
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,82 @@
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::webcams::{get_nearest_webcam, Webcam};
+use crate::Location;
+use super::Panel;
+
+const THUMBNAIL_WIDTH: f32 = 320.0;
+
+/// A live image from the closest Windy webcam. Needs a Windy API key.
+pub struct WebcamPanel {
+    api_key: Option<String>,
+    webcam: Option<Fetch<Option<Webcam>>>,
+    // Uploaded from the fetched image on first display.
+    texture: Option<(String, egui::TextureHandle)>,
+}
+
+impl WebcamPanel {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            api_key: config.windy_webcams_api_key(),
+            webcam: None,
+            texture: None,
+        }
+    }
+
+}
+
+impl Panel for WebcamPanel {
+    fn title(&self) -> &str {
+        "Webcam"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        let Some(api_key) = self.api_key.clone() else { return };
+        let (lat, lon) = (location.lat, location.lon);
+        self.webcam = Some(Fetch::spawn(ctx, async move {
+            get_nearest_webcam(&Client::new(), lat, lon, &api_key).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        if self.texture.is_none() {
+            let Some(webcam) = &mut self.webcam else {
+                ui.label("Set WINDY_WEBCAMS_API_KEY to show a nearby webcam.");
+                return;
+            };
+            webcam.poll();
+
+            match webcam {
+                Fetch::Pending(_) => {
+                    ui.spinner();
+                    return;
+                }
+                Fetch::Failed(e) => {
+                    ui.label(format!("Unable to load webcam: {}", e));
+                    return;
+                }
+                Fetch::Ready(None) => {
+                    ui.label("No webcams nearby.");
+                    return;
+                }
+                Fetch::Ready(Some(cam)) => {
+                    let image = std::mem::take(&mut cam.image);
+                    let texture = ui.ctx().load_texture("webcam", image, Default::default());
+                    self.texture = Some((cam.title.clone(), texture));
+                }
+            }
+        }
+
+        if let Some((title, texture)) = &self.texture {
+            ui.label(title);
+            let size = texture.size_vec2();
+            ui.image(texture, size * (THUMBNAIL_WIDTH / size.x));
+        }
+    }
+
+    fn heavy(&self) -> bool {
+        true
+    }
+}
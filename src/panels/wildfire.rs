@@ -0,0 +1,76 @@
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::wildfires::{get_hotspots, Hotspot, SEARCH_RADIUS_KM};
+use crate::Location;
+use super::Panel;
+
+const HOTSPOTS_SHOWN: usize = 5;
+
+/// Nearby satellite fire detections. Needs a FIRMS map key.
+pub struct WildfirePanel {
+    map_key: Option<String>,
+    hotspots: Option<Fetch<Vec<Hotspot>>>,
+}
+
+impl WildfirePanel {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            map_key: config.firms_map_key(),
+            hotspots: None,
+        }
+    }
+
+}
+
+impl Panel for WildfirePanel {
+    fn title(&self) -> &str {
+        "Wildfires"
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context, location: &Location) {
+        let Some(map_key) = self.map_key.clone() else { return };
+        let (lat, lon) = (location.lat, location.lon);
+        self.hotspots = Some(Fetch::spawn(ctx, async move {
+            get_hotspots(&Client::new(), lat, lon, &map_key).await
+        }));
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui, _location: &Location) {
+        let Some(hotspots) = &mut self.hotspots else {
+            ui.label("Set FIRMS_MAP_KEY to show nearby fire detections.");
+            return;
+        };
+        hotspots.poll();
+
+        match hotspots {
+            Fetch::Pending(_) => {
+                ui.spinner();
+            }
+            Fetch::Failed(e) => {
+                ui.label(format!("Unable to load fire detections: {}", e));
+            }
+            Fetch::Ready(hotspots) if hotspots.is_empty() => {
+                ui.label(format!("No fires detected within {:.0} km.", SEARCH_RADIUS_KM));
+            }
+            Fetch::Ready(hotspots) => show_hotspots(ui, hotspots),
+        }
+    }
+}
+
+fn show_hotspots(ui: &mut egui::Ui, hotspots: &[Hotspot]) {
+    ui.label(format!(
+        "{} fire detections within {:.0} km in the last 2 days",
+        hotspots.len(),
+        SEARCH_RADIUS_KM
+    ));
+    egui::Grid::new("wildfire_hotspots").striped(true).show(ui, |ui| {
+        for hotspot in hotspots.iter().take(HOTSPOTS_SHOWN) {
+            ui.label(format!("{:.0} km away", hotspot.distance_km));
+            ui.label(&hotspot.acquired);
+            ui.label(format!("{:.1} MW", hotspot.frp));
+            ui.end_row();
+        }
+    });
+}
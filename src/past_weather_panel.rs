@@ -0,0 +1,146 @@
+use chrono::{Local, TimeZone};
+use eframe::egui;
+use egui::plot::{Bar, BarChart, Line, Plot, PlotPoints};
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::i18n;
+use crate::session;
+use crate::timemachine::{fetch_past, PastHour};
+use crate::units::Units;
+use crate::Location;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Range {
+    Day,
+    Week,
+}
+
+impl Range {
+    /// Hours covered and hours between readings; the week is sampled every
+    /// three hours to keep it to 56 requests.
+    fn span(self) -> (i64, i64) {
+        match self {
+            Range::Day => (24, 1),
+            Range::Week => (7 * 24, 3),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Range::Day => "Past 24h",
+            Range::Week => "Past 7d",
+        }
+    }
+
+    fn axis_format(self) -> &'static str {
+        match self {
+            Range::Day => "%Hh",
+            Range::Week => "%a %Hh",
+        }
+    }
+}
+
+/// Temperature and precipitation over the past day or week, from the One
+/// Call timemachine endpoint or Open-Meteo. Nothing is requested until it is
+/// expanded.
+pub struct PastWeatherPanel {
+    api_key: Option<String>,
+    range: Range,
+    day: Option<Fetch<Vec<PastHour>>>,
+    week: Option<Fetch<Vec<PastHour>>>,
+}
+
+impl PastWeatherPanel {
+    pub fn new(config: &Config) -> Self {
+        Self { api_key: config.api_key(), range: Range::Day, day: None, week: None }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, location: &Location, units: Units, low_data: bool) {
+        session::card(ui, "Past weather", false, |ui| {
+            ui.horizontal(|ui| {
+                for range in [Range::Day, Range::Week] {
+                    ui.selectable_value(&mut self.range, range, range.label());
+                }
+            });
+
+            let range = self.range;
+            let slot = match range {
+                Range::Day => &mut self.day,
+                Range::Week => &mut self.week,
+            };
+            let (lat, lon) = (location.lat, location.lon);
+            let (hours, step) = range.span();
+            if slot.is_none() && low_data && !ui.button("Load").on_hover_text("Low data mode is on").clicked() {
+                return;
+            }
+            let api_key = self.api_key.clone();
+            let history =
+                slot.get_or_insert_with(|| Fetch::spawn(ui.ctx(), fetch_past(api_key, lat, lon, hours, step)));
+            history.poll();
+            match history {
+                Fetch::Pending(_) => {
+                    ui.spinner();
+                }
+                Fetch::Failed(e) => {
+                    ui.label(format!("Unable to load past weather: {}", e));
+                }
+                Fetch::Ready(past) if past.is_empty() => {
+                    ui.label("No past weather available.");
+                }
+                Fetch::Ready(past) => show_charts(ui, past, range, units),
+            }
+        });
+    }
+}
+
+fn show_charts(ui: &mut egui::Ui, past: &[PastHour], range: Range, units: Units) {
+    let total: f64 = past.iter().map(|hour| hour.precipitation).sum::<f64>() * range.span().1 as f64;
+    let (low, high) = past.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), hour| {
+        (low.min(hour.temp), high.max(hour.temp))
+    });
+    ui.label(format!(
+        "Low {}, high {}, about {} of precipitation",
+        units.format_temperature(low, 0),
+        units.format_temperature(high, 0),
+        units.format_precipitation(total)
+    ));
+
+    let format = range.axis_format();
+    let axis = move |x: f64, _range: &std::ops::RangeInclusive<f64>| {
+        Local
+            .timestamp_opt(x as i64, 0)
+            .single()
+            .map(|t| i18n::format_datetime(&t, format))
+            .unwrap_or_default()
+    };
+    let link = egui::Id::new("past_weather_axis");
+
+    let temps: PlotPoints = past.iter().map(|hour| [hour.dt as f64, units.temperature(hour.temp)]).collect();
+    Plot::new("past_temperature")
+        .height(140.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .link_axis(link, true, false)
+        .x_axis_formatter(axis)
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(temps).name(format!("Temperature ({})", units.temperature_unit())));
+        });
+
+    let width = (range.span().1 * 3600) as f64 * 0.8;
+    let bars: Vec<Bar> = past
+        .iter()
+        .map(|hour| Bar::new(hour.dt as f64, units.precipitation(hour.precipitation)).width(width))
+        .collect();
+    Plot::new("past_precipitation")
+        .height(80.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .link_axis(link, true, false)
+        .x_axis_formatter(axis)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).name(format!("Precipitation ({}/h)", units.precipitation.symbol())));
+        });
+}
@@ -0,0 +1,170 @@
+use std::time::{Duration, Instant};
+use eframe::egui;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::config::Config;
+use crate::degrees_to_cardinal;
+use crate::error::WeatherError;
+use crate::fetch::Fetch;
+use crate::units::Units;
+
+// Local stations update every minute or so, and polling the LAN is cheap.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Ecowitt gateway sensor ids in `common_list`.
+const OUTDOOR_TEMP: &str = "0x02";
+const OUTDOOR_HUMIDITY: &str = "0x07";
+const WIND_DIRECTION: &str = "0x0A";
+const WIND_SPEED: &str = "0x0B";
+const WIND_GUST: &str = "0x0C";
+
+#[derive(Debug, Deserialize)]
+struct LiveData {
+    #[serde(default)]
+    common_list: Vec<Sensor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sensor {
+    id: String,
+    val: String,
+    unit: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct StationReading {
+    pub temp_f: Option<f64>,
+    pub humidity: Option<f64>,
+    pub wind_mph: Option<f64>,
+    pub gust_mph: Option<f64>,
+    pub wind_deg: Option<u16>,
+}
+
+/// Reads the live data page of an Ecowitt gateway (GW1000/GW2000 and similar)
+/// on the local network.
+pub async fn get_ecowitt_reading(client: &Client, host: &str) -> Result<StationReading, WeatherError> {
+    let res = client
+        .get(format!("http://{}/get_livedata_info", host))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    let live: LiveData = res.json().await?;
+
+    let mut reading = StationReading::default();
+    for sensor in live.common_list {
+        // Values come as strings such as "61.2", "45%" or "3.4 mph".
+        let (value, inline_unit) = split_value(&sensor.val);
+        let Some(value) = value else { continue };
+        let unit = sensor.unit.as_deref().unwrap_or(inline_unit);
+        match sensor.id.as_str() {
+            OUTDOOR_TEMP => reading.temp_f = Some(to_fahrenheit(value, unit)),
+            OUTDOOR_HUMIDITY => reading.humidity = Some(value),
+            WIND_SPEED => reading.wind_mph = Some(to_mph(value, unit)),
+            WIND_GUST => reading.gust_mph = Some(to_mph(value, unit)),
+            WIND_DIRECTION => reading.wind_deg = Some(value.round() as u16 % 360),
+            _ => {}
+        }
+    }
+    Ok(reading)
+}
+
+fn split_value(raw: &str) -> (Option<f64>, &str) {
+    let raw = raw.trim();
+    let end = raw
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(raw.len());
+    (raw[..end].parse().ok(), raw[end..].trim())
+}
+
+fn to_fahrenheit(value: f64, unit: &str) -> f64 {
+    if unit.trim_start_matches('°').eq_ignore_ascii_case("C") {
+        value * 9.0 / 5.0 + 32.0
+    } else {
+        value
+    }
+}
+
+fn to_mph(value: f64, unit: &str) -> f64 {
+    match unit.to_ascii_lowercase().as_str() {
+        "m/s" => value * 2.23694,
+        "km/h" => value * 0.621371,
+        "knots" | "kn" => value * 1.15078,
+        _ => value,
+    }
+}
+
+/// Backyard readings from a personal station, shown alongside the forecast.
+pub struct PersonalStation {
+    host: Option<String>,
+    reading: Option<Fetch<StationReading>>,
+    last_poll: Option<Instant>,
+    // Kept across polls so the line doesn't flicker while a new reading loads.
+    latest: Option<StationReading>,
+}
+
+impl PersonalStation {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            host: config.pws_ecowitt_host(),
+            reading: None,
+            last_poll: None,
+            latest: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, units: Units) {
+        let Some(host) = self.host.clone() else { return };
+
+        if self.last_poll.is_none_or(|t| t.elapsed() >= POLL_INTERVAL) {
+            self.last_poll = Some(Instant::now());
+            self.reading = Some(Fetch::spawn(ui.ctx(), async move {
+                get_ecowitt_reading(&Client::new(), &host).await
+            }));
+        }
+        if let Some(reading) = &mut self.reading {
+            reading.poll();
+            match reading {
+                Fetch::Pending(_) => {}
+                Fetch::Ready(_) => {
+                    if let Some(Fetch::Ready(latest)) = self.reading.take() {
+                        self.latest = Some(latest);
+                    }
+                }
+                Fetch::Failed(e) => {
+                    tracing::warn!("Personal weather station unavailable: {}", e);
+                    self.reading = None;
+                }
+            }
+        }
+        // Wake up for the next poll even if nothing else repaints.
+        ui.ctx().request_repaint_after(POLL_INTERVAL);
+
+        if let Some(latest) = &self.latest {
+            ui.label(format_reading(latest, units));
+        }
+    }
+}
+
+fn format_reading(reading: &StationReading, units: Units) -> String {
+    let mut parts = Vec::new();
+    if let Some(temp) = reading.temp_f {
+        parts.push(units.format_temperature(temp, 1));
+    }
+    if let Some(humidity) = reading.humidity {
+        parts.push(format!("{:.0}% humidity", humidity));
+    }
+    if let Some(wind) = reading.wind_mph {
+        let mut text = format!("wind {}", units.format_speed(wind, 1));
+        if let Some(gust) = reading.gust_mph {
+            text.push_str(&format!(" (gust {:.1})", units.speed(gust)));
+        }
+        if let Some(deg) = reading.wind_deg {
+            text.push(' ');
+            text.push_str(degrees_to_cardinal(deg));
+        }
+        parts.push(text);
+    }
+    format!("Backyard station: {}", parts.join(", "))
+}
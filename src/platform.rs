@@ -0,0 +1,130 @@
+//! Where files live and how to tell the user something went wrong, per
+//! operating system. Paths follow each platform's conventions, e.g.
+//! `~/.config/weather_alerts` on Linux and the roaming AppData folder on Windows.
+
+use std::path::PathBuf;
+use directories::ProjectDirs;
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "weather_alerts")
+}
+
+/// Settings and saved locations.
+pub fn config_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Data that can be fetched again if it's lost.
+pub fn cache_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// Files written for other programs to read, such as the summary files.
+pub fn data_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.data_local_dir().to_path_buf())
+}
+
+/// Logs, where the platform sets a place aside for them (Linux only).
+pub fn state_dir() -> Option<PathBuf> {
+    project_dirs()?.state_dir().map(|dir| dir.to_path_buf())
+}
+
+/// Latitude, longitude and accuracy in metres from the operating system's
+/// location services, by way of a command-line shim: Windows Geolocation
+/// through PowerShell, Core Location through `CoreLocationCLI` on macOS, and
+/// GeoClue's `where-am-i` elsewhere. Blocks for up to a few seconds; `None`
+/// when the service is unavailable or the user hasn't allowed it.
+#[cfg(windows)]
+pub fn system_location() -> Option<(f64, f64, Option<f64>)> {
+    const SCRIPT: &str = "Add-Type -AssemblyName System.Device; \
+        $w = New-Object System.Device.Location.GeoCoordinateWatcher; \
+        if ($w.TryStart($true, [TimeSpan]::FromSeconds(5))) { \
+            $c = $w.Position.Location; \
+            if (-not $c.IsUnknown) { \
+                [string]::Format([Globalization.CultureInfo]::InvariantCulture, '{0} {1} {2}', \
+                    $c.Latitude, $c.Longitude, $c.HorizontalAccuracy) } }";
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .output()
+        .ok()?;
+    parse_coordinates(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_location() -> Option<(f64, f64, Option<f64>)> {
+    let output = std::process::Command::new("CoreLocationCLI")
+        .args(["--format", "%latitude %longitude %h_accuracy"])
+        .output()
+        .ok()?;
+    parse_coordinates(&String::from_utf8_lossy(&output.stdout))
+}
+
+// The demo agent ships with GeoClue under libexec or lib depending on the
+// distribution.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn system_location() -> Option<(f64, f64, Option<f64>)> {
+    let output = ["/usr/libexec/geoclue-2.0/demos/where-am-i", "/usr/lib/geoclue-2.0/demos/where-am-i"]
+        .iter()
+        .find_map(|path| std::process::Command::new(path).args(["-t", "5"]).output().ok())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // "Latitude:    52.520008°", "Accuracy:    25000.000000 meters"
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .and_then(|value| value.trim().trim_end_matches(" meters").trim_end_matches('°').parse::<f64>().ok())
+    };
+    Some((field("Latitude:")?, field("Longitude:")?, field("Accuracy:")))
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn system_location() -> Option<(f64, f64, Option<f64>)> {
+    None
+}
+
+/// "lat lon accuracy", as printed by the Windows and macOS shims.
+#[cfg(any(windows, target_os = "macos"))]
+fn parse_coordinates(text: &str) -> Option<(f64, f64, Option<f64>)> {
+    let mut values = text.split_whitespace().map(|v| v.parse::<f64>().ok());
+    Some((values.next()??, values.next()??, values.next().flatten()))
+}
+
+/// Reports an error that stops the app from starting. Launched from a desktop
+/// shortcut there is no terminal to read stderr from, so a native dialog is
+/// shown as well where one is available.
+#[cfg(feature = "gui")]
+pub fn show_error(title: &str, message: &str) {
+    tracing::error!("{}: {}", title, message);
+    error_dialog(title, message);
+}
+
+#[cfg(all(feature = "gui", windows))]
+fn error_dialog(title: &str, message: &str) {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONERROR, MB_OK};
+    let wide = |s: &str| s.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let (title, message) = (wide(title), wide(message));
+    // SAFETY: both strings are NUL-terminated and outlive the call.
+    unsafe {
+        MessageBoxW(0, message.as_ptr(), title.as_ptr(), MB_OK | MB_ICONERROR);
+    }
+}
+
+#[cfg(all(feature = "gui", target_os = "macos"))]
+fn error_dialog(title: &str, message: &str) {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!("display alert {} message {} as critical", quote(title), quote(message));
+    let _ = std::process::Command::new("osascript").args(["-e", &script]).status();
+}
+
+// zenity on GNOME and most others, kdialog on KDE; with neither installed the
+// message has already gone to stderr.
+#[cfg(all(feature = "gui", unix, not(target_os = "macos")))]
+fn error_dialog(title: &str, message: &str) {
+    use std::process::Command;
+    let shown = Command::new("zenity")
+        .args(["--error", "--title", title, "--text", message])
+        .status()
+        .or_else(|_| Command::new("kdialog").args(["--title", title, "--error", message]).status());
+    if let Err(e) = shown {
+        tracing::warn!("No zenity or kdialog to show the error with: {}", e);
+    }
+}
@@ -0,0 +1,84 @@
+use eframe::egui;
+use reqwest::Client;
+use crate::fetch::Fetch;
+use crate::metrics::station_pressure;
+use crate::open_meteo::get_elevation;
+use crate::session;
+use crate::units::{PrecipitationUnit, PressureUnit, Units};
+use crate::{Current, Location};
+
+const FEET_PER_METRE: f64 = 3.280_84;
+
+/// Sea-level pressure as reported, plus the pressure at the location's
+/// elevation, which is what a barometer there actually reads.
+pub struct PressurePanel {
+    elevation: Option<Fetch<f64>>,
+    // Metres; set when the user corrects the looked-up elevation.
+    elevation_override: Option<f64>,
+}
+
+impl PressurePanel {
+    pub fn new() -> Self {
+        Self { elevation: None, elevation_override: None }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        location: &Location,
+        current: &Current,
+        units: Units,
+        pressure_unit: &mut PressureUnit,
+    ) {
+        let Some(sea_level) = current.pressure else { return };
+        session::card(ui, "Pressure", false, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Sea level: {}", pressure_unit.format(sea_level)));
+                egui::ComboBox::from_id_source("pressure_unit")
+                    .selected_text(pressure_unit.label())
+                    .show_ui(ui, |ui| {
+                        for option in PressureUnit::ALL {
+                            ui.selectable_value(pressure_unit, option, option.label());
+                        }
+                    });
+            });
+
+            let (lat, lon) = (location.lat, location.lon);
+            let elevation = self.elevation.get_or_insert_with(|| {
+                Fetch::spawn(ui.ctx(), async move { get_elevation(&Client::new(), lat, lon).await })
+            });
+            elevation.poll();
+            let looked_up = match elevation {
+                Fetch::Ready(metres) => Some(*metres),
+                _ => None,
+            };
+
+            let Some(metres) = self.elevation_override.or(looked_up) else {
+                match elevation {
+                    Fetch::Failed(e) => ui.small(format!("Elevation lookup failed: {}", e)),
+                    _ => ui.small("Looking up elevation..."),
+                };
+                return;
+            };
+            ui.horizontal(|ui| {
+                // Feet alongside inches of rain, metres otherwise.
+                let (factor, suffix) = match units.precipitation {
+                    PrecipitationUnit::Inches => (FEET_PER_METRE, " ft"),
+                    PrecipitationUnit::Mm => (1.0, " m"),
+                };
+                let mut shown = metres * factor;
+                let pressure = station_pressure(sea_level, metres, current.temp);
+                ui.label(format!("Station: {} at", pressure_unit.format(pressure)));
+                let response = ui
+                    .add(egui::DragValue::new(&mut shown).suffix(suffix).speed(5.0).max_decimals(0))
+                    .on_hover_text("Elevation; drag or type to correct it");
+                if response.changed() {
+                    self.elevation_override = Some(shown / factor);
+                }
+                if self.elevation_override.is_some() && looked_up.is_some() && ui.small_button("Reset").clicked() {
+                    self.elevation_override = None;
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,228 @@
+//! Where forecasts come from. OpenWeatherMap's One Call API needs an API key
+//! (with a subscription for 3.0); Open-Meteo needs none, so the app still
+//! works without a key.
+
+use std::future::Future;
+use reqwest::Client;
+#[cfg(feature = "gui")]
+use serde::Deserialize;
+use crate::alerts::Alert;
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::open_meteo::OpenMeteo;
+#[cfg(feature = "gui")]
+use crate::recording;
+use crate::{get_coordinates, get_weather_data, Current, Daily, Hourly, SchemaCheck, WeatherResponse};
+
+#[cfg(feature = "gui")]
+const OWM_GEOCODING_URL: &str = "https://api.openweathermap.org/geo/1.0/direct";
+
+/// One result of a place search.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+pub struct PlaceMatch {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub country_code: String,
+    /// State or province, when the backend has one.
+    pub region: Option<String>,
+}
+
+#[cfg(feature = "gui")]
+impl PlaceMatch {
+    // "Springfield, Illinois, US" tells the many Springfields apart.
+    pub fn describe(&self) -> String {
+        match &self.region {
+            Some(region) => format!("{}, {}, {}", self.name, region, self.country_code),
+            None => format!("{}, {}", self.name, self.country_code),
+        }
+    }
+}
+
+/// A forecast backend. Temperatures are °F and speeds mph whatever the
+/// backend, since display units are applied later.
+pub trait WeatherProvider: Sync {
+    /// Coordinates for a city, optionally narrowed down by ISO country code.
+    fn geocode(
+        &self,
+        city: &str,
+        country_code: &str,
+    ) -> impl Future<Output = Result<(f64, f64), WeatherError>> + Send;
+
+    /// Up to `limit` places matching free text, best first.
+    #[cfg(feature = "gui")]
+    fn search(&self, query: &str, limit: u8) -> impl Future<Output = Result<Vec<PlaceMatch>, WeatherError>> + Send;
+
+    fn fetch_current(&self, lat: f64, lon: f64) -> impl Future<Output = Result<Current, WeatherError>> + Send;
+
+    /// The next 48 hours.
+    fn fetch_hourly(&self, lat: f64, lon: f64) -> impl Future<Output = Result<Vec<Hourly>, WeatherError>> + Send;
+
+    /// Today first, then the days after.
+    fn fetch_daily(&self, lat: f64, lon: f64) -> impl Future<Output = Result<Vec<Daily>, WeatherError>> + Send;
+
+    /// Alerts currently in effect; empty when the backend has none to offer.
+    fn fetch_alerts(&self, lat: f64, lon: f64) -> impl Future<Output = Result<Vec<Alert>, WeatherError>> + Send;
+
+    /// Everything at once. Backends that answer with a single request
+    /// override this.
+    fn fetch_forecast(&self, lat: f64, lon: f64) -> impl Future<Output = Result<WeatherResponse, WeatherError>> + Send {
+        async move {
+            let current = self.fetch_current(lat, lon).await?;
+            let hourly = self.fetch_hourly(lat, lon).await?;
+            let daily = self.fetch_daily(lat, lon).await?;
+            let alerts = self.fetch_alerts(lat, lon).await?;
+            Ok(WeatherResponse { current, minutely: Vec::new(), hourly, daily, alerts })
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Deserialize)]
+struct OwmPlace {
+    name: String,
+    lat: f64,
+    lon: f64,
+    #[serde(default)]
+    country: String,
+    state: Option<String>,
+}
+
+/// OpenWeatherMap One Call 3.0 plus its geocoding API.
+pub struct OpenWeatherMap {
+    client: Client,
+    api_key: String,
+    schema_check: SchemaCheck,
+}
+
+impl OpenWeatherMap {
+    fn new(api_key: String, schema_check: SchemaCheck) -> Self {
+        Self { client: Client::new(), api_key, schema_check }
+    }
+}
+
+// One Call bills per request and answers with every section, so the
+// individual sections are cut from a full response; `fetch_forecast` is what
+// the app uses.
+impl WeatherProvider for OpenWeatherMap {
+    async fn geocode(&self, city: &str, country_code: &str) -> Result<(f64, f64), WeatherError> {
+        get_coordinates(&self.client, city, country_code, &self.api_key).await
+    }
+
+    #[cfg(feature = "gui")]
+    async fn search(&self, query: &str, limit: u8) -> Result<Vec<PlaceMatch>, WeatherError> {
+        let request = self.client.get(OWM_GEOCODING_URL).query(&[
+            ("q", query),
+            ("limit", &limit.to_string()),
+            ("appid", &self.api_key),
+        ]);
+        let places: Vec<OwmPlace> = serde_json::from_str(&recording::send("openweathermap-search", request).await?)?;
+        Ok(places
+            .into_iter()
+            .map(|place| PlaceMatch {
+                name: place.name,
+                lat: place.lat,
+                lon: place.lon,
+                country_code: place.country,
+                region: place.state,
+            })
+            .collect())
+    }
+
+    async fn fetch_current(&self, lat: f64, lon: f64) -> Result<Current, WeatherError> {
+        Ok(self.fetch_forecast(lat, lon).await?.current)
+    }
+
+    async fn fetch_hourly(&self, lat: f64, lon: f64) -> Result<Vec<Hourly>, WeatherError> {
+        Ok(self.fetch_forecast(lat, lon).await?.hourly)
+    }
+
+    async fn fetch_daily(&self, lat: f64, lon: f64) -> Result<Vec<Daily>, WeatherError> {
+        Ok(self.fetch_forecast(lat, lon).await?.daily)
+    }
+
+    async fn fetch_alerts(&self, lat: f64, lon: f64) -> Result<Vec<Alert>, WeatherError> {
+        Ok(self.fetch_forecast(lat, lon).await?.alerts)
+    }
+
+    async fn fetch_forecast(&self, lat: f64, lon: f64) -> Result<WeatherResponse, WeatherError> {
+        get_weather_data(&self.client, lat, lon, &self.api_key, self.schema_check).await
+    }
+}
+
+/// The backend picked by `WEATHER_PROVIDER` or the settings window.
+pub enum Provider {
+    OpenWeatherMap(OpenWeatherMap),
+    OpenMeteo(OpenMeteo),
+}
+
+impl Provider {
+    /// The provider is `openweathermap` or `open-meteo`. Unset, it is
+    /// OpenWeatherMap when there is an API key and Open-Meteo otherwise.
+    pub fn from_config(config: &Config) -> Result<Self, WeatherError> {
+        match (config.provider().as_deref(), config.api_key()) {
+            (Some("open-meteo"), _) | (None, None) => Ok(Provider::OpenMeteo(OpenMeteo::new())),
+            (Some("openweathermap"), Some(key)) | (None, Some(key)) => {
+                Ok(Provider::OpenWeatherMap(OpenWeatherMap::new(key, SchemaCheck::from_config(config))))
+            }
+            (Some("openweathermap"), None) => Err(WeatherError::MissingApiKey),
+            (Some(other), _) => Err(WeatherError::InvalidSetting(format!(
+                "Unknown WEATHER_PROVIDER {:?}; use openweathermap or open-meteo",
+                other
+            ))),
+        }
+    }
+}
+
+impl WeatherProvider for Provider {
+    async fn geocode(&self, city: &str, country_code: &str) -> Result<(f64, f64), WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.geocode(city, country_code).await,
+            Provider::OpenMeteo(p) => p.geocode(city, country_code).await,
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    async fn search(&self, query: &str, limit: u8) -> Result<Vec<PlaceMatch>, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.search(query, limit).await,
+            Provider::OpenMeteo(p) => p.search(query, limit).await,
+        }
+    }
+
+    async fn fetch_current(&self, lat: f64, lon: f64) -> Result<Current, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.fetch_current(lat, lon).await,
+            Provider::OpenMeteo(p) => p.fetch_current(lat, lon).await,
+        }
+    }
+
+    async fn fetch_hourly(&self, lat: f64, lon: f64) -> Result<Vec<Hourly>, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.fetch_hourly(lat, lon).await,
+            Provider::OpenMeteo(p) => p.fetch_hourly(lat, lon).await,
+        }
+    }
+
+    async fn fetch_daily(&self, lat: f64, lon: f64) -> Result<Vec<Daily>, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.fetch_daily(lat, lon).await,
+            Provider::OpenMeteo(p) => p.fetch_daily(lat, lon).await,
+        }
+    }
+
+    async fn fetch_alerts(&self, lat: f64, lon: f64) -> Result<Vec<Alert>, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.fetch_alerts(lat, lon).await,
+            Provider::OpenMeteo(p) => p.fetch_alerts(lat, lon).await,
+        }
+    }
+
+    async fn fetch_forecast(&self, lat: f64, lon: f64) -> Result<WeatherResponse, WeatherError> {
+        match self {
+            Provider::OpenWeatherMap(p) => p.fetch_forecast(lat, lon).await,
+            Provider::OpenMeteo(p) => p.fetch_forecast(lat, lon).await,
+        }
+    }
+}
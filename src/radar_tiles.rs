@@ -0,0 +1,113 @@
+use std::f64::consts::PI;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use eframe::egui::ColorImage;
+use reqwest::Client;
+use crate::error::WeatherError;
+use crate::platform;
+
+const OWM_TILE_URL: &str = "https://tile.openweathermap.org/map";
+const OSM_TILE_URL: &str = "https://tile.openstreetmap.org";
+// The OpenStreetMap tile servers refuse requests without one.
+const USER_AGENT: &str = "weather_alerts (https://github.com/jargray85/weather_alerts)";
+pub const TILE_SIZE: f32 = 256.0;
+// OpenWeatherMap updates its layers about every ten minutes; the base map
+// hardly changes.
+pub const WEATHER_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+const BASE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What a tile shows. The weather layers are transparent and drawn over
+/// the base map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileLayer {
+    Base,
+    Precipitation,
+    Clouds,
+}
+
+impl TileLayer {
+    fn name(self) -> &'static str {
+        match self {
+            TileLayer::Base => "osm",
+            TileLayer::Precipitation => "precipitation_new",
+            TileLayer::Clouds => "clouds_new",
+        }
+    }
+
+    fn max_age(self) -> Duration {
+        match self {
+            TileLayer::Base => BASE_MAX_AGE,
+            TileLayer::Precipitation | TileLayer::Clouds => WEATHER_MAX_AGE,
+        }
+    }
+}
+
+/// A slippy-map tile: zoom level and column and row at that zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub zoom: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Where the coordinates fall in Web Mercator tile units at `zoom`, e.g.
+/// (3.5, 2.25) for a quarter of the way down the middle of tile (3, 2).
+pub fn tile_position(lat: f64, lon: f64, zoom: u8) -> (f64, f64) {
+    let n = f64::from(1u32 << zoom);
+    // Mercator runs off to infinity at the poles.
+    let lat = lat.clamp(-85.05, 85.05).to_radians();
+    let x = (lon + 180.0) / 360.0 * n;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+    (x, y)
+}
+
+fn cache_path(layer: TileLayer, tile: TileId) -> Option<PathBuf> {
+    platform::cache_dir().map(|dir| {
+        dir.join("tiles").join(layer.name()).join(tile.zoom.to_string()).join(format!("{}_{}.png", tile.x, tile.y))
+    })
+}
+
+fn cached(layer: TileLayer, tile: TileId) -> Option<Vec<u8>> {
+    let path = cache_path(layer, tile)?;
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().unwrap_or_default();
+    if age > layer.max_age() {
+        return None;
+    }
+    fs::read(path).ok()
+}
+
+fn store(layer: TileLayer, tile: TileId, bytes: &[u8]) {
+    let Some(path) = cache_path(layer, tile) else { return };
+    let result = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, bytes));
+    if let Err(e) = result {
+        tracing::warn!("Unable to cache map tile at {}: {}", path.display(), e);
+    }
+}
+
+/// One tile of `layer`, from the disk cache while it is fresh. Weather
+/// layers need an OpenWeatherMap key.
+pub async fn get_tile(
+    client: &Client,
+    layer: TileLayer,
+    tile: TileId,
+    api_key: &str,
+) -> Result<ColorImage, WeatherError> {
+    let bytes = match cached(layer, tile) {
+        Some(bytes) => bytes,
+        None => {
+            let url = match layer {
+                TileLayer::Base => format!("{}/{}/{}/{}.png", OSM_TILE_URL, tile.zoom, tile.x, tile.y),
+                _ => format!("{}/{}/{}/{}/{}.png?appid={}", OWM_TILE_URL, layer.name(), tile.zoom, tile.x, tile.y, api_key),
+            };
+            let res = client.get(&url).header(reqwest::header::USER_AGENT, USER_AGENT).send().await?;
+            let bytes = res.error_for_status()?.bytes().await?.to_vec();
+            store(layer, tile, &bytes);
+            bytes
+        }
+    };
+    let decoded = image::load_from_memory(&bytes)?.to_rgba8();
+    let size = [decoded.width() as usize, decoded.height() as usize];
+    Ok(ColorImage::from_rgba_unmultiplied(size, decoded.as_flat_samples().as_slice()))
+}
+
@@ -0,0 +1,184 @@
+//! `--record <dir>` saves every raw response the forecast pipeline gets from
+//! upstream, and `--replay <dir>` answers the same requests from those files
+//! instead of the network, so a user's parsing problem can be reproduced
+//! from what they recorded.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use crate::error::WeatherError;
+
+// Query parameters holding keys, blanked in recorded URLs so the files can
+// be shared.
+const SECRET_PARAMS: &[&str] = &["appid", "key", "apikey", "api_key", "token"];
+
+enum Mode {
+    Record(PathBuf),
+    // Responses by request name, oldest first. The last one is kept and
+    // answered again, so a replaying app can keep refreshing.
+    Replay { dir: PathBuf, responses: Mutex<HashMap<String, VecDeque<Exchange>>> },
+}
+
+static MODE: OnceLock<Mode> = OnceLock::new();
+
+/// One request and what came back, as saved to `<time>-<name>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    name: String,
+    recorded_at: DateTime<Utc>,
+    url: String,
+    status: u16,
+    body: String,
+}
+
+/// Saves responses to `dir` from now on, creating it if need be.
+pub fn record_to(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let _ = MODE.set(Mode::Record(dir.to_path_buf()));
+    Ok(())
+}
+
+/// Answers requests from the responses recorded in `dir` from now on.
+pub fn replay_from(dir: &Path) -> io::Result<()> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .collect();
+    // File names start with the time they were recorded.
+    files.sort();
+    let mut responses: HashMap<String, VecDeque<Exchange>> = HashMap::new();
+    for path in files {
+        let exchange = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str::<Exchange>(&text).map_err(|e| e.to_string()));
+        match exchange {
+            Ok(exchange) => responses.entry(exchange.name.clone()).or_default().push_back(exchange),
+            Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    if responses.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "no recorded responses"));
+    }
+    let _ = MODE.set(Mode::Replay { dir: dir.to_path_buf(), responses: Mutex::new(responses) });
+    Ok(())
+}
+
+/// Whether responses are being recorded or replayed. The operating
+/// system's location is skipped then, since it can't be recorded.
+pub fn active() -> bool {
+    MODE.get().is_some()
+}
+
+/// Whether requests are being answered from a recording.
+#[cfg(feature = "gui")]
+pub fn replaying() -> bool {
+    matches!(MODE.get(), Some(Mode::Replay { .. }))
+}
+
+/// Sends `request` and returns the body of a successful response, saving it
+/// when recording. When replaying, the next response recorded under `name`
+/// is returned without touching the network.
+#[tracing::instrument(name = "request", level = "debug", skip(request))]
+pub async fn send(name: &str, request: RequestBuilder) -> Result<String, WeatherError> {
+    match MODE.get() {
+        None => Ok(request.send().await?.error_for_status()?.text().await?),
+        Some(Mode::Replay { dir, responses }) => {
+            let exchange = {
+                let mut responses = responses.lock().unwrap_or_else(|e| e.into_inner());
+                match responses.get_mut(name) {
+                    Some(queue) if queue.len() > 1 => queue.pop_front(),
+                    Some(queue) => queue.front().cloned(),
+                    None => None,
+                }
+            };
+            let Some(exchange) = exchange else {
+                return Err(WeatherError::NotRecorded(name.to_string(), dir.display().to_string()));
+            };
+            check_status(exchange.status)?;
+            Ok(exchange.body)
+        }
+        Some(Mode::Record(dir)) => {
+            let (client, request) = request.build_split();
+            let request = request?;
+            let url = redact(request.url().clone());
+            let response = client.execute(request).await?;
+            let status = response.status().as_u16();
+            let body = response.text().await?;
+            let exchange = Exchange { name: name.to_string(), recorded_at: Utc::now(), url, status, body };
+            save(dir, &exchange);
+            check_status(status)?;
+            Ok(exchange.body)
+        }
+    }
+}
+
+fn check_status(status: u16) -> Result<(), WeatherError> {
+    match StatusCode::from_u16(status) {
+        Ok(status) if !status.is_success() => Err(WeatherError::HttpStatus(status)),
+        _ => Ok(()),
+    }
+}
+
+fn redact(mut url: reqwest::Url) -> String {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let secret = SECRET_PARAMS.iter().any(|param| key.eq_ignore_ascii_case(param));
+            let value = if secret { "REDACTED".into() } else { value };
+            (key.into_owned(), value.into_owned())
+        })
+        .collect();
+    if !pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+fn save(dir: &Path, exchange: &Exchange) {
+    let path = dir.join(format!("{}-{}.json", exchange.recorded_at.format("%Y%m%dT%H%M%S%.6f"), exchange.name));
+    let result = serde_json::to_string_pretty(exchange)
+        .map_err(io::Error::from)
+        .and_then(|json| fs::write(&path, json));
+    if let Err(e) = result {
+        tracing::warn!("Unable to record {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redacted(url: &str) -> String {
+        redact(reqwest::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn secrets_are_replaced() {
+        let url = redacted("https://api.openweathermap.org/data/3.0/onecall?lat=40.71&lon=-74.01&appid=s3cr3t");
+        assert_eq!(url, "https://api.openweathermap.org/data/3.0/onecall?lat=40.71&lon=-74.01&appid=REDACTED");
+        for param in SECRET_PARAMS {
+            let url = redacted(&format!("https://example.com/?{}=s3cr3t&units=imperial", param));
+            assert!(!url.contains("s3cr3t"), "{} leaked: {}", param, url);
+            assert!(url.contains("units=imperial"));
+        }
+    }
+
+    #[test]
+    fn secrets_are_replaced_whatever_the_case_or_position() {
+        let url = redacted("https://example.com/a?APPID=one&q=Paris&Token=two&key=three");
+        assert!(!url.contains("one") && !url.contains("two") && !url.contains("three"), "{}", url);
+        assert!(url.contains("q=Paris"));
+    }
+
+    #[test]
+    fn urls_without_secrets_are_unchanged() {
+        let plain = "https://api.open-meteo.com/v1/forecast?latitude=40.71&longitude=-74.01";
+        assert_eq!(redacted(plain), plain);
+        assert_eq!(redacted("https://example.com/path"), "https://example.com/path");
+    }
+}
@@ -0,0 +1,269 @@
+//! A coarse grid of forecasts over a region, for keeping an eye on an area
+//! rather than one place. Every point comes from one Open-Meteo request.
+
+use eframe::egui;
+use egui::plot::{Plot, PlotPoints, Points, Polygon};
+use reqwest::Client;
+use crate::fetch::Fetch;
+use crate::open_meteo::{get_grid, GridPoint};
+use crate::rules::Rule;
+use crate::session;
+use crate::units::Units;
+use crate::Location;
+
+// Keeps a single request reasonable for a free API.
+const MAX_POINTS: usize = 100;
+// Half the side of the box first drawn around the location, in degrees.
+const DEFAULT_HALF_SPAN: f64 = 1.0;
+const BREACH_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 220, 90);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layer {
+    High,
+    Low,
+    Rain,
+}
+
+impl Layer {
+    fn label(self) -> &'static str {
+        match self {
+            Layer::High => "High",
+            Layer::Low => "Low",
+            Layer::Rain => "Chance of rain",
+        }
+    }
+
+    fn value(self, point: &GridPoint) -> Option<f64> {
+        match self {
+            Layer::High => point.temp_max,
+            Layer::Low => point.temp_min,
+            Layer::Rain => point.precipitation_probability,
+        }
+    }
+
+    fn format(self, value: f64, units: Units) -> String {
+        match self {
+            Layer::High | Layer::Low => units.format_temperature(value, 0),
+            Layer::Rain => format!("{:.0}%", value),
+        }
+    }
+
+    /// Blue through red from 0°F to 100°F, or white to blue with the
+    /// chance of rain.
+    fn color(self, value: f64) -> egui::Color32 {
+        match self {
+            Layer::High | Layer::Low => {
+                let t = (value / 100.0).clamp(0.0, 1.0) as f32;
+                // Green peaks mid-range so the middle isn't a muddy purple.
+                let green = 90.0 + 80.0 * (1.0 - (2.0 * t - 1.0).abs());
+                egui::Color32::from_rgb((40.0 + 215.0 * t) as u8, green as u8, (255.0 * (1.0 - t)) as u8)
+            }
+            Layer::Rain => {
+                let t = (value / 100.0).clamp(0.0, 1.0) as f32;
+                egui::Color32::from_rgb((235.0 - 200.0 * t) as u8, (240.0 - 140.0 * t) as u8, 255)
+            }
+        }
+    }
+}
+
+/// A bounding box split into points `spacing` degrees apart, shown as a
+/// heatmap of today's high, low or chance of rain. Once fetched it is
+/// fetched again with every refresh, and points that break one of the
+/// user's rules are outlined.
+pub struct RegionGrid {
+    south: f64,
+    west: f64,
+    north: f64,
+    east: f64,
+    spacing: f64,
+    layer: Layer,
+    // Set once the box has been placed around a location.
+    placed: bool,
+    grid: Option<Fetch<Vec<GridPoint>>>,
+}
+
+impl RegionGrid {
+    pub fn new() -> Self {
+        Self {
+            south: 0.0,
+            west: 0.0,
+            north: 0.0,
+            east: 0.0,
+            spacing: 0.25,
+            layer: Layer::High,
+            placed: false,
+            grid: None,
+        }
+    }
+
+    fn center_on(&mut self, location: &Location) {
+        self.south = location.lat - DEFAULT_HALF_SPAN;
+        self.north = location.lat + DEFAULT_HALF_SPAN;
+        self.west = location.lon - DEFAULT_HALF_SPAN;
+        self.east = location.lon + DEFAULT_HALF_SPAN;
+        self.placed = true;
+    }
+
+    /// Rows and columns of points.
+    fn size(&self) -> (usize, usize) {
+        let steps = |from: f64, to: f64| ((to - from) / self.spacing).floor().max(0.0) as usize + 1;
+        (steps(self.south, self.north), steps(self.west, self.east))
+    }
+
+    fn valid(&self) -> bool {
+        let (rows, columns) = self.size();
+        self.north > self.south && self.east > self.west && rows * columns <= MAX_POINTS
+    }
+
+    fn points(&self) -> Vec<(f64, f64)> {
+        let (rows, columns) = self.size();
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| (self.south + row as f64 * self.spacing, self.west + column as f64 * self.spacing))
+            .collect()
+    }
+
+    fn fetch(&mut self, ctx: &egui::Context) {
+        let points = self.points();
+        self.grid = Some(Fetch::spawn(ctx, async move { get_grid(&Client::new(), &points).await }));
+    }
+
+    /// Fetches the grid again, if it has been fetched at all.
+    pub fn refresh(&mut self, ctx: &egui::Context) {
+        if self.grid.is_some() && self.valid() {
+            self.fetch(ctx);
+        }
+    }
+
+    /// Points where an enabled rule is broken, as notification summaries
+    /// and bodies; `None` until the grid has loaded.
+    pub fn breaches(&self, rules: &[Rule], units: Units) -> Option<Vec<(String, String)>> {
+        let Some(Fetch::Ready(ref points)) = self.grid else { return None };
+        let mut breaches = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            for point in points {
+                let Some(value) = rule.check_day(point.temp_min, point.temp_max, point.precipitation_probability) else {
+                    continue;
+                };
+                breaches.push((
+                    format!("{} at {:.2}, {:.2}", rule.describe(units), point.lat, point.lon),
+                    rule.format_value(value, units),
+                ));
+            }
+        }
+        Some(breaches)
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, location: &Location, rules: &[Rule], units: Units) {
+        if !self.placed {
+            self.center_on(location);
+        }
+        session::card(ui, "Region grid", false, |ui| {
+            egui::Grid::new("region_grid_box").num_columns(4).show(ui, |ui| {
+                ui.label("North");
+                ui.add(egui::DragValue::new(&mut self.north).clamp_range(-90.0..=90.0).speed(0.05).suffix("°"));
+                ui.label("South");
+                ui.add(egui::DragValue::new(&mut self.south).clamp_range(-90.0..=90.0).speed(0.05).suffix("°"));
+                ui.end_row();
+                ui.label("West");
+                ui.add(egui::DragValue::new(&mut self.west).clamp_range(-180.0..=180.0).speed(0.05).suffix("°"));
+                ui.label("East");
+                ui.add(egui::DragValue::new(&mut self.east).clamp_range(-180.0..=180.0).speed(0.05).suffix("°"));
+                ui.end_row();
+                ui.label("Spacing");
+                ui.add(egui::DragValue::new(&mut self.spacing).clamp_range(0.05..=5.0).speed(0.01).suffix("°"));
+                ui.end_row();
+            });
+
+            let (rows, columns) = self.size();
+            let count = rows * columns;
+            ui.horizontal(|ui| {
+                if ui.button("Center on location").clicked() {
+                    self.center_on(location);
+                }
+                if ui.add_enabled(self.valid(), egui::Button::new("Fetch grid")).clicked() {
+                    self.fetch(ui.ctx());
+                }
+                if count > MAX_POINTS {
+                    ui.label(format!("{} points; at most {}", count, MAX_POINTS));
+                } else {
+                    ui.label(format!("{} points", count));
+                }
+            });
+            ui.horizontal(|ui| {
+                for layer in [Layer::High, Layer::Low, Layer::Rain] {
+                    ui.selectable_value(&mut self.layer, layer, layer.label());
+                }
+            });
+
+            let Some(grid) = &mut self.grid else { return };
+            grid.poll();
+            match grid {
+                Fetch::Pending(_) => {
+                    ui.spinner();
+                }
+                Fetch::Failed(e) => {
+                    ui.label(format!("Unable to load the grid: {}", e));
+                }
+                Fetch::Ready(points) => show_heatmap(ui, points, self.layer, self.spacing, location, rules, units),
+            }
+        });
+    }
+}
+
+fn show_heatmap(
+    ui: &mut egui::Ui,
+    points: &[GridPoint],
+    layer: Layer,
+    spacing: f64,
+    location: &Location,
+    rules: &[Rule],
+    units: Units,
+) {
+    let values: Vec<f64> = points.iter().filter_map(|point| layer.value(point)).collect();
+    if let (Some(low), Some(high)) = (values.iter().copied().reduce(f64::min), values.iter().copied().reduce(f64::max)) {
+        ui.label(format!("{} across the region: {} to {}", layer.label(), layer.format(low, units), layer.format(high, units)));
+    }
+
+    let half = spacing / 2.0;
+    let cell = |point: &GridPoint| -> PlotPoints {
+        vec![
+            [point.lon - half, point.lat - half],
+            [point.lon + half, point.lat - half],
+            [point.lon + half, point.lat + half],
+            [point.lon - half, point.lat + half],
+        ]
+        .into()
+    };
+    let breached = |point: &GridPoint| {
+        rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .any(|rule| rule.check_day(point.temp_min, point.temp_max, point.precipitation_probability).is_some())
+    };
+    let hover_points = points.to_vec();
+    Plot::new("region_grid")
+        .height(260.0)
+        // Degrees of longitude shrink towards the poles.
+        .data_aspect((1.0 / location.lat.to_radians().cos().max(0.1)) as f32)
+        .label_formatter(move |_name, pointer| {
+            let nearest = hover_points.iter().min_by(|a, b| {
+                let distance = |p: &GridPoint| (p.lon - pointer.x).powi(2) + (p.lat - pointer.y).powi(2);
+                distance(a).total_cmp(&distance(b))
+            });
+            match nearest.and_then(|point| Some((point, layer.value(point)?))) {
+                Some((point, value)) => format!("{:.2}, {:.2}\n{}", point.lat, point.lon, layer.format(value, units)),
+                None => format!("{:.2}, {:.2}", pointer.y, pointer.x),
+            }
+        })
+        .show(ui, |plot_ui| {
+            for point in points {
+                let Some(value) = layer.value(point) else { continue };
+                plot_ui.polygon(Polygon::new(cell(point)).color(layer.color(value)).fill_alpha(0.85).width(0.0));
+            }
+            for point in points.iter().filter(|point| breached(point)) {
+                plot_ui.polygon(Polygon::new(cell(point)).color(BREACH_COLOR).fill_alpha(0.0).width(2.0));
+            }
+            plot_ui.points(Points::new(vec![[location.lon, location.lat]]).radius(4.0).color(egui::Color32::BLACK).name(&location.city));
+        });
+}
@@ -0,0 +1,179 @@
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+use crate::geo::distance_km;
+
+const USGS_IV_URL: &str = "https://waterservices.usgs.gov/nwis/iv/";
+const NWPS_GAUGE_URL: &str = "https://api.water.noaa.gov/nwps/v1/gauges";
+// USGS parameter code for gage height in feet.
+const GAGE_HEIGHT_PARAMETER: &str = "00065";
+// Half-width of the search box in degrees (roughly 25 km).
+const SEARCH_RADIUS_DEG: f64 = 0.25;
+const MAX_GAUGES: usize = 5;
+
+#[derive(Debug, Deserialize)]
+struct IvResponse {
+    value: IvValue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IvValue {
+    #[serde(default)]
+    time_series: Vec<TimeSeries>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeSeries {
+    source_info: SourceInfo,
+    values: Vec<TimeSeriesValues>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceInfo {
+    site_name: String,
+    site_code: Vec<SiteCode>,
+    geo_location: GeoLocation,
+}
+
+#[derive(Debug, Deserialize)]
+struct SiteCode {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeoLocation {
+    geog_location: GeogLocation,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeogLocation {
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeSeriesValues {
+    value: Vec<Reading>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reading {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwpsGauge {
+    flood: Option<NwpsFlood>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwpsFlood {
+    categories: Option<NwpsCategories>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwpsCategories {
+    minor: Option<NwpsCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NwpsCategory {
+    stage: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct RiverGauge {
+    pub site_name: String,
+    pub distance_km: f64,
+    pub stage_ft: f64,
+    // Minor flood stage from the NWS, when the gauge has one.
+    pub flood_stage_ft: Option<f64>,
+}
+
+impl RiverGauge {
+    pub fn is_flooding(&self) -> bool {
+        self.flood_stage_ft.is_some_and(|flood| self.stage_ft >= flood)
+    }
+}
+
+/// Current gage height for the USGS stream gauges closest to the coordinates,
+/// nearest first.
+pub async fn get_river_gauges(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+) -> Result<Vec<RiverGauge>, WeatherError> {
+    let bbox = format!(
+        "{:.4},{:.4},{:.4},{:.4}",
+        lon - SEARCH_RADIUS_DEG,
+        lat - SEARCH_RADIUS_DEG,
+        lon + SEARCH_RADIUS_DEG,
+        lat + SEARCH_RADIUS_DEG
+    );
+    let res = client
+        .get(USGS_IV_URL)
+        .query(&[
+            ("format", "json"),
+            ("bBox", bbox.as_str()),
+            ("parameterCd", GAGE_HEIGHT_PARAMETER),
+            ("siteStatus", "active"),
+        ])
+        .send()
+        .await?;
+
+    // USGS answers 404 when the box simply has no matching sites.
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    let iv: IvResponse = res.error_for_status()?.json().await?;
+
+    let mut gauges: Vec<(String, RiverGauge)> = iv
+        .value
+        .time_series
+        .into_iter()
+        .filter_map(|series| {
+            let site = series.source_info;
+            let stage_ft = series.values.first()?.value.last()?.value.parse().ok()?;
+            let site_code = site.site_code.first()?.value.clone();
+            let position = site.geo_location.geog_location;
+            let gauge = RiverGauge {
+                site_name: site.site_name,
+                distance_km: distance_km(lat, lon, position.latitude, position.longitude),
+                stage_ft,
+                flood_stage_ft: None,
+            };
+            Some((site_code, gauge))
+        })
+        .collect();
+    gauges.sort_by(|a, b| a.1.distance_km.total_cmp(&b.1.distance_km));
+    gauges.truncate(MAX_GAUGES);
+
+    let mut result = Vec::with_capacity(gauges.len());
+    for (site_code, mut gauge) in gauges {
+        // Flood stage is optional context; a gauge without an NWS forecast point still shows.
+        gauge.flood_stage_ft = get_flood_stage(client, &site_code).await.unwrap_or(None);
+        result.push(gauge);
+    }
+    Ok(result)
+}
+
+async fn get_flood_stage(client: &Client, usgs_site: &str) -> Result<Option<f64>, WeatherError> {
+    let res = client
+        .get(format!("{}/{}", NWPS_GAUGE_URL, usgs_site))
+        .send()
+        .await?
+        .error_for_status()?;
+    let gauge: NwpsGauge = res.json().await?;
+    let stage = gauge
+        .flood
+        .and_then(|f| f.categories)
+        .and_then(|c| c.minor)
+        .and_then(|m| m.stage)
+        // NWPS uses negative sentinels for categories that aren't defined.
+        .filter(|stage| *stage >= 0.0);
+    Ok(stage)
+}
@@ -0,0 +1,203 @@
+use eframe::egui;
+use crate::time_format;
+use crate::Hourly;
+
+const FREEZING_F: f64 = 32.0;
+// Snow can still stick to roads a little above freezing.
+const SNOW_STICKS_F: f64 = 34.0;
+// Only show the panel when it's cold enough for any of this to matter.
+const WINTER_THRESHOLD_F: f64 = 40.0;
+const PRECIPITATION_POP: f64 = 0.3;
+const LOOKAHEAD_HOURS: usize = 24;
+const ADVISORY_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 150, 230);
+
+// Ordered from most to least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RoadHazard {
+    FreezingRain,
+    Snow,
+    BlackIce,
+}
+
+impl RoadHazard {
+    fn describe(self) -> &'static str {
+        match self {
+            RoadHazard::FreezingRain => "Freezing rain or sleet likely",
+            RoadHazard::Snow => "Snow-covered roads possible",
+            RoadHazard::BlackIce => "Black ice possible",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RoadAdvisory {
+    pub hazard: RoadHazard,
+    pub starts: i64,
+    // `None` when the hazard lasts past the end of the lookahead window.
+    pub ends: Option<i64>,
+}
+
+/// Finds the first winter driving hazard in the next 24 hours from temperature,
+/// precipitation type, and whether earlier hours left the roads wet.
+pub fn road_advisory(hourly: &[Hourly]) -> Option<RoadAdvisory> {
+    let mut advisory: Option<RoadAdvisory> = None;
+
+    for (hour, hazard) in hourly.iter().zip(hourly_hazards(hourly)).take(LOOKAHEAD_HOURS) {
+        match (&mut advisory, hazard) {
+            (None, Some(hazard)) => {
+                advisory = Some(RoadAdvisory {
+                    hazard,
+                    starts: hour.dt,
+                    ends: None,
+                })
+            }
+            (Some(current), Some(hazard)) if hazard < current.hazard => current.hazard = hazard,
+            (Some(current), None) => {
+                current.ends = Some(hour.dt);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    advisory
+}
+
+/// The hazard for each of `hourly`, if any.
+pub fn hourly_hazards(hourly: &[Hourly]) -> Vec<Option<RoadHazard>> {
+    let mut roads_wet = false;
+    hourly
+        .iter()
+        .map(|hour| {
+            let precipitating = is_precipitating(hour);
+            let condition_id = hour.weather.first().map(|w| w.id).unwrap_or_default();
+            let freezing = hour.temp <= FREEZING_F;
+
+            let hazard = if freezing && precipitating && is_freezing_precipitation(condition_id) {
+                Some(RoadHazard::FreezingRain)
+            } else if precipitating && is_snow(condition_id) && hour.temp <= SNOW_STICKS_F {
+                Some(RoadHazard::Snow)
+            } else if freezing && (roads_wet || precipitating) {
+                Some(RoadHazard::BlackIce)
+            } else {
+                None
+            };
+            roads_wet |= precipitating;
+            hazard
+        })
+        .collect()
+}
+
+fn is_precipitating(hour: &Hourly) -> bool {
+    let volume = hour.rain.as_ref().map(|r| r.one_hour).unwrap_or_default()
+        + hour.snow.as_ref().map(|s| s.one_hour).unwrap_or_default();
+    // OWM condition groups 2xx-6xx are thunderstorm, drizzle, rain and snow.
+    let condition_id = hour.weather.first().map(|w| w.id).unwrap_or_default();
+    volume > 0.0 || (hour.pop >= PRECIPITATION_POP && (200..700).contains(&condition_id))
+}
+
+fn is_freezing_precipitation(condition_id: u16) -> bool {
+    // 511 is freezing rain; 611-616 are sleet and rain/snow mixes.
+    condition_id == 511 || (611..=616).contains(&condition_id)
+}
+
+fn is_snow(condition_id: u16) -> bool {
+    (600..700).contains(&condition_id)
+}
+
+/// Road conditions panel, only shown when the next day gets near freezing.
+pub fn show(ui: &mut egui::Ui, hourly: &[Hourly]) {
+    let coldest = hourly
+        .iter()
+        .take(LOOKAHEAD_HOURS)
+        .map(|h| h.temp)
+        .fold(f64::INFINITY, f64::min);
+    if coldest > WINTER_THRESHOLD_F {
+        return;
+    }
+
+    ui.separator();
+    match road_advisory(hourly) {
+        Some(advisory) => {
+            ui.colored_label(ADVISORY_COLOR, describe_advisory(&advisory, hourly.first().map(|h| h.dt)));
+        }
+        None => {
+            ui.label("Roads: no winter driving hazards expected in the next 24 hours.");
+        }
+    }
+}
+
+fn describe_advisory(advisory: &RoadAdvisory, now: Option<i64>) -> String {
+    let hazard = advisory.hazard.describe();
+    let starts_now = Some(advisory.starts) == now;
+    match (starts_now, advisory.ends) {
+        (true, Some(ends)) => format!("Roads: {} before {}", hazard, time_format::local_time(ends)),
+        (true, None) => format!("Roads: {} through the next 24 hours", hazard),
+        (false, Some(ends)) => format!("Roads: {} from {} until {}", hazard, time_format::local_time(advisory.starts), time_format::local_time(ends)),
+        (false, None) => format!("Roads: {} from {}", hazard, time_format::local_time(advisory.starts)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+
+    // `condition` is an OWM condition id.
+    fn hour(i: i64, temp: f64, pop: f64, condition: u16) -> Hourly {
+        serde_json::from_value(serde_json::json!({
+            "dt": i * HOUR, "temp": temp, "pop": pop, "wind_speed": 5.0, "wind_deg": 0,
+            "weather": [{"id": condition, "main": "", "description": "", "icon": ""}],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn hazards_follow_temperature_and_precipitation() {
+        let hourly = [
+            hour(0, 45.0, 0.0, 800),
+            // Cold rain that isn't freezing rain leaves the roads wet...
+            hour(1, 36.0, 0.8, 500),
+            // ...so they ice over once it drops below freezing.
+            hour(2, 30.0, 0.0, 800),
+            hour(3, 30.0, 0.9, 511),
+            hour(4, 33.0, 0.9, 601),
+            // Snow too warm to stick.
+            hour(5, 36.0, 0.9, 601),
+        ];
+        assert_eq!(
+            hourly_hazards(&hourly),
+            [None, None, Some(RoadHazard::BlackIce), Some(RoadHazard::FreezingRain), Some(RoadHazard::Snow), None]
+        );
+    }
+
+    #[test]
+    fn dry_cold_is_not_a_hazard() {
+        let hourly = [hour(0, 20.0, 0.0, 800), hour(1, 20.0, 0.1, 600)];
+        assert_eq!(hourly_hazards(&hourly), [None, None]);
+    }
+
+    #[test]
+    fn advisory_takes_the_worst_hazard_of_the_spell() {
+        let hourly = [
+            hour(0, 40.0, 0.0, 800),
+            hour(1, 31.0, 0.9, 601),
+            hour(2, 31.0, 0.9, 511),
+            hour(3, 31.0, 0.0, 800),
+            hour(4, 38.0, 0.0, 800),
+        ];
+        let advisory = road_advisory(&hourly).unwrap();
+        assert_eq!(advisory.hazard, RoadHazard::FreezingRain);
+        assert_eq!(advisory.starts, HOUR);
+        assert_eq!(advisory.ends, Some(4 * HOUR));
+    }
+
+    #[test]
+    fn advisory_lasting_past_the_forecast_has_no_end() {
+        let hourly: Vec<_> = (0..30).map(|i| hour(i, 28.0, 0.9, 601)).collect();
+        let advisory = road_advisory(&hourly).unwrap();
+        assert_eq!((advisory.hazard, advisory.starts, advisory.ends), (RoadHazard::Snow, 0, None));
+        assert!(road_advisory(&[hour(0, 50.0, 0.9, 500)]).is_none());
+    }
+}
@@ -0,0 +1,722 @@
+//! User-defined alert rules such as "low below 32°F tomorrow", "gusts
+//! above 25 mph today", "chance of rain changes by 30% tomorrow" or "road
+//! hazards above 0 h tomorrow", kept in `rules.json` and checked on every
+//! refresh. Rules on air quality, a nearby river or recent earthquakes look
+//! at [`Readings`] fetched after the refresh instead of the weather forecast.
+
+use std::fs;
+use std::path::PathBuf;
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::fetch::Fetch;
+use crate::forecast_changes::{Change, Quantity};
+use crate::i18n;
+use crate::road_conditions;
+use crate::units::Units;
+use crate::{platform, Location, WeatherResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    Temperature,
+    WindSpeed,
+    WindGust,
+    ChanceOfRain,
+    UvIndex,
+    Humidity,
+    /// Hours with a winter driving hazard, see [`road_conditions`].
+    RoadHazards,
+    /// Feet above flood stage at the nearest river gauge that has one.
+    RiverStage,
+    /// Of earthquakes in the last day within the quake panel's radius.
+    QuakeMagnitude,
+    QuakeDistance,
+    /// US AQI from the air pollution forecast.
+    AirQuality,
+}
+
+// How long an earthquake counts for the rules.
+#[cfg(feature = "earthquakes")]
+const QUAKE_RULE_HOURS: i64 = 24;
+
+impl Metric {
+    const ALL: [Metric; 11] = [
+        Metric::Temperature,
+        Metric::WindSpeed,
+        Metric::WindGust,
+        Metric::ChanceOfRain,
+        Metric::UvIndex,
+        Metric::Humidity,
+        Metric::RoadHazards,
+        Metric::RiverStage,
+        Metric::QuakeMagnitude,
+        Metric::QuakeDistance,
+        Metric::AirQuality,
+    ];
+
+    /// The metrics this build can check; the rest are still kept in
+    /// `rules.json` but never match.
+    // A match reads better than `matches!` with the features spelled out.
+    #[allow(clippy::match_like_matches_macro)]
+    fn available() -> impl Iterator<Item = Metric> {
+        Metric::ALL.into_iter().filter(|&metric| match metric {
+            Metric::RiverStage => cfg!(feature = "river-gauges"),
+            Metric::QuakeMagnitude | Metric::QuakeDistance => cfg!(feature = "earthquakes"),
+            Metric::AirQuality => cfg!(feature = "air-quality"),
+            _ => true,
+        })
+    }
+
+    /// The windows a rule on this metric can look at.
+    fn windows(self) -> &'static [Window] {
+        match self {
+            Metric::RiverStage | Metric::QuakeMagnitude | Metric::QuakeDistance => &[Window::Now],
+            _ => &Window::ALL,
+        }
+    }
+
+    /// Whether the metric comes from [`Readings`] rather than the forecast.
+    fn is_reading(self) -> bool {
+        matches!(self, Metric::RiverStage | Metric::QuakeMagnitude | Metric::QuakeDistance | Metric::AirQuality)
+    }
+
+    fn label(self) -> &'static str {
+        i18n::tr(match self {
+            Metric::Temperature => "Temperature",
+            Metric::WindSpeed => "Wind",
+            Metric::WindGust => "Gusts",
+            Metric::ChanceOfRain => "Chance of rain",
+            Metric::UvIndex => "UV index",
+            Metric::Humidity => "Humidity",
+            Metric::RoadHazards => "Road hazards",
+            Metric::RiverStage => "River over flood stage",
+            Metric::QuakeMagnitude => "Quake magnitude",
+            Metric::QuakeDistance => "Quake distance",
+            Metric::AirQuality => "AQI",
+        })
+    }
+
+    /// `value` is in the forecast's units: °F, mph or percent, or hours
+    /// for road hazards, feet for rivers and kilometres for quakes.
+    fn format(self, value: f64, units: Units) -> String {
+        match self {
+            Metric::Temperature => units.format_temperature(value, 0),
+            Metric::WindSpeed | Metric::WindGust => units.format_speed(value, 0),
+            Metric::ChanceOfRain | Metric::Humidity => format!("{:.0}%", value),
+            Metric::UvIndex | Metric::AirQuality => format!("{:.0}", value),
+            Metric::RoadHazards => format!("{:.0} h", value),
+            Metric::RiverStage => format!("{:+.1} ft", value),
+            Metric::QuakeMagnitude => format!("M{:.1}", value),
+            Metric::QuakeDistance => format!("{:.0} km", value),
+        }
+    }
+
+    /// Unit the editor takes thresholds in.
+    fn unit(self, units: Units) -> &'static str {
+        match self {
+            Metric::Temperature => units.temperature_unit(),
+            Metric::WindSpeed | Metric::WindGust => units.speed_unit(),
+            Metric::ChanceOfRain | Metric::Humidity => "%",
+            Metric::UvIndex | Metric::AirQuality => "",
+            Metric::RoadHazards => " h",
+            Metric::RiverStage => " ft",
+            Metric::QuakeMagnitude => "",
+            Metric::QuakeDistance => " km",
+        }
+    }
+
+    /// [`Metric::format`] for a difference between two values.
+    fn format_change(self, value: f64, units: Units) -> String {
+        match self {
+            Metric::Temperature => format!("{:.0}{}", units.temperature_delta(value), units.temperature_unit()),
+            _ => self.format(value, units),
+        }
+    }
+
+    /// What a "changes by" rule on this metric looks at.
+    fn quantities(self) -> &'static [Quantity] {
+        match self {
+            Metric::Temperature => &[Quantity::High, Quantity::Low],
+            Metric::WindSpeed => &[Quantity::Wind],
+            Metric::ChanceOfRain => &[Quantity::ChanceOfRain],
+            Metric::WindGust | Metric::UvIndex | Metric::Humidity => &[],
+            Metric::RoadHazards | Metric::RiverStage | Metric::QuakeMagnitude | Metric::QuakeDistance => &[],
+            Metric::AirQuality => &[],
+        }
+    }
+
+    fn to_forecast_units(self, value: f64, units: Units, change: bool) -> f64 {
+        match self {
+            Metric::Temperature if change => value / units.temperature_delta(1.0),
+            Metric::Temperature => units.to_fahrenheit(value),
+            Metric::WindSpeed | Metric::WindGust => units.to_mph(value),
+            _ => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Comparator {
+    Below,
+    Above,
+    // Revised by at least the threshold since the last refresh.
+    ChangesBy,
+}
+
+/// The part of the forecast a rule looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Window {
+    Now,
+    Today,
+    Tomorrow,
+    Next24Hours,
+}
+
+impl Window {
+    const ALL: [Window; 4] = [Window::Now, Window::Today, Window::Tomorrow, Window::Next24Hours];
+
+    fn label(self) -> &'static str {
+        i18n::tr(match self {
+            Window::Now => "now",
+            Window::Today => "today",
+            Window::Tomorrow => "tomorrow",
+            Window::Next24Hours => "in the next 24 hours",
+        })
+    }
+
+    /// The local date of `Today` or `Tomorrow`.
+    fn date(self) -> NaiveDate {
+        let offset = if self == Window::Tomorrow { 1 } else { 0 };
+        Local::now().date_naive() + Duration::days(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub metric: Metric,
+    pub comparator: Comparator,
+    /// °F, mph or percent, like the forecast.
+    pub threshold: f64,
+    pub window: Window,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl Rule {
+    /// E.g. "Temperature below 32°F tomorrow".
+    pub fn describe(&self, units: Units) -> String {
+        let (comparator, threshold) = match self.comparator {
+            Comparator::Below => (i18n::tr("below"), self.metric.format(self.threshold, units)),
+            Comparator::Above => (i18n::tr("above"), self.metric.format(self.threshold, units)),
+            Comparator::ChangesBy => (i18n::tr("changes by"), self.metric.format_change(self.threshold, units)),
+        };
+        format!("{} {} {} {}", self.metric.label(), comparator, threshold, self.window.label())
+    }
+
+    /// What the rule found, e.g. "Down to 28°F tomorrow", or `None` when it
+    /// doesn't match. "Changes by" rules look at `changes`, the rest at
+    /// `weather` or `readings`.
+    pub fn status(&self, weather: &WeatherResponse, readings: &Readings, changes: &[Change], units: Units) -> Option<String> {
+        match self.comparator {
+            Comparator::ChangesBy => self.check_changes(changes).map(|change| change.describe(units)),
+            Comparator::Below | Comparator::Above => {
+                self.check(weather, readings).map(|value| self.format_value(value, units))
+            }
+        }
+    }
+
+    /// The largest of `changes` that a "changes by" rule matches.
+    pub fn check_changes<'a>(&self, changes: &'a [Change]) -> Option<&'a Change> {
+        if self.comparator != Comparator::ChangesBy {
+            return None;
+        }
+        let today = Local::now().date_naive();
+        let dates = match self.window {
+            Window::Now | Window::Today => vec![today],
+            Window::Tomorrow => vec![today + Duration::days(1)],
+            Window::Next24Hours => vec![today, today + Duration::days(1)],
+        };
+        changes
+            .iter()
+            .filter(|change| self.metric.quantities().contains(&change.quantity) && dates.contains(&change.date))
+            .filter(|change| change.delta().abs() >= self.threshold)
+            .max_by(|a, b| a.delta().abs().total_cmp(&b.delta().abs()))
+    }
+
+    /// The lowest value for "below" rules, or the highest for "above" ones,
+    /// when it crosses the threshold; `None` when the rule doesn't apply or
+    /// the forecast doesn't cover it.
+    pub fn check(&self, weather: &WeatherResponse, readings: &Readings) -> Option<f64> {
+        if self.metric.is_reading() {
+            return self.crossed(readings.values(self.metric, self.window));
+        }
+        self.crossed(values(weather, self.metric, self.window))
+    }
+
+    /// [`Rule::check`] for a place known only by today's low, high and
+    /// chance of rain, like a point of the region grid. Only temperature
+    /// and rain rules for today or the next 24 hours apply.
+    pub fn check_day(&self, low: Option<f64>, high: Option<f64>, pop: Option<f64>) -> Option<f64> {
+        if !matches!(self.window, Window::Today | Window::Next24Hours) {
+            return None;
+        }
+        match self.metric {
+            Metric::Temperature => self.crossed(low.into_iter().chain(high).collect()),
+            Metric::ChanceOfRain => self.crossed(pop.into_iter().collect()),
+            _ => None,
+        }
+    }
+
+    fn crossed(&self, values: Vec<f64>) -> Option<f64> {
+        let extreme = match self.comparator {
+            Comparator::Below => values.into_iter().reduce(f64::min)?,
+            Comparator::Above => values.into_iter().reduce(f64::max)?,
+            Comparator::ChangesBy => return None,
+        };
+        let crossed = match self.comparator {
+            Comparator::Below => extreme < self.threshold,
+            Comparator::Above | Comparator::ChangesBy => extreme > self.threshold,
+        };
+        crossed.then_some(extreme)
+    }
+
+    /// E.g. "Down to 28°F tomorrow".
+    pub fn format_value(&self, value: f64, units: Units) -> String {
+        let direction = match self.comparator {
+            Comparator::Below => i18n::tr("Down to"),
+            Comparator::Above | Comparator::ChangesBy => i18n::tr("Up to"),
+        };
+        format!("{} {} {}", direction, self.metric.format(value, units), self.window.label())
+    }
+}
+
+/// Measurements from outside the forecast that rules can look at, fetched
+/// after a refresh when an enabled rule needs them.
+#[derive(Debug, Clone, Default)]
+pub struct Readings {
+    /// Feet above minor flood stage at the nearest gauge that has one;
+    /// negative below it.
+    pub river_above_flood_ft: Option<f64>,
+    /// Magnitude and distance in km of each earthquake in the last day.
+    pub quakes: Vec<(f64, f64)>,
+    /// Time and US AQI of each hour of the air pollution forecast.
+    pub aqi_forecast: Vec<(i64, f64)>,
+}
+
+impl Readings {
+    /// Starts fetching what the enabled `rules` need at `location`, or
+    /// returns `None` when they only look at the forecast.
+    #[cfg_attr(
+        not(any(feature = "river-gauges", feature = "earthquakes", feature = "air-quality")),
+        allow(unused_variables, unused_mut)
+    )]
+    pub fn fetch(ctx: &egui::Context, rules: &[Rule], location: &Location, config: &Config) -> Option<Fetch<Readings>> {
+        let wanted = |metric| rules.iter().any(|rule| rule.enabled && rule.metric == metric);
+        if !Metric::available().any(|metric| metric.is_reading() && wanted(metric)) {
+            return None;
+        }
+        #[cfg(feature = "river-gauges")]
+        let river = wanted(Metric::RiverStage) && location.country_code == "US";
+        #[cfg(feature = "earthquakes")]
+        let quakes = (wanted(Metric::QuakeMagnitude) || wanted(Metric::QuakeDistance)).then(|| {
+            let radius_km = config.quake_radius_km().unwrap_or(crate::earthquakes::DEFAULT_RADIUS_KM);
+            let min_magnitude = config.quake_min_magnitude().unwrap_or(crate::earthquakes::DEFAULT_MIN_MAGNITUDE);
+            (radius_km, min_magnitude)
+        });
+        // From OpenWeatherMap with a key, like the air quality panel.
+        #[cfg(feature = "air-quality")]
+        let aqi = wanted(Metric::AirQuality).then(|| config.api_key());
+        let (lat, lon) = (location.lat, location.lon);
+        Some(Fetch::spawn(ctx, async move {
+            let client = reqwest::Client::new();
+            let mut readings = Readings::default();
+            // Each source is on its own; one being down leaves just its
+            // rules unmatched.
+            #[cfg(feature = "river-gauges")]
+            if river {
+                match crate::river_gauges::get_river_gauges(&client, lat, lon).await {
+                    Ok(gauges) => {
+                        readings.river_above_flood_ft = gauges
+                            .iter()
+                            .find_map(|gauge| Some(gauge.stage_ft - gauge.flood_stage_ft?));
+                    }
+                    Err(e) => tracing::warn!("River gauges for the alert rules failed: {}", e),
+                }
+            }
+            #[cfg(feature = "earthquakes")]
+            if let Some((radius_km, min_magnitude)) = quakes {
+                match crate::earthquakes::get_earthquakes(&client, lat, lon, radius_km, min_magnitude).await {
+                    Ok(quakes) => {
+                        let since = chrono::Utc::now() - Duration::hours(QUAKE_RULE_HOURS);
+                        readings.quakes = quakes
+                            .iter()
+                            .filter(|quake| quake.time >= since)
+                            .map(|quake| (quake.magnitude, quake.distance_km))
+                            .collect();
+                    }
+                    Err(e) => tracing::warn!("Earthquakes for the alert rules failed: {}", e),
+                }
+            }
+            #[cfg(feature = "air-quality")]
+            if let Some(api_key) = aqi {
+                match crate::air_quality::get_air_quality_forecast(&client, lat, lon, api_key.as_deref()).await {
+                    Ok(samples) => readings.aqi_forecast = samples.iter().map(|sample| (sample.dt, sample.aqi)).collect(),
+                    Err(e) => tracing::warn!("Air quality forecast for the alert rules failed: {}", e),
+                }
+            }
+            Ok::<_, std::convert::Infallible>(readings)
+        }))
+    }
+
+    /// Like [`values`], for the metrics that come from here.
+    fn values(&self, metric: Metric, window: Window) -> Vec<f64> {
+        if !metric.windows().contains(&window) {
+            return Vec::new();
+        }
+        match metric {
+            Metric::RiverStage => self.river_above_flood_ft.into_iter().collect(),
+            Metric::QuakeMagnitude => self.quakes.iter().map(|(magnitude, _)| *magnitude).collect(),
+            Metric::QuakeDistance => self.quakes.iter().map(|(_, distance)| *distance).collect(),
+            Metric::AirQuality => {
+                // The forecast starts with the current hour.
+                let start = self.aqi_forecast.first().map_or(0, |(dt, _)| *dt);
+                self.aqi_forecast
+                    .iter()
+                    .filter(|(dt, _)| match window {
+                        Window::Now => *dt == start,
+                        Window::Next24Hours => *dt < start + 24 * 3600,
+                        Window::Today | Window::Tomorrow => local_date(*dt) == Some(window.date()),
+                    })
+                    .map(|(_, aqi)| *aqi)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Every forecast value of `metric` that falls in `window`. Days combine
+/// the daily forecast with the hourly one for as far as that reaches.
+fn values(weather: &WeatherResponse, metric: Metric, window: Window) -> Vec<f64> {
+    if metric == Metric::RoadHazards {
+        return road_hazard_hours(weather, window).into_iter().collect();
+    }
+    let current = &weather.current;
+    let mut values = Vec::new();
+    let (day, hours): (Option<usize>, Vec<_>) = match window {
+        Window::Now => {
+            let next_hour = weather.hourly.first();
+            values.extend(match metric {
+                Metric::Temperature => Some(current.temp),
+                Metric::WindSpeed => Some(current.wind_speed),
+                Metric::WindGust => next_hour.and_then(|h| h.wind_gust),
+                Metric::ChanceOfRain => next_hour.map(|h| h.pop * 100.0),
+                Metric::UvIndex => current.uvi,
+                Metric::Humidity => Some(f64::from(current.humidity)),
+                Metric::RoadHazards | Metric::RiverStage | Metric::QuakeMagnitude | Metric::QuakeDistance => None,
+                Metric::AirQuality => None,
+            });
+            return values;
+        }
+        Window::Next24Hours => (None, weather.hourly.iter().take(24).collect()),
+        Window::Today | Window::Tomorrow => {
+            let date = window.date();
+            let day = weather.daily.iter().position(|d| local_date(d.dt) == Some(date));
+            (day, weather.hourly.iter().filter(|h| local_date(h.dt) == Some(date)).collect())
+        }
+    };
+
+    if let Some(day) = day.map(|i| &weather.daily[i]) {
+        match metric {
+            Metric::Temperature => values.extend([day.temp.min, day.temp.max]),
+            Metric::WindSpeed => values.extend(day.wind_speed),
+            Metric::WindGust | Metric::RoadHazards | Metric::RiverStage => {}
+            Metric::QuakeMagnitude | Metric::QuakeDistance | Metric::AirQuality => {}
+            Metric::ChanceOfRain => values.push(day.pop * 100.0),
+            Metric::UvIndex => values.extend(day.uvi),
+            Metric::Humidity => values.extend(day.humidity.map(f64::from)),
+        }
+    }
+    for hour in hours {
+        match metric {
+            Metric::Temperature => values.push(hour.temp),
+            Metric::WindSpeed => values.push(hour.wind_speed),
+            Metric::WindGust => values.extend(hour.wind_gust),
+            Metric::ChanceOfRain => values.push(hour.pop * 100.0),
+            Metric::UvIndex | Metric::Humidity | Metric::RoadHazards | Metric::RiverStage => {}
+            Metric::QuakeMagnitude | Metric::QuakeDistance | Metric::AirQuality => {}
+        }
+    }
+    values
+}
+
+/// How many hours of `window` have a winter driving hazard, or `None` when
+/// the hourly forecast doesn't reach it.
+fn road_hazard_hours(weather: &WeatherResponse, window: Window) -> Option<f64> {
+    let hours = weather.hourly.iter().zip(road_conditions::hourly_hazards(&weather.hourly));
+    let hazards: Vec<_> = match window {
+        Window::Now => hours.take(1).map(|(_, hazard)| hazard).collect(),
+        Window::Next24Hours => hours.take(24).map(|(_, hazard)| hazard).collect(),
+        Window::Today | Window::Tomorrow => {
+            let date = window.date();
+            hours.filter(|(hour, _)| local_date(hour.dt) == Some(date)).map(|(_, hazard)| hazard).collect()
+        }
+    };
+    (!hazards.is_empty()).then(|| hazards.iter().flatten().count() as f64)
+}
+
+fn local_date(dt: i64) -> Option<NaiveDate> {
+    Local.timestamp_opt(dt, 0).single().map(|t| t.date_naive())
+}
+
+fn rules_path() -> Option<PathBuf> {
+    platform::config_dir().map(|dir| dir.join("rules.json"))
+}
+
+/// The saved rules and the window for editing them.
+pub struct Rules {
+    pub open: bool,
+    rules: Vec<Rule>,
+    // The rule being put together, its threshold in display units.
+    draft: Rule,
+}
+
+impl Rules {
+    pub fn load() -> Self {
+        let rules = rules_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let draft = Rule {
+            metric: Metric::Temperature,
+            comparator: Comparator::Below,
+            threshold: 32.0,
+            window: Window::Tomorrow,
+            enabled: true,
+        };
+        Self { open: false, rules, draft }
+    }
+
+    fn save(&self) {
+        let Some(path) = rules_path() else { return };
+        let result = serde_json::to_string_pretty(&self.rules)
+            .map_err(std::io::Error::from)
+            .and_then(|text| {
+                path.parent().map_or(Ok(()), fs::create_dir_all)?;
+                fs::write(&path, text)
+            });
+        if let Err(e) = result {
+            tracing::warn!("Unable to save alert rules to {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        weather: Option<&WeatherResponse>,
+        readings: &Readings,
+        changes: &[Change],
+        units: Units,
+    ) {
+        let mut open = self.open;
+        let mut changed = false;
+        egui::Window::new(i18n::tr("Alert rules")).id(egui::Id::new("rules_window")).open(&mut open).show(ctx, |ui| {
+            if self.rules.is_empty() {
+                ui.label(i18n::tr("No rules yet."));
+            }
+            let mut removed = None;
+            for (i, rule) in self.rules.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let description = rule.describe(units);
+                    changed |= ui.checkbox(&mut rule.enabled, description).changed();
+                    match weather.and_then(|w| rule.status(w, readings, changes, units)) {
+                        Some(status) if rule.enabled => {
+                            ui.colored_label(ui.visuals().warn_fg_color, status);
+                        }
+                        _ => {}
+                    }
+                    if ui.small_button(i18n::tr("Remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                self.rules.remove(i);
+                changed = true;
+            }
+
+            ui.separator();
+            self.show_draft(ui, units, &mut changed);
+        });
+        self.open = open;
+        if changed {
+            self.save();
+        }
+    }
+
+    fn show_draft(&mut self, ui: &mut egui::Ui, units: Units, changed: &mut bool) {
+        let draft = &mut self.draft;
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_source("rule_metric").selected_text(draft.metric.label()).show_ui(ui, |ui| {
+                for metric in Metric::available() {
+                    ui.selectable_value(&mut draft.metric, metric, metric.label());
+                }
+            });
+            if !draft.metric.windows().contains(&draft.window) {
+                draft.window = draft.metric.windows()[0];
+            }
+            // Only some metrics are tracked between refreshes.
+            let can_change = !draft.metric.quantities().is_empty();
+            if !can_change && draft.comparator == Comparator::ChangesBy {
+                draft.comparator = Comparator::Below;
+            }
+            ui.selectable_value(&mut draft.comparator, Comparator::Below, i18n::tr("below"));
+            ui.selectable_value(&mut draft.comparator, Comparator::Above, i18n::tr("above"));
+            if can_change {
+                ui.selectable_value(&mut draft.comparator, Comparator::ChangesBy, i18n::tr("changes by"))
+                    .on_hover_text("Between one refresh and the next");
+            }
+            ui.add(egui::DragValue::new(&mut draft.threshold).suffix(draft.metric.unit(units)));
+            egui::ComboBox::from_id_source("rule_window").selected_text(draft.window.label()).show_ui(ui, |ui| {
+                for &window in draft.metric.windows() {
+                    ui.selectable_value(&mut draft.window, window, window.label());
+                }
+            });
+            if ui.button(i18n::tr("Add rule")).clicked() {
+                let change = draft.comparator == Comparator::ChangesBy;
+                let threshold = draft.metric.to_forecast_units(draft.threshold, units, change);
+                self.rules.push(Rule { threshold, ..draft.clone() });
+                *changed = true;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use crate::tests::ONECALL;
+
+    const HOUR: i64 = 3600;
+
+    fn rule(metric: Metric, comparator: Comparator, threshold: f64, window: Window) -> Rule {
+        Rule { metric, comparator, threshold, window, enabled: true }
+    }
+
+    // `temps` and `conditions` are per hour from now.
+    fn forecast(temps: &[f64], conditions: &[u16]) -> WeatherResponse {
+        let now = Local::now().timestamp();
+        let hourly: Vec<_> = temps
+            .iter()
+            .zip(conditions.iter().chain(std::iter::repeat(&800)))
+            .enumerate()
+            .map(|(i, (temp, id))| {
+                serde_json::json!({
+                    "dt": now + i as i64 * HOUR, "temp": temp, "pop": 0.9, "wind_speed": 5.0, "wind_deg": 0,
+                    "weather": [{"id": id, "main": "", "description": "", "icon": ""}],
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "current": {"temp": temps[0], "humidity": 50, "wind_speed": 5.0, "wind_deg": 0},
+            "hourly": hourly,
+            "daily": [{"dt": now, "temp": {"min": 40.0, "max": 70.0}}],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn now_rules_read_the_current_conditions() {
+        let weather: WeatherResponse = serde_json::from_str(ONECALL).unwrap();
+        let readings = Readings::default();
+        let check = |metric, comparator, threshold| rule(metric, comparator, threshold, Window::Now).check(&weather, &readings);
+        assert_eq!(check(Metric::Temperature, Comparator::Below, 70.0), Some(61.5));
+        assert_eq!(check(Metric::Temperature, Comparator::Above, 70.0), None);
+        assert_eq!(check(Metric::Humidity, Comparator::Above, 70.0), Some(72.0));
+        // Gusts come from the hourly forecast, which the fixture leaves out.
+        assert_eq!(check(Metric::WindGust, Comparator::Above, 0.0), None);
+    }
+
+    #[test]
+    fn windows_pick_their_hours() {
+        let temps: Vec<f64> = (0..30).map(f64::from).collect();
+        let weather = forecast(&temps, &[]);
+        let readings = Readings::default();
+        let above = |threshold, window| rule(Metric::Temperature, Comparator::Above, threshold, window).check(&weather, &readings);
+        assert_eq!(above(20.0, Window::Next24Hours), Some(23.0));
+        assert_eq!(above(25.0, Window::Next24Hours), None);
+        // Today's daily high counts alongside its hours.
+        assert_eq!(above(60.0, Window::Today), Some(70.0));
+    }
+
+    #[test]
+    fn road_hazard_rules_count_hours() {
+        let weather = forecast(&[30.0, 30.0, 30.0, 45.0], &[601, 601, 800, 800]);
+        let readings = Readings::default();
+        let hazards = |window| rule(Metric::RoadHazards, Comparator::Above, 1.0, window).check(&weather, &readings);
+        // Two hours of snow, then an icy hour on the wet roads.
+        assert_eq!(hazards(Window::Next24Hours), Some(3.0));
+        assert_eq!(hazards(Window::Now), None);
+    }
+
+    #[test]
+    fn reading_rules_look_at_the_readings() {
+        let weather: WeatherResponse = serde_json::from_str(ONECALL).unwrap();
+        let start = Local::now().timestamp() / HOUR * HOUR;
+        let readings = Readings {
+            river_above_flood_ft: Some(1.5),
+            quakes: vec![(3.0, 120.0), (2.6, 40.0)],
+            aqi_forecast: vec![(start, 50.0), (start + 23 * HOUR, 160.0), (start + 25 * HOUR, 200.0)],
+        };
+        let check = |metric, comparator, threshold, window| rule(metric, comparator, threshold, window).check(&weather, &readings);
+        assert_eq!(check(Metric::RiverStage, Comparator::Above, 0.0, Window::Now), Some(1.5));
+        // Only the latest stage is known.
+        assert_eq!(check(Metric::RiverStage, Comparator::Above, 0.0, Window::Today), None);
+        assert_eq!(check(Metric::QuakeDistance, Comparator::Below, 50.0, Window::Now), Some(40.0));
+        assert_eq!(check(Metric::QuakeMagnitude, Comparator::Above, 2.5, Window::Now), Some(3.0));
+        assert_eq!(check(Metric::AirQuality, Comparator::Above, 150.0, Window::Next24Hours), Some(160.0));
+        assert_eq!(check(Metric::AirQuality, Comparator::Above, 150.0, Window::Now), None);
+        // Without readings nothing matches.
+        let none = Readings::default();
+        assert_eq!(rule(Metric::RiverStage, Comparator::Below, 10.0, Window::Now).check(&weather, &none), None);
+    }
+
+    #[test]
+    fn grid_points_only_match_temperature_and_rain_today() {
+        let cold = rule(Metric::Temperature, Comparator::Below, 32.0, Window::Today);
+        assert_eq!(cold.check_day(Some(28.0), Some(40.0), None), Some(28.0));
+        assert_eq!(cold.check_day(Some(33.0), Some(40.0), None), None);
+        assert_eq!(rule(Metric::Temperature, Comparator::Below, 32.0, Window::Tomorrow).check_day(Some(28.0), None, None), None);
+        assert_eq!(rule(Metric::ChanceOfRain, Comparator::Above, 50.0, Window::Next24Hours).check_day(None, None, Some(80.0)), Some(80.0));
+        assert_eq!(rule(Metric::WindSpeed, Comparator::Above, 0.0, Window::Today).check_day(None, None, Some(80.0)), None);
+    }
+
+    #[test]
+    fn change_rules_ignore_the_forecast_values() {
+        let weather: WeatherResponse = serde_json::from_str(ONECALL).unwrap();
+        let rule = rule(Metric::Temperature, Comparator::ChangesBy, 1.0, Window::Now);
+        assert_eq!(rule.check(&weather, &Readings::default()), None);
+        assert!(rule.check_changes(&[]).is_none());
+    }
+
+    #[test]
+    fn describes_rules_and_matches() {
+        Language::En.apply();
+        let rule = rule(Metric::Temperature, Comparator::Below, 32.0, Window::Tomorrow);
+        assert_eq!(rule.describe(Units::IMPERIAL), "Temperature below 32°F tomorrow");
+        assert_eq!(rule.format_value(28.0, Units::IMPERIAL), "Down to 28°F tomorrow");
+    }
+}
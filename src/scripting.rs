@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+use eframe::egui;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use crate::config::Config;
+use crate::session;
+use crate::{Daily, Location, WeatherResponse};
+
+// Keeps a runaway script from freezing the UI thread.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// What a user script produced for the latest weather.
+#[derive(Debug, Default)]
+pub struct ScriptOutput {
+    /// Extra "name: value" lines shown under the summary.
+    pub fields: Vec<(String, String)>,
+    /// Messages shown as highlighted alerts.
+    pub alerts: Vec<String>,
+    /// Free-form text for the script's own panel.
+    pub panel_text: Option<String>,
+}
+
+/// A Rhai script run against the typed weather model after each fetch.
+///
+/// The script sees a `weather` map and can call `set_field(name, value)`,
+/// `alert(message)` and `panel_text(text)`.
+pub struct WeatherScript {
+    engine: Engine,
+    ast: AST,
+    output: Rc<RefCell<ScriptOutput>>,
+}
+
+impl WeatherScript {
+    /// Compiles the configured script, if there is one.
+    pub fn from_config(config: &Config) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let Some(path) = config.weather_script() else {
+            return Ok(None);
+        };
+        let source = fs::read_to_string(&path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+
+        let output = Rc::new(RefCell::new(ScriptOutput::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let fields = output.clone();
+        engine.register_fn("set_field", move |name: &str, value: Dynamic| {
+            fields.borrow_mut().fields.push((name.to_string(), value.to_string()));
+        });
+        let alerts = output.clone();
+        engine.register_fn("alert", move |message: &str| {
+            alerts.borrow_mut().alerts.push(message.to_string());
+        });
+        let panel = output.clone();
+        engine.register_fn("panel_text", move |text: &str| {
+            panel.borrow_mut().panel_text = Some(text.to_string());
+        });
+
+        let ast = engine.compile(&source)?;
+        Ok(Some(Self { engine, ast, output }))
+    }
+
+    pub fn run(&self, weather: &WeatherResponse, location: &Location) -> Result<ScriptOutput, String> {
+        let mut scope = Scope::new();
+        scope.push_constant("weather", weather_map(weather, location));
+        self.engine
+            .run_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| e.to_string())?;
+        Ok(self.output.take())
+    }
+}
+
+fn weather_map(weather: &WeatherResponse, location: &Location) -> Map {
+    let current = &weather.current;
+    let mut map = Map::new();
+    map.insert("city".into(), location.city.clone().into());
+    map.insert("temp".into(), current.temp.into());
+    map.insert("feels_like".into(), current.feels_like().into());
+    map.insert("humidity".into(), (current.humidity as i64).into());
+    map.insert("wind_speed".into(), current.wind_speed.into());
+    map.insert("wind_deg".into(), (current.wind_deg as i64).into());
+    map.insert("uvi".into(), current.uvi.map(Dynamic::from).unwrap_or(Dynamic::UNIT));
+    map.insert(
+        "description".into(),
+        current.weather.first().map(|w| w.description.clone()).unwrap_or_default().into(),
+    );
+    map.insert("today".into(), day_map(weather.daily.first()));
+    map.insert("tomorrow".into(), day_map(weather.daily.get(1)));
+
+    let hourly: Array = weather
+        .hourly
+        .iter()
+        .map(|hour| {
+            let mut entry = Map::new();
+            entry.insert("dt".into(), hour.dt.into());
+            entry.insert("temp".into(), hour.temp.into());
+            entry.insert("pop".into(), hour.pop.into());
+            entry.insert("wind_speed".into(), hour.wind_speed.into());
+            entry.into()
+        })
+        .collect();
+    map.insert("hourly".into(), hourly.into());
+    map
+}
+
+fn day_map(day: Option<&Daily>) -> Dynamic {
+    let Some(day) = day else { return Dynamic::UNIT };
+    let mut map = Map::new();
+    map.insert("high".into(), day.temp.max.into());
+    map.insert("low".into(), day.temp.min.into());
+    map.insert("pop".into(), day.pop.into());
+    map.insert("summary".into(), day.summary.clone().into());
+    map.into()
+}
+
+/// Loads and runs the configured script, if any. Compile and runtime errors
+/// are returned as text for display.
+pub fn run(config: &Config, weather: &WeatherResponse, location: &Location) -> Option<Result<ScriptOutput, String>> {
+    match WeatherScript::from_config(config) {
+        Ok(Some(script)) => Some(script.run(weather, location)),
+        Ok(None) => None,
+        Err(e) => Some(Err(e.to_string())),
+    }
+}
+
+const ALERT_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 40);
+
+pub fn show(ui: &mut egui::Ui, output: &Result<ScriptOutput, String>) {
+    match output {
+        Ok(output) => {
+            for message in &output.alerts {
+                ui.colored_label(ALERT_COLOR, message);
+            }
+            for (name, value) in &output.fields {
+                ui.label(format!("{}: {}", name, value));
+            }
+            if let Some(text) = &output.panel_text {
+                session::card(ui, "Script", true, |ui| {
+                    ui.label(text);
+                });
+            }
+        }
+        Err(e) => {
+            ui.small(format!("Script error: {}", e));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! What was on screen at exit, so the next launch picks up where the user
+//! left off. The selected location is kept with the saved locations.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use eframe::egui::{self, collapsing_header::CollapsingState};
+use serde::{Deserialize, Serialize};
+use crate::platform;
+
+// Card title to whether it is expanded, kept in egui's memory so the panels
+// don't all need the session passed in.
+type Cards = BTreeMap<String, bool>;
+
+fn cards_id() -> egui::Id {
+    egui::Id::new("session_cards")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    cards: Cards,
+    pub trip_planner_open: bool,
+    pub comparison_open: bool,
+    pub settings_open: bool,
+    pub locations_open: bool,
+    pub rules_open: bool,
+    pub mini_mode: bool,
+}
+
+fn session_path() -> Option<PathBuf> {
+    platform::config_dir().map(|dir| dir.join("session.json"))
+}
+
+impl Session {
+    /// Also hands the card states to `ctx` for [`card`] to pick up.
+    pub fn load(ctx: &egui::Context) -> Self {
+        let session: Self = session_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        ctx.data_mut(|d| d.insert_temp(cards_id(), session.cards.clone()));
+        session
+    }
+
+    /// The cards as they were last drawn; windows are filled in by the caller.
+    pub fn capture(ctx: &egui::Context) -> Self {
+        Self {
+            cards: ctx.data(|d| d.get_temp::<Cards>(cards_id())).unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = session_path() else { return };
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::from)
+            .and_then(|text| {
+                path.parent().map_or(Ok(()), fs::create_dir_all)?;
+                fs::write(&path, text)
+            });
+        if let Err(e) = result {
+            tracing::warn!("Unable to save session to {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// A collapsing card that opens the way it was left last time, or
+/// `default_open` the first time it is seen.
+pub fn card<R>(
+    ui: &mut egui::Ui,
+    title: &str,
+    default_open: bool,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> egui::CollapsingResponse<R> {
+    let ctx = ui.ctx().clone();
+    let saved = ctx.data(|d| d.get_temp::<Cards>(cards_id()).and_then(|cards| cards.get(title).copied()));
+    let response = egui::CollapsingHeader::new(title)
+        .default_open(saved.unwrap_or(default_open))
+        .show(ui, add_contents);
+    let open = CollapsingState::load(&ctx, response.header_response.id).map_or(response.openness > 0.5, |s| s.is_open());
+    if saved != Some(open) {
+        ctx.data_mut(|d| d.get_temp_mut_or_default::<Cards>(cards_id()).insert(title.to_owned(), open));
+    }
+    response
+}
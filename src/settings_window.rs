@@ -0,0 +1,307 @@
+use eframe::egui;
+use crate::config::{Config, UiScale, ENV_OVERRIDES};
+use crate::current_conditions::DisplayModel;
+use crate::hotkeys::{self, HotkeyCommand};
+use crate::i18n::{self, Language};
+use crate::log_window::LogWindow;
+use crate::notifications::Notifier;
+use crate::sun_exposure::SkinType;
+use crate::theme::Theme;
+use crate::time_format::{self, Clock};
+use crate::units::{PrecipitationUnit, PressureUnit, SpeedUnit, TemperatureUnit};
+
+const PROVIDERS: [(Option<&str>, &str); 3] = [
+    (None, "Automatic"),
+    (Some("openweathermap"), "OpenWeatherMap"),
+    (Some("open-meteo"), "Open-Meteo"),
+];
+
+/// Window for editing `config.toml`. Values set in the environment take
+/// precedence, so the window says when one is being overridden.
+pub struct SettingsWindow {
+    pub open: bool,
+    log_window: LogWindow,
+    // The API key while it is being typed; `None` when the field isn't focused.
+    api_key_draft: Option<String>,
+    // Same for the hotkeys, in the order of `HotkeyCommand::ALL`, with why
+    // the last one typed couldn't be used.
+    hotkey_drafts: [Option<String>; 2],
+    hotkey_errors: [Option<String>; 2],
+}
+
+impl SettingsWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            log_window: LogWindow::new(),
+            api_key_draft: None,
+            hotkey_drafts: Default::default(),
+            hotkey_errors: Default::default(),
+        }
+    }
+
+    /// Returns whether anything changed; the caller saves and applies it.
+    pub fn show(&mut self, ctx: &egui::Context, config: &mut Config, notifier: &Notifier) -> bool {
+        let before = config.clone();
+        let mut open = self.open;
+        egui::Window::new(i18n::tr("Settings")).id(egui::Id::new("settings_window")).open(&mut open).show(ctx, |ui| {
+            egui::Grid::new("settings").num_columns(2).show(ui, |ui| {
+                ui.label(i18n::tr("Provider"));
+                let selected = PROVIDERS
+                    .iter()
+                    .find(|(value, _)| config.provider.as_deref() == *value)
+                    .map_or("Automatic", |(_, label)| label);
+                egui::ComboBox::from_id_source("settings_provider")
+                    .selected_text(i18n::tr(selected))
+                    .show_ui(ui, |ui| {
+                        for (value, label) in PROVIDERS {
+                            let value = value.map(str::to_string);
+                            ui.selectable_value(&mut config.provider, value, i18n::tr(label));
+                        }
+                    })
+                    .response
+                    .on_hover_text(i18n::tr("Automatic uses OpenWeatherMap when there is an API key"));
+                ui.end_row();
+
+                ui.label(i18n::tr("OpenWeatherMap API key"));
+                let draft = self.api_key_draft.get_or_insert_with(|| config.api_key.clone().unwrap_or_default());
+                let response = ui.add(egui::TextEdit::singleline(draft).password(true));
+                // Taken once typing is done (Enter or clicking away); every
+                // keystroke would refetch with a half-typed key.
+                if response.lost_focus() {
+                    config.api_key = Some(draft.trim().to_string()).filter(|key| !key.is_empty());
+                }
+                if !response.has_focus() {
+                    self.api_key_draft = None;
+                }
+                ui.end_row();
+
+                ui.label(i18n::tr("Refresh every"));
+                let mut minutes = config.refresh_interval_minutes.unwrap_or(15);
+                let response = ui
+                    .add(egui::DragValue::new(&mut minutes).clamp_range(0..=24 * 60).suffix(" min"))
+                    .on_hover_text(i18n::tr("0 turns automatic refreshes off"));
+                if response.changed() {
+                    config.refresh_interval_minutes = Some(minutes);
+                }
+                ui.end_row();
+
+                ui.label(i18n::tr("Low data"));
+                ui.checkbox(&mut config.low_data, i18n::tr("For metered connections")).on_hover_text(i18n::tr(
+                    "Refreshes four times less often, turns off radar and webcams, and only loads other panels when asked",
+                ));
+                ui.end_row();
+
+                ui.label(i18n::tr("Temperature"));
+                egui::ComboBox::from_id_source("settings_temperature_unit")
+                    .selected_text(config.units.temperature.symbol())
+                    .show_ui(ui, |ui| {
+                        for option in TemperatureUnit::ALL {
+                            ui.selectable_value(&mut config.units.temperature, option, option.symbol());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Wind speed"));
+                egui::ComboBox::from_id_source("settings_speed_unit")
+                    .selected_text(config.units.speed.symbol())
+                    .show_ui(ui, |ui| {
+                        for option in SpeedUnit::ALL {
+                            ui.selectable_value(&mut config.units.speed, option, option.symbol());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Precipitation"));
+                egui::ComboBox::from_id_source("settings_precipitation_unit")
+                    .selected_text(config.units.precipitation.symbol())
+                    .show_ui(ui, |ui| {
+                        for option in PrecipitationUnit::ALL {
+                            ui.selectable_value(&mut config.units.precipitation, option, option.symbol());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Times"));
+                egui::ComboBox::from_id_source("settings_clock")
+                    .selected_text(i18n::tr(config.clock.label()))
+                    .show_ui(ui, |ui| {
+                        for option in Clock::ALL {
+                            ui.selectable_value(&mut config.clock, option, i18n::tr(option.label()));
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Language"));
+                egui::ComboBox::from_id_source("settings_language")
+                    .selected_text(i18n::tr(config.language.label()))
+                    .show_ui(ui, |ui| {
+                        for option in Language::ALL {
+                            ui.selectable_value(&mut config.language, option, i18n::tr(option.label()));
+                        }
+                    })
+                    .response
+                    .on_hover_text(i18n::tr("Labels, numbers, dates and OpenWeatherMap's descriptions"));
+                ui.end_row();
+
+                ui.label(i18n::tr("Theme"));
+                egui::ComboBox::from_id_source("settings_theme")
+                    .selected_text(i18n::tr(config.theme.label()))
+                    .show_ui(ui, |ui| {
+                        for option in Theme::ALL {
+                            ui.selectable_value(&mut config.theme, option, i18n::tr(option.label()));
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Interface scale"));
+                ui.horizontal(|ui| {
+                    let custom = match config.ui_scale {
+                        UiScale::Custom(scale) => scale,
+                        _ => ui.ctx().pixels_per_point(),
+                    };
+                    ui.selectable_value(&mut config.ui_scale, UiScale::System, i18n::tr("System"))
+                        .on_hover_text(i18n::tr("Follow the scale the monitor reports"));
+                    ui.selectable_value(&mut config.ui_scale, UiScale::Integer, i18n::tr("Whole"))
+                        .on_hover_text(i18n::tr("Round fractional scaling to a whole number for sharper text"));
+                    if ui.selectable_label(matches!(config.ui_scale, UiScale::Custom(_)), i18n::tr("Custom")).clicked() {
+                        config.ui_scale = UiScale::Custom(custom);
+                    }
+                    if let UiScale::Custom(ref mut scale) = config.ui_scale {
+                        ui.add(egui::DragValue::new(scale).clamp_range(0.5..=4.0).speed(0.01).suffix("x"));
+                    }
+                });
+                ui.end_row();
+
+                ui.label(i18n::tr("Pressure"));
+                egui::ComboBox::from_id_source("settings_pressure_unit")
+                    .selected_text(config.pressure_unit.label())
+                    .show_ui(ui, |ui| {
+                        for option in PressureUnit::ALL {
+                            ui.selectable_value(&mut config.pressure_unit, option, option.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(i18n::tr("Skin type"));
+                let label = |skin_type: Option<u8>| match skin_type.and_then(SkinType::new) {
+                    Some(skin_type) => i18n::tr_args("Type {}", &[&skin_type.roman()]),
+                    None => i18n::tr("Not set").to_string(),
+                };
+                egui::ComboBox::from_id_source("settings_skin_type")
+                    .selected_text(label(config.skin_type))
+                    .show_ui(ui, |ui| {
+                        for option in std::iter::once(None).chain((1..=6).map(Some)) {
+                            ui.selectable_value(&mut config.skin_type, option, label(option));
+                        }
+                    })
+                    .response
+                    .on_hover_text(i18n::tr("Fitzpatrick type, from I (always burns) to VI (never burns), for the time to sunburn next to the UV index"));
+                ui.end_row();
+            });
+
+            egui::CollapsingHeader::new(i18n::tr("Current conditions")).show(ui, |ui| {
+                for label in DisplayModel::LABELS {
+                    let mut shown = !config.hidden_conditions.iter().any(|h| h == label);
+                    if ui.checkbox(&mut shown, i18n::tr(label)).changed() {
+                        if shown {
+                            config.hidden_conditions.retain(|h| h != label);
+                        } else {
+                            config.hidden_conditions.push(label.to_string());
+                        }
+                    }
+                }
+            });
+
+            egui::CollapsingHeader::new(i18n::tr("Hotkeys")).show(ui, |ui| self.show_hotkeys(ui, config));
+            egui::CollapsingHeader::new(i18n::tr("Notifications")).show(ui, |ui| show_notifications(ui, notifier));
+            if ui.button(i18n::tr("View logs")).clicked() {
+                self.log_window.open = true;
+            }
+
+            let overridden: Vec<_> = ENV_OVERRIDES
+                .into_iter()
+                .filter(|name| std::env::var(name).is_ok())
+                .collect();
+            if !overridden.is_empty() {
+                ui.small(i18n::tr_args("Overridden by the environment: {}", &[&overridden.join(", ")]));
+            }
+        });
+        self.open = open;
+        self.log_window.show(ctx);
+        *config != before
+    }
+}
+
+/// Desktop notifications are the only channel; the test goes through the
+/// same path as real alerts.
+impl SettingsWindow {
+    fn show_hotkeys(&mut self, ui: &mut egui::Ui, config: &mut Config) {
+        egui::Grid::new("settings_hotkeys").num_columns(2).show(ui, |ui| {
+            for (i, command) in HotkeyCommand::ALL.into_iter().enumerate() {
+                ui.label(i18n::tr(command.label()));
+                let field = match command {
+                    HotkeyCommand::Refresh => &mut config.refresh_hotkey,
+                    HotkeyCommand::CycleLocation => &mut config.cycle_location_hotkey,
+                };
+                let draft = self.hotkey_drafts[i]
+                    .get_or_insert_with(|| field.clone().unwrap_or_else(|| command.default_hotkey().to_string()));
+                let response = ui
+                    .add(egui::TextEdit::singleline(draft).hint_text(i18n::tr("Off")))
+                    .on_hover_text(i18n::tr_args("For example {}; leave it empty to turn it off", &[&command.default_hotkey()]));
+                if response.lost_focus() {
+                    let text = draft.trim();
+                    match Some(text).filter(|text| !text.is_empty()).map(hotkeys::validate).transpose() {
+                        Ok(_) => {
+                            *field = Some(text.to_string()).filter(|text| text != command.default_hotkey());
+                            self.hotkey_errors[i] = None;
+                        }
+                        Err(e) => self.hotkey_errors[i] = Some(format!("{:?}: {}", text, e)),
+                    }
+                }
+                if !response.has_focus() {
+                    self.hotkey_drafts[i] = None;
+                }
+                ui.end_row();
+                if let Some(ref error) = self.hotkey_errors[i] {
+                    ui.label("");
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    ui.end_row();
+                }
+            }
+        });
+    }
+}
+
+fn show_notifications(ui: &mut egui::Ui, notifier: &Notifier) {
+    let deliveries = notifier.deliveries();
+    ui.horizontal(|ui| {
+        if ui.button(i18n::tr("Send test notification")).clicked() {
+            notifier.send_test();
+        }
+        match deliveries.iter().find(|d| d.test) {
+            Some(d) if d.result.is_ok() => {
+                ui.label(i18n::tr_args("Sent at {}", &[&time_format::local_time(d.at)]));
+            }
+            Some(d) => {
+                ui.colored_label(ui.visuals().error_fg_color, i18n::tr("Failed"));
+                if let Err(ref e) = d.result {
+                    ui.label(e);
+                }
+            }
+            None => {}
+        }
+    });
+    if deliveries.is_empty() {
+        ui.small(i18n::tr("Nothing sent yet."));
+        return;
+    }
+    ui.label(i18n::tr("Recent:"));
+    for delivery in &deliveries {
+        let status = match delivery.result {
+            Ok(()) => i18n::tr("shown").to_string(),
+            Err(ref e) => i18n::tr_args("failed: {}", &[e]),
+        };
+        ui.small(format!("{}  {} — {}", time_format::local_time(delivery.at), delivery.summary, status));
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use crate::error::WeatherError;
+use crate::geo::distance_km;
+
+const MPING_REPORTS_URL: &str = "https://mping.ou.edu/mping/api/v2/reports";
+const LOOKBACK_HOURS: i64 = 6;
+pub const SEARCH_RADIUS_KM: f64 = 150.0;
+// Reports come back nationwide, newest first, a page at a time.
+const MAX_PAGES: usize = 5;
+// mPING's `obtime`, always in UTC.
+const OBTIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// What can be reported from the panel: mPING category and description.
+pub const REPORT_TYPES: [(&str, &str); 12] = [
+    ("Rain/Snow", "Rain"),
+    ("Rain/Snow", "Freezing Rain"),
+    ("Rain/Snow", "Drizzle"),
+    ("Rain/Snow", "Ice Pellets/Sleet"),
+    ("Rain/Snow", "Snow and/or Graupel"),
+    ("Rain/Snow", "Mixed Rain and Snow"),
+    ("Hail", "Pea (0.25 in.)"),
+    ("Hail", "Quarter (1.00 in.)"),
+    ("Hail", "Golf Ball (1.75 in.)"),
+    ("Wind Damage", "Trees uprooted or snapped; Roof blown off"),
+    ("Flood", "River/Creek overflowing; Cropland/Yard/Basement Flooding"),
+    ("Reduced Visibility", "Dense Fog"),
+];
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    results: Vec<RawReport>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawReport {
+    obtime: String,
+    category: String,
+    description: String,
+    location: Point,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Point {
+    #[serde(rename = "type")]
+    kind: String,
+    // GeoJSON order: longitude, latitude.
+    coordinates: (f64, f64),
+}
+
+#[derive(Debug, Serialize)]
+struct NewReport<'a> {
+    obtime: String,
+    category: &'a str,
+    description: &'a str,
+    location: Point,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpotterReport {
+    pub category: String,
+    pub description: String,
+    pub observed: DateTime<Utc>,
+    pub lat: f64,
+    pub lon: f64,
+    pub distance_km: f64,
+}
+
+/// mPING reports from the last few hours within `SEARCH_RADIUS_KM`, newest first.
+pub async fn get_reports(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    token: &str,
+) -> Result<Vec<SpotterReport>, WeatherError> {
+    let since = (Utc::now() - Duration::hours(LOOKBACK_HOURS)).format(OBTIME_FORMAT).to_string();
+    let mut request = client.get(MPING_REPORTS_URL).query(&[("obtime_gte", since.as_str())]);
+    let mut reports = Vec::new();
+    for _ in 0..MAX_PAGES {
+        let page: Page = request
+            .header("Authorization", format!("Token {}", token))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        reports.extend(page.results.into_iter().filter_map(|raw| {
+            let (report_lon, report_lat) = raw.location.coordinates;
+            let observed = NaiveDateTime::parse_from_str(&raw.obtime, OBTIME_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(&raw.obtime, "%Y-%m-%dT%H:%M:%SZ"))
+                .ok()?
+                .and_utc();
+            Some(SpotterReport {
+                category: raw.category,
+                description: raw.description,
+                observed,
+                lat: report_lat,
+                lon: report_lon,
+                distance_km: distance_km(lat, lon, report_lat, report_lon),
+            })
+        }));
+        let Some(next) = page.next else { break };
+        request = client.get(next);
+    }
+    reports.retain(|report| report.distance_km <= SEARCH_RADIUS_KM);
+    reports.sort_by_key(|report| std::cmp::Reverse(report.observed));
+    Ok(reports)
+}
+
+/// Sends a report of what is happening right now at the coordinates. The
+/// token needs mPING's permission to submit.
+pub async fn submit_report(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    (category, description): (&str, &str),
+    token: &str,
+) -> Result<(), WeatherError> {
+    let report = NewReport {
+        obtime: Utc::now().format(OBTIME_FORMAT).to_string(),
+        category,
+        description,
+        location: Point { kind: "Point".to_string(), coordinates: (lon, lat) },
+    };
+    client
+        .post(MPING_REPORTS_URL)
+        .header("Authorization", format!("Token {}", token))
+        .json(&report)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
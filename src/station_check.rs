@@ -0,0 +1,60 @@
+use eframe::egui;
+use reqwest::Client;
+use crate::fetch::Fetch;
+use crate::nws::{get_nearest_observation, StationObservation};
+use crate::units::Units;
+use crate::Location;
+
+// Differences beyond this between the model and the station are called out.
+const DISCREPANCY_F: f64 = 5.0;
+const DISCREPANCY_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 40);
+
+/// One-line comparison of the forecast model's current temperature with the
+/// nearest NWS station's latest observation.
+pub struct StationCheck {
+    observation: Option<Fetch<StationObservation>>,
+}
+
+impl StationCheck {
+    pub fn new() -> Self {
+        Self { observation: None }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, location: &Location, model_temp_f: f64, units: Units, low_data: bool) {
+        // The NWS only has stations for US locations, and the check isn't
+        // worth the data on a metered connection.
+        if location.country_code != "US" || (low_data && self.observation.is_none()) {
+            return;
+        }
+
+        let (lat, lon) = (location.lat, location.lon);
+        let observation = self.observation.get_or_insert_with(|| {
+            Fetch::spawn(ui.ctx(), async move { get_nearest_observation(&Client::new(), lat, lon).await })
+        });
+        observation.poll();
+
+        // Failures are left silent; this is a secondary cross-check.
+        if let Fetch::Ready(observation) = observation {
+            let text = format!(
+                "Station {} reports {}",
+                observation.station_id,
+                units.format_temperature(observation.temp_f, 0)
+            );
+            let difference = observation.temp_f - model_temp_f;
+            let response = if difference.abs() > DISCREPANCY_F {
+                ui.colored_label(
+                    DISCREPANCY_COLOR,
+                    format!(
+                        "{} ({:+.0}{} vs forecast model)",
+                        text,
+                        units.temperature_delta(difference),
+                        units.temperature_unit()
+                    ),
+                )
+            } else {
+                ui.label(text)
+            };
+            response.on_hover_text(&observation.station_name);
+        }
+    }
+}
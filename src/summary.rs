@@ -0,0 +1,140 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use chrono::TimeZone;
+use serde_json::json;
+use crate::config::Config;
+use crate::platform;
+use crate::template::Template;
+use crate::time_format;
+use crate::units::Units;
+use crate::weekend;
+use crate::{beaufort, capitalize_first_letter, degrees_to_cardinal, format_chance_of_rain, format_temperature, WeatherSnapshot};
+
+/// Directory the summary files are written to: the configured one (empty
+/// disables them), otherwise the platform's local data directory.
+fn summary_dir(config: &Config) -> Option<PathBuf> {
+    match config.summary_dir() {
+        Some(dir) if dir.trim().is_empty() => None,
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => platform::data_dir(),
+    }
+}
+
+/// Writes `summary.txt` and `summary.json` for desktop widgets such as conky,
+/// GeekTool or Übersicht. Failures are logged rather than interrupting a refresh.
+pub fn write_files(snapshot: &WeatherSnapshot, config: &Config, template: Option<&Template>) {
+    let Some(dir) = summary_dir(config) else { return };
+    if let Err(e) = write(&dir, snapshot, config.units, template) {
+        tracing::warn!("Unable to write weather summary to {}: {}", dir.display(), e);
+    }
+}
+
+fn write(dir: &Path, snapshot: &WeatherSnapshot, units: Units, template: Option<&Template>) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    write_atomic(&dir.join("summary.txt"), to_text(snapshot, units, template).as_bytes())?;
+    write_atomic(&dir.join("summary.json"), to_json(snapshot, units).to_string().as_bytes())
+}
+
+/// The contents of `summary.txt`: the display template if there is one,
+/// otherwise a few short lines.
+pub fn to_text(snapshot: &WeatherSnapshot, units: Units, template: Option<&Template>) -> String {
+    let (weather, location) = (&snapshot.weather, &snapshot.location);
+    let fetched_at = snapshot.fetched_at;
+    let current = &weather.current;
+    let today = weather.daily.first();
+    let description = current.weather.first().map(|w| capitalize_first_letter(&w.description));
+    let wind_force = beaufort::force(current.wind_speed);
+
+    match template {
+        Some(template) => template.render(snapshot, units) + "\n",
+        None => {
+            let mut text = format!("{}\n{}", location.city, units.format_temperature(current.temp, 0));
+            if let Some(ref description) = description {
+                text.push_str(&format!(", {}", description));
+            }
+            text.push_str(&format!(
+                "\nHigh {} / Low {}, {} chance of rain\n{} from the {}\nUpdated {}\n",
+                format_temperature(today.map(|day| day.temp.max), units),
+                format_temperature(today.map(|day| day.temp.min), units),
+                format_chance_of_rain(today),
+                beaufort::description(wind_force),
+                degrees_to_cardinal(current.wind_deg),
+                time_format::time(fetched_at.naive_local()),
+            ));
+            text
+        }
+    }
+}
+
+/// The current conditions and then the hourly forecast, one row each, in
+/// the display units with a plain decimal point.
+#[cfg(feature = "gui")]
+pub fn to_csv(snapshot: &WeatherSnapshot, units: Units) -> Result<String, Box<dyn std::error::Error>> {
+    let weather = &snapshot.weather;
+    let description = |conditions: &[crate::Weather]| conditions.first().map_or(String::new(), |w| w.description.clone());
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "time".to_string(),
+        format!("temperature ({})", units.temperature_unit()),
+        "chance of rain (%)".to_string(),
+        format!("wind speed ({})", units.speed_unit()),
+        "wind direction (°)".to_string(),
+        "conditions".to_string(),
+    ])?;
+    let current = &weather.current;
+    writer.write_record([
+        snapshot.fetched_at.to_rfc3339(),
+        format!("{:.1}", units.temperature(current.temp)),
+        String::new(),
+        format!("{:.1}", units.speed(current.wind_speed)),
+        current.wind_deg.to_string(),
+        description(&current.weather),
+    ])?;
+    for hour in &weather.hourly {
+        let time = chrono::Local.timestamp_opt(hour.dt, 0).single().map_or(String::new(), |t| t.to_rfc3339());
+        writer.write_record([
+            time,
+            format!("{:.1}", units.temperature(hour.temp)),
+            format!("{:.0}", hour.pop * 100.0),
+            format!("{:.1}", units.speed(hour.wind_speed)),
+            hour.wind_deg.to_string(),
+            description(&hour.weather),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// The fields of `summary.json`, also printed by `--cli --json`.
+pub fn to_json(snapshot: &WeatherSnapshot, units: Units) -> serde_json::Value {
+    let (weather, location) = (&snapshot.weather, &snapshot.location);
+    let current = &weather.current;
+    let today = weather.daily.first();
+    let description = current.weather.first().map(|w| capitalize_first_letter(&w.description));
+    let wind_force = beaufort::force(current.wind_speed);
+    json!({
+        "city": location.city,
+        "country_code": location.country_code,
+        "units": units,
+        "temp": units.temperature(current.temp),
+        "feels_like": units.temperature(current.feels_like()),
+        "description": description,
+        "high": today.map(|day| units.temperature(day.temp.max)),
+        "low": today.map(|day| units.temperature(day.temp.min)),
+        "pop": today.map(|day| day.pop),
+        "wind_speed": units.speed(current.wind_speed),
+        "wind_deg": current.wind_deg,
+        "beaufort": wind_force,
+        "wind_description": beaufort::description(wind_force),
+        "weekend": weekend::summarize(weather, units),
+        "updated_at": snapshot.fetched_at,
+    })
+}
+
+// Widgets poll these files, so never let them see a half-written one.
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
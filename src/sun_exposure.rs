@@ -0,0 +1,30 @@
+// Minimal erythemal dose in J/m² for Fitzpatrick skin types I-VI.
+const MED_BY_SKIN_TYPE: [f64; 6] = [200.0, 250.0, 350.0, 450.0, 600.0, 1000.0];
+// One UV index unit is 0.025 W/m² of erythemally weighted irradiance.
+const UVI_IRRADIANCE: f64 = 0.025;
+const ROMAN: [&str; 6] = ["I", "II", "III", "IV", "V", "VI"];
+
+/// Fitzpatrick skin type, 1 (always burns) through 6 (never burns).
+#[derive(Debug, Clone, Copy)]
+pub struct SkinType(u8);
+
+impl SkinType {
+    /// `None` outside 1-6, in which case no estimate is shown.
+    pub fn new(skin_type: u8) -> Option<Self> {
+        (1..=6).contains(&skin_type).then_some(SkinType(skin_type))
+    }
+
+    pub fn roman(self) -> &'static str {
+        ROMAN[(self.0 - 1) as usize]
+    }
+
+    /// Approximate unprotected minutes until sunburn at the given UV index.
+    /// `None` when UV is too low for a meaningful estimate.
+    pub fn burn_minutes(self, uvi: f64) -> Option<f64> {
+        if uvi < 1.0 {
+            return None;
+        }
+        let med = MED_BY_SKIN_TYPE[(self.0 - 1) as usize];
+        Some(med / (uvi * UVI_IRRADIANCE * 60.0))
+    }
+}
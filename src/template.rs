@@ -0,0 +1,76 @@
+use crate::config::Config;
+use crate::sun_exposure::SkinType;
+use crate::{i18n, time_format};
+use crate::units::Units;
+use crate::{beaufort, capitalize_first_letter, degrees_to_cardinal, format_chance_of_rain, format_temperature, format_uv_index};
+use crate::{WeatherSnapshot, DATA_UNAVAILABLE};
+
+/// A user-supplied layout such as `{{city}}: {{temp}}, {{condition}}`.
+/// Placeholders that aren't recognised are left in the output as written,
+/// which makes typos easy to spot.
+pub struct Template {
+    source: String,
+    // For the burn time next to `{{uv}}`.
+    skin_type: Option<SkinType>,
+}
+
+impl Template {
+    /// The configured template, with `\n` standing for a line break so that
+    /// a multi-line layout fits on one line of `.env`.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let source = config.display_template()?;
+        Some(Self { source: source.replace("\\n", "\n"), skin_type: config.skin_type() })
+    }
+
+    pub fn render(&self, snapshot: &WeatherSnapshot, units: Units) -> String {
+        let mut out = String::with_capacity(self.source.len());
+        let mut rest = self.source.as_str();
+        while let Some(open) = rest.find("{{") {
+            let Some(close) = rest[open..].find("}}").map(|close| open + close) else { break };
+            out.push_str(&rest[..open]);
+            match value(rest[open + 2..close].trim(), snapshot, units, self.skin_type) {
+                Some(value) => out.push_str(&value),
+                None => out.push_str(&rest[open..close + 2]),
+            }
+            rest = &rest[close + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+fn value(name: &str, snapshot: &WeatherSnapshot, units: Units, skin_type: Option<SkinType>) -> Option<String> {
+    let weather = &snapshot.weather;
+    let current = &weather.current;
+    let today = weather.daily.first();
+    let value = match name {
+        "city" => snapshot.location.city.clone(),
+        "country" => snapshot.location.country_code.clone(),
+        "summary" => today
+            .map(|day| day.summary.as_str())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(i18n::tr(DATA_UNAVAILABLE))
+            .to_string(),
+        "condition" => capitalize_first_letter(current.weather.first().map_or(i18n::tr(DATA_UNAVAILABLE), |w| w.description.as_str())),
+        "temp" => units.format_temperature(current.temp, 1),
+        "feels_like" => units.format_temperature(current.feels_like(), 1),
+        "high" => format_temperature(today.map(|day| day.temp.max), units),
+        "low" => format_temperature(today.map(|day| day.temp.min), units),
+        "humidity" => format!("{}%", current.humidity),
+        "dew_point" => units.format_temperature(current.dew_point(), 1),
+        "visibility" => current.visibility.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), |m| units.format_distance(m)),
+        "sunrise" => current.sunrise.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), time_format::local_time),
+        "sunset" => current.sunset.map_or(i18n::tr(DATA_UNAVAILABLE).to_string(), time_format::local_time),
+        "wind" => units.format_speed(current.wind_speed, 1),
+        "wind_dir" => degrees_to_cardinal(current.wind_deg).to_string(),
+        "beaufort" => beaufort::force(current.wind_speed).to_string(),
+        "wind_desc" => beaufort::description(beaufort::force(current.wind_speed)).to_string(),
+        "uv" => format_uv_index(current.uvi, skin_type),
+        "pop_today" => format_chance_of_rain(today),
+        "pop_tomorrow" => format_chance_of_rain(weather.daily.get(1)),
+        "alerts" => weather.alerts.iter().map(|a| a.event.as_str()).collect::<Vec<_>>().join(", "),
+        "updated" => time_format::time(snapshot.fetched_at.naive_local()),
+        _ => return None,
+    };
+    Some(value)
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use chrono::Local;
+#[cfg(feature = "gui")]
+use eframe::egui::{self, Color32, Mesh, Pos2, Rect};
+#[cfg(feature = "gui")]
+use crate::{Current, WeatherSnapshot};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Light by day and dark after sunset, going by the current location.
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+// How close to sunrise or sunset the sky counts as twilight.
+#[cfg(feature = "gui")]
+const TWILIGHT_SECS: i64 = 45 * 60;
+#[cfg(feature = "gui")]
+const STAR_COUNT: u32 = 60;
+
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sky {
+    Clear,
+    Clouds,
+    Rain,
+    Thunder,
+    Snow,
+    Fog,
+    Twilight,
+}
+
+#[cfg(feature = "gui")]
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Auto, Theme::Light, Theme::Dark];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Auto => "Day/night",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+
+    /// Switches egui between light and dark visuals. `Auto` leaves the
+    /// system theme alone until sunrise and sunset are known.
+    pub fn apply(self, ctx: &egui::Context, snapshot: Option<&WeatherSnapshot>) {
+        let dark = match self {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::Auto => match snapshot.and_then(|s| is_night(&s.weather.current)) {
+                Some(night) => night,
+                None => return,
+            },
+        };
+        if ctx.style().visuals.dark_mode != dark {
+            ctx.set_visuals(if dark { egui::Visuals::dark() } else { egui::Visuals::light() });
+        }
+    }
+}
+
+/// `None` when the provider gave no sunrise and sunset, e.g. in polar day.
+#[cfg(feature = "gui")]
+fn is_night(current: &Current) -> Option<bool> {
+    let now = Local::now().timestamp();
+    Some(now < current.sunrise? || now > current.sunset?)
+}
+
+#[cfg(feature = "gui")]
+fn sky(current: &Current) -> Sky {
+    let now = Local::now().timestamp();
+    let near = |t: Option<i64>| t.is_some_and(|t| (now - t).abs() <= TWILIGHT_SECS);
+    let id = current.weather.first().map_or(800, |w| w.id);
+    match id {
+        200..=299 => Sky::Thunder,
+        300..=599 => Sky::Rain,
+        600..=699 => Sky::Snow,
+        700..=799 => Sky::Fog,
+        _ if near(current.sunrise) || near(current.sunset) => Sky::Twilight,
+        800 => Sky::Clear,
+        _ => Sky::Clouds,
+    }
+}
+
+// Top and bottom of the gradient; the light palettes keep dark text readable
+// and the dark ones light text.
+#[cfg(feature = "gui")]
+fn gradient(sky: Sky, dark: bool) -> (Color32, Color32) {
+    let rgb = Color32::from_rgb;
+    match (sky, dark) {
+        (Sky::Clear, false) => (rgb(135, 190, 235), rgb(225, 240, 250)),
+        (Sky::Clouds, false) => (rgb(190, 200, 210), rgb(235, 238, 242)),
+        (Sky::Rain, false) => (rgb(150, 165, 185), rgb(215, 222, 232)),
+        (Sky::Thunder, false) => (rgb(150, 140, 175), rgb(215, 210, 228)),
+        (Sky::Snow, false) => (rgb(215, 225, 235), rgb(248, 250, 252)),
+        (Sky::Fog, false) => (rgb(200, 200, 200), rgb(238, 238, 238)),
+        (Sky::Twilight, false) => (rgb(250, 180, 120), rgb(252, 228, 200)),
+        (Sky::Clear, true) => (rgb(8, 14, 40), rgb(28, 40, 80)),
+        (Sky::Clouds, true) => (rgb(30, 34, 42), rgb(48, 54, 64)),
+        (Sky::Rain, true) => (rgb(22, 30, 44), rgb(40, 50, 66)),
+        (Sky::Thunder, true) => (rgb(28, 20, 44), rgb(50, 40, 70)),
+        (Sky::Snow, true) => (rgb(40, 46, 58), rgb(64, 72, 86)),
+        (Sky::Fog, true) => (rgb(40, 40, 44), rgb(60, 60, 64)),
+        (Sky::Twilight, true) => (rgb(60, 30, 60), rgb(140, 70, 50)),
+    }
+}
+
+/// Fills `ui`'s area with a sky gradient for the current conditions. Call it
+/// before adding widgets so they are drawn on top.
+#[cfg(feature = "gui")]
+pub fn paint_background(ui: &egui::Ui, snapshot: Option<&WeatherSnapshot>) {
+    let Some(snapshot) = snapshot else { return };
+    let current = &snapshot.weather.current;
+    let dark = ui.visuals().dark_mode;
+    let sky = sky(current);
+    let (top, bottom) = gradient(sky, dark);
+    let rect = ui.max_rect().expand(ui.spacing().item_spacing.x);
+
+    let mut mesh = Mesh::default();
+    mesh.colored_vertex(rect.left_top(), top);
+    mesh.colored_vertex(rect.right_top(), top);
+    mesh.colored_vertex(rect.left_bottom(), bottom);
+    mesh.colored_vertex(rect.right_bottom(), bottom);
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(1, 2, 3);
+    let painter = ui.painter();
+    painter.add(mesh);
+
+    if dark && sky == Sky::Clear && is_night(current) == Some(true) {
+        paint_stars(painter, rect);
+    }
+}
+
+// Scattered over the upper half, in the same places every frame.
+#[cfg(feature = "gui")]
+fn paint_stars(painter: &egui::Painter, rect: Rect) {
+    let mut seed: u32 = 0x2545_f491;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    for _ in 0..STAR_COUNT {
+        let pos = Pos2::new(rect.left() + next() * rect.width(), rect.top() + next() * rect.height() * 0.5);
+        let brightness = (120.0 + next() * 135.0) as u8;
+        painter.circle_filled(pos, 0.6 + next(), Color32::from_white_alpha(brightness));
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+use crate::geo::distance_km;
+
+const STATIONS_URL: &str =
+    "https://api.tidesandcurrents.noaa.gov/mdapi/prod/webapi/stations.json?type=tidepredictions";
+const PREDICTIONS_URL: &str = "https://api.tidesandcurrents.noaa.gov/api/prod/datagetter";
+
+// Beyond this the nearest station says little about local tides, so the
+// location is treated as inland.
+const MAX_STATION_DISTANCE_KM: f64 = 100.0;
+
+#[derive(Debug, Deserialize)]
+struct StationList {
+    stations: Vec<Station>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Station {
+    pub id: String,
+    pub name: String,
+    lat: f64,
+    lng: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PredictionResponse {
+    #[serde(default)]
+    predictions: Vec<Prediction>,
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Prediction {
+    t: String,
+    v: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Debug)]
+pub struct TideEvent {
+    pub time: DateTime<Utc>,
+    pub height_ft: f64,
+    pub high: bool,
+}
+
+#[derive(Debug)]
+pub struct TideForecast {
+    pub station: Station,
+    pub distance_km: f64,
+    pub events: Vec<TideEvent>,
+}
+
+/// Finds the closest NOAA CO-OPS prediction station and its next high/low tides.
+/// Returns `None` when no station is close enough for the location to count as coastal.
+pub async fn get_tides(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+) -> Result<Option<TideForecast>, WeatherError> {
+    let res = client.get(STATIONS_URL).send().await?;
+    let station_list: StationList = res.json().await?;
+
+    let nearest = station_list
+        .stations
+        .into_iter()
+        .map(|station| (distance_km(lat, lon, station.lat, station.lng), station))
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (distance_km, station) = match nearest {
+        Some((distance, station)) if distance <= MAX_STATION_DISTANCE_KM => (distance, station),
+        _ => return Ok(None),
+    };
+
+    let begin_date = Utc::now().format("%Y%m%d").to_string();
+    let res = client
+        .get(PREDICTIONS_URL)
+        .query(&[
+            ("station", station.id.as_str()),
+            ("product", "predictions"),
+            ("datum", "MLLW"),
+            ("interval", "hilo"),
+            ("units", "english"),
+            // Station local time would have to be matched with the station's
+            // zone, so ask for UTC.
+            ("time_zone", "gmt"),
+            ("format", "json"),
+            ("begin_date", begin_date.as_str()),
+            ("range", "48"),
+            ("application", "weather_alerts"),
+        ])
+        .send()
+        .await?;
+    let predictions: PredictionResponse = res.json().await?;
+
+    if let Some(error) = predictions.error {
+        return Err(WeatherError::Service(format!("NOAA tide predictions: {}", error.message)));
+    }
+
+    let now = Utc::now();
+    let events = predictions
+        .predictions
+        .into_iter()
+        .filter_map(|p| {
+            let time = NaiveDateTime::parse_from_str(&p.t, "%Y-%m-%d %H:%M").ok()?.and_utc();
+            let height_ft = p.v.parse().ok()?;
+            Some(TideEvent { time, height_ft, high: p.kind == "H" })
+        })
+        .filter(|event| event.time >= now)
+        .collect();
+
+    Ok(Some(TideForecast { station, distance_km, events }))
+}
@@ -0,0 +1,92 @@
+//! Clock times as shown everywhere in the app, in 12- or 24-hour form.
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use crate::i18n;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Clock {
+    /// Whatever the locale (`LC_ALL`, `LC_TIME` or `LANG`) suggests.
+    #[default]
+    Auto,
+    #[serde(rename = "12h")]
+    TwelveHour,
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+}
+
+// Locales where 12-hour times are the norm; everything else gets 24-hour.
+const TWELVE_HOUR_LOCALES: [&str; 6] = ["en_US", "en_CA", "en_AU", "en_NZ", "en_IN", "en_PH"];
+
+// Set from the config once at startup and again when it changes, so the
+// formatting helpers don't need it passed through every panel.
+static CLOCK: AtomicU8 = AtomicU8::new(0);
+
+impl Clock {
+    #[cfg(feature = "gui")]
+    pub const ALL: [Clock; 3] = [Clock::Auto, Clock::TwelveHour, Clock::TwentyFourHour];
+
+    #[cfg(feature = "gui")]
+    pub fn label(self) -> &'static str {
+        match self {
+            Clock::Auto => "System default",
+            Clock::TwelveHour => "12-hour",
+            Clock::TwentyFourHour => "24-hour",
+        }
+    }
+
+    /// Makes this the clock every helper below uses.
+    pub fn apply(self) {
+        CLOCK.store(self as u8, Ordering::Relaxed);
+    }
+
+    fn twelve_hour(self) -> bool {
+        match self {
+            Clock::TwelveHour => true,
+            Clock::TwentyFourHour => false,
+            Clock::Auto => ["LC_ALL", "LC_TIME", "LANG"]
+                .into_iter()
+                .find_map(|name| env::var(name).ok().filter(|v| !v.is_empty()))
+                .is_some_and(|locale| TWELVE_HOUR_LOCALES.iter().any(|prefix| locale.starts_with(prefix))),
+        }
+    }
+}
+
+fn twelve_hour() -> bool {
+    let clock = match CLOCK.load(Ordering::Relaxed) {
+        1 => Clock::TwelveHour,
+        2 => Clock::TwentyFourHour,
+        _ => Clock::Auto,
+    };
+    clock.twelve_hour()
+}
+
+/// "14:05" or "2:05 PM".
+pub fn time(t: NaiveDateTime) -> String {
+    t.format(if twelve_hour() { "%-I:%M %p" } else { "%H:%M" }).to_string()
+}
+
+/// "Tue 14:05" or "Tue 2:05 PM", with the day in the current language.
+pub fn weekday_time(t: NaiveDateTime) -> String {
+    format!("{} {}", i18n::format_date(t.date(), "%a"), time(t))
+}
+
+/// [`time`] for a Unix timestamp, in local time.
+pub fn local_time(dt: i64) -> String {
+    Local.timestamp_opt(dt, 0).single().map(|t| time(t.naive_local())).unwrap_or_default()
+}
+
+/// On-the-hour label for hourly strips: "14:00" or "2 PM".
+#[cfg(feature = "gui")]
+pub fn local_hour(dt: i64) -> String {
+    let Some(t) = Local.timestamp_opt(dt, 0).single() else { return String::new() };
+    t.format(if twelve_hour() { "%-I %p" } else { "%H:%M" }).to_string()
+}
+
+/// [`weekday_time`] for a Unix timestamp, in local time.
+pub fn local_weekday_time(dt: i64) -> String {
+    Local.timestamp_opt(dt, 0).single().map(|t| weekday_time(t.naive_local())).unwrap_or_default()
+}
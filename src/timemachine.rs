@@ -0,0 +1,127 @@
+//! Past hourly readings from the One Call 3.0 timemachine endpoint, or from
+//! Open-Meteo without an API key. Each timemachine request returns a single
+//! hour, so answers are cached on disk: the past doesn't change, and the
+//! free tier allows 1,000 calls a day.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use chrono::Local;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use crate::error::WeatherError;
+use crate::open_meteo;
+use crate::{platform, Precipitation};
+
+const HOUR_SECS: i64 = 3600;
+// Entries older than the longest range shown are dropped when saving.
+const KEEP_SECS: i64 = 8 * 24 * HOUR_SECS;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PastHour {
+    pub dt: i64,
+    /// °F.
+    pub temp: f64,
+    /// Rain and snow in the hour, mm.
+    pub precipitation: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimemachineResponse {
+    data: Vec<TimemachineHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimemachineHour {
+    dt: i64,
+    temp: f64,
+    rain: Option<Precipitation>,
+    snow: Option<Precipitation>,
+}
+
+type Entries = HashMap<String, PastHour>;
+
+fn cache_path() -> Option<PathBuf> {
+    platform::cache_dir().map(|dir| dir.join("timemachine.json"))
+}
+
+fn load() -> Entries {
+    cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(entries: &mut Entries) {
+    let Some(path) = cache_path() else { return };
+    let oldest = Local::now().timestamp() - KEEP_SECS;
+    entries.retain(|_, hour| hour.dt >= oldest);
+    let result = serde_json::to_string(entries)
+        .map_err(std::io::Error::from)
+        .and_then(|text| {
+            path.parent().map_or(Ok(()), fs::create_dir_all)?;
+            fs::write(&path, text)
+        });
+    if let Err(e) = result {
+        tracing::warn!("Unable to update history cache at {}: {}", path.display(), e);
+    }
+}
+
+// About a kilometre apart, so a location re-detected from a slightly
+// different IP position still hits the cache.
+fn key(lat: f64, lon: f64, dt: i64) -> String {
+    format!("{:.2},{:.2},{}", lat, lon, dt)
+}
+
+async fn get_past_hour(client: &Client, lat: f64, lon: f64, dt: i64, api_key: &str) -> Result<PastHour, WeatherError> {
+    let url = format!(
+        "https://api.openweathermap.org/data/3.0/onecall/timemachine?lat={}&lon={}&dt={}&units=imperial&appid={}",
+        lat, lon, dt, api_key
+    );
+    let res = client.get(&url).send().await?.error_for_status()?;
+    let response: TimemachineResponse = res.json().await?;
+    let hour = response
+        .data
+        .into_iter()
+        .next()
+        .ok_or_else(|| WeatherError::Deserialize("timemachine returned no data".to_string()))?;
+    let amount = |p: Option<Precipitation>| p.map_or(0.0, |p| p.one_hour);
+    Ok(PastHour { dt: hour.dt, temp: hour.temp, precipitation: amount(hour.rain) + amount(hour.snow) })
+}
+
+/// The last `hours` full hours, one reading every `step` hours, oldest first.
+/// Only hours missing from the cache are requested.
+pub async fn fetch_past(
+    api_key: Option<String>,
+    lat: f64,
+    lon: f64,
+    hours: i64,
+    step: i64,
+) -> Result<Vec<PastHour>, WeatherError> {
+    let latest = Local::now().timestamp() / HOUR_SECS * HOUR_SECS - HOUR_SECS;
+    let wanted: Vec<i64> = (0..hours / step).map(|i| latest - i * step * HOUR_SECS).rev().collect();
+    let client = Client::new();
+
+    let Some(api_key) = api_key else {
+        // Open-Meteo has every hour in one request, so there's nothing to cache.
+        let days = (hours + 23) / 24 + 1;
+        let past = open_meteo::get_past_hours(&client, lat, lon, days as u32).await?;
+        return Ok(past.into_iter().filter(|hour| wanted.contains(&hour.dt)).collect());
+    };
+    let mut entries = load();
+    let mut requests = JoinSet::new();
+    for &dt in wanted.iter().filter(|&&dt| !entries.contains_key(&key(lat, lon, dt))) {
+        let (client, api_key) = (client.clone(), api_key.clone());
+        requests.spawn(async move { (dt, get_past_hour(&client, lat, lon, dt, &api_key).await) });
+    }
+    let fetched = !requests.is_empty();
+    while let Some(joined) = requests.join_next().await {
+        let Ok((dt, result)) = joined else { continue };
+        entries.insert(key(lat, lon, dt), result?);
+    }
+    if fetched {
+        save(&mut entries);
+    }
+    Ok(wanted.iter().filter_map(|&dt| entries.get(&key(lat, lon, dt)).cloned()).collect())
+}
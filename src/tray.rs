@@ -0,0 +1,160 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use eframe::egui;
+use ksni::menu::{MenuItem, RadioGroup, RadioItem, StandardItem, SubMenu};
+use ksni::TrayMethods;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use crate::config::Config;
+
+/// Actions picked from the tray menu.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayCommand {
+    ShowWindow,
+    Refresh,
+    // `None` is the IP-detected location, like `locations::Store::selected`.
+    SelectLocation(Option<usize>),
+    Quit,
+}
+
+/// What the tray icon shows; the app sends a new one whenever it changes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrayState {
+    pub title: String,
+    pub tooltip: String,
+    pub icon: String,
+    // Location names, the IP-detected location first.
+    pub locations: Vec<String>,
+    pub selected: usize,
+}
+
+struct WeatherTray {
+    state: TrayState,
+    commands: Sender<TrayCommand>,
+    ctx: egui::Context,
+}
+
+impl WeatherTray {
+    fn send(&self, command: TrayCommand) {
+        let _ = self.commands.send(command);
+        self.ctx.request_repaint();
+    }
+}
+
+impl ksni::Tray for WeatherTray {
+    fn id(&self) -> String {
+        "weather_alerts".into()
+    }
+
+    fn title(&self) -> String {
+        self.state.title.clone()
+    }
+
+    fn icon_name(&self) -> String {
+        self.state.icon.clone()
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: self.state.title.clone(),
+            description: self.state.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.send(TrayCommand::ShowWindow);
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let options = self
+            .state
+            .locations
+            .iter()
+            .map(|name| RadioItem { label: name.clone(), ..Default::default() })
+            .collect();
+        vec![
+            StandardItem {
+                label: "Open Weather Alerts".into(),
+                activate: Box::new(|tray: &mut Self| tray.send(TrayCommand::ShowWindow)),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Refresh".into(),
+                icon_name: "view-refresh".into(),
+                activate: Box::new(|tray: &mut Self| tray.send(TrayCommand::Refresh)),
+                ..Default::default()
+            }
+            .into(),
+            SubMenu {
+                label: "Location".into(),
+                submenu: vec![RadioGroup {
+                    selected: self.state.selected,
+                    select: Box::new(|tray: &mut Self, index| {
+                        tray.state.selected = index;
+                        tray.send(TrayCommand::SelectLocation(index.checked_sub(1)));
+                    }),
+                    options,
+                }
+                .into()],
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: "Quit".into(),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|tray: &mut Self| tray.send(TrayCommand::Quit)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// Status-notifier tray icon showing the current temperature, for running the
+/// app in the background.
+pub struct Tray {
+    updates: UnboundedSender<TrayState>,
+    commands: Receiver<TrayCommand>,
+    last_state: TrayState,
+}
+
+impl Tray {
+    /// Starts the tray icon if tray mode is on.
+    pub fn start(ctx: &egui::Context, config: &Config) -> Option<Self> {
+        if !config.tray_mode() {
+            return None;
+        }
+
+        let (tx, commands) = mpsc::channel();
+        let (updates, mut pending) = unbounded_channel::<TrayState>();
+        let tray = WeatherTray { state: TrayState::default(), commands: tx, ctx: ctx.clone() };
+        tokio::spawn(async move {
+            let handle = match tray.spawn().await {
+                Ok(handle) => handle,
+                Err(e) => {
+                    tracing::warn!("Tray icon unavailable: {}", e);
+                    return;
+                }
+            };
+            while let Some(state) = pending.recv().await {
+                handle.update(|tray: &mut WeatherTray| tray.state = state).await;
+            }
+        });
+
+        Some(Self { updates, commands, last_state: TrayState::default() })
+    }
+
+    /// Shows `state`, unless it's what the icon already shows.
+    pub fn set_state(&mut self, state: TrayState) {
+        if state != self.last_state {
+            self.last_state = state.clone();
+            let _ = self.updates.send(state);
+        }
+    }
+
+    /// Menu actions chosen since the last call.
+    pub fn take_commands(&self) -> Vec<TrayCommand> {
+        self.commands.try_iter().collect()
+    }
+}
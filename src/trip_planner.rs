@@ -0,0 +1,102 @@
+use chrono::{Duration, Local, NaiveDate};
+use eframe::egui;
+use reqwest::Client;
+use crate::config::Config;
+use crate::error::WeatherError;
+use crate::fetch::Fetch;
+use crate::geocode;
+use crate::i18n;
+use crate::provider::Provider;
+use crate::open_meteo::{describe_weather_code, get_day_forecast, DayForecast, MAX_FORECAST_DAYS};
+use crate::units::Units;
+
+/// Window for checking a single future day at another destination.
+pub struct TripPlanner {
+    pub open: bool,
+    destination: String,
+    days_ahead: i64,
+    forecast: Option<(String, Fetch<DayForecast>)>,
+}
+
+impl TripPlanner {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            destination: String::new(),
+            days_ahead: 7,
+            forecast: None,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, config: &Config) {
+        let mut open = self.open;
+        egui::Window::new("Trip planner").open(&mut open).show(ctx, |ui| {
+            let date = Local::now().date_naive() + Duration::days(self.days_ahead);
+            ui.horizontal(|ui| {
+                ui.label("Destination");
+                ui.text_edit_singleline(&mut self.destination)
+                    .on_hover_text("City, optionally followed by a country code, e.g. \"Paris, FR\"");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Days ahead");
+                ui.add(egui::DragValue::new(&mut self.days_ahead).clamp_range(0..=MAX_FORECAST_DAYS - 1));
+                ui.label(i18n::format_date(date, "%a %b %-d"));
+            });
+
+            let can_search = !self.destination.trim().is_empty();
+            if ui.add_enabled(can_search, egui::Button::new("Get forecast")).clicked() {
+                let destination = self.destination.trim().to_string();
+                let provider = Provider::from_config(config);
+                let fetch = Fetch::spawn(ui.ctx(), {
+                    let destination = destination.clone();
+                    async move { fetch_trip_forecast(provider?, destination, date).await }
+                });
+                self.forecast = Some((destination, fetch));
+            }
+
+            if let Some((destination, forecast)) = &mut self.forecast {
+                ui.separator();
+                forecast.poll();
+                match forecast {
+                    Fetch::Pending(_) => {
+                        ui.spinner();
+                    }
+                    Fetch::Failed(e) => {
+                        ui.label(format!("Unable to get forecast: {}", e));
+                    }
+                    Fetch::Ready(day) => show_day(ui, destination, day, config.units),
+                }
+            }
+        });
+        self.open = open;
+    }
+}
+
+async fn fetch_trip_forecast(provider: Provider, destination: String, date: NaiveDate) -> Result<DayForecast, WeatherError> {
+    let (city, country_code) = match destination.split_once(',') {
+        Some((city, country)) => (city.trim(), country.trim()),
+        None => (destination.as_str(), ""),
+    };
+    let (lat, lon) = geocode(&provider, city, country_code).await?;
+    get_day_forecast(&Client::new(), lat, lon, date).await
+}
+
+fn show_day(ui: &mut egui::Ui, destination: &str, day: &DayForecast, units: Units) {
+    ui.strong(format!("{} on {}", destination, i18n::format_date(day.date, "%A, %B %-d")));
+    if let Some(code) = day.weather_code {
+        ui.label(describe_weather_code(code));
+    }
+    let temp = |t: Option<f64>| t.map(|t| units.format_temperature(t, 0)).unwrap_or_else(|| "n/a".to_string());
+    ui.label(format!("High {} / Low {}", temp(day.temp_max), temp(day.temp_min)));
+    if let Some(pop) = day.precipitation_probability {
+        ui.label(format!("Chance of precipitation: {:.0}%", pop));
+    }
+
+    let days_out = (day.date - Local::now().date_naive()).num_days();
+    let caveat = match days_out {
+        0..=3 => "Short-range forecast: generally reliable.",
+        4..=7 => "Medium-range forecast: expect some changes before the trip.",
+        _ => "Long-range forecast: treat as a rough trend only; details will change.",
+    };
+    ui.small(caveat);
+}
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+use crate::i18n;
+
+/// Display units, chosen per measurement so that e.g. °C can go with mph.
+/// Forecasts are always fetched in imperial units (precipitation amounts in
+/// mm), which the app's thresholds (freezing, flyable wind, ...) are written
+/// against, and converted only when shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredUnits")]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub speed: SpeedUnit,
+    pub precipitation: PrecipitationUnit,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units::IMPERIAL
+    }
+}
+
+// Settings saved before units could be mixed hold just a preset name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredUnits {
+    Preset(Preset),
+    Custom {
+        temperature: TemperatureUnit,
+        speed: SpeedUnit,
+        #[serde(default)]
+        precipitation: PrecipitationUnit,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Preset {
+    Imperial,
+    Metric,
+    Kelvin,
+}
+
+impl From<StoredUnits> for Units {
+    fn from(stored: StoredUnits) -> Self {
+        match stored {
+            StoredUnits::Preset(Preset::Imperial) => Units::IMPERIAL,
+            StoredUnits::Preset(Preset::Metric) => Units::METRIC,
+            StoredUnits::Preset(Preset::Kelvin) => Units::KELVIN,
+            StoredUnits::Custom { temperature, speed, precipitation } => Units { temperature, speed, precipitation },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Fahrenheit,
+    Celsius,
+    Kelvin,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    #[default]
+    Mph,
+    Kmh,
+    Mps,
+    Knots,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipitationUnit {
+    #[default]
+    Inches,
+    Mm,
+}
+
+const MPS_PER_MPH: f64 = 0.44704;
+const METRES_PER_MILE: f64 = 1609.344;
+const KMH_PER_MPH: f64 = 1.609_344;
+const KNOTS_PER_MPH: f64 = 0.868_976;
+#[cfg(feature = "gui")]
+const MM_PER_INCH: f64 = 25.4;
+
+impl TemperatureUnit {
+    #[cfg(feature = "gui")]
+    pub const ALL: [TemperatureUnit; 3] = [TemperatureUnit::Fahrenheit, TemperatureUnit::Celsius, TemperatureUnit::Kelvin];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+}
+
+impl SpeedUnit {
+    #[cfg(feature = "gui")]
+    pub const ALL: [SpeedUnit; 4] = [SpeedUnit::Mph, SpeedUnit::Kmh, SpeedUnit::Mps, SpeedUnit::Knots];
+
+    fn per_mph(self) -> f64 {
+        match self {
+            SpeedUnit::Mph => 1.0,
+            SpeedUnit::Kmh => KMH_PER_MPH,
+            SpeedUnit::Mps => MPS_PER_MPH,
+            SpeedUnit::Knots => KNOTS_PER_MPH,
+        }
+    }
+
+    pub fn convert(self, mph: f64) -> f64 {
+        mph * self.per_mph()
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            SpeedUnit::Mph => "mph",
+            SpeedUnit::Kmh => "km/h",
+            SpeedUnit::Mps => "m/s",
+            SpeedUnit::Knots => "kn",
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl PrecipitationUnit {
+    pub const ALL: [PrecipitationUnit; 2] = [PrecipitationUnit::Inches, PrecipitationUnit::Mm];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            PrecipitationUnit::Inches => "in",
+            PrecipitationUnit::Mm => "mm",
+        }
+    }
+}
+
+impl Units {
+    pub const IMPERIAL: Units = Units {
+        temperature: TemperatureUnit::Fahrenheit,
+        speed: SpeedUnit::Mph,
+        precipitation: PrecipitationUnit::Inches,
+    };
+    pub const METRIC: Units = Units {
+        temperature: TemperatureUnit::Celsius,
+        speed: SpeedUnit::Mps,
+        precipitation: PrecipitationUnit::Mm,
+    };
+    pub const KELVIN: Units = Units { temperature: TemperatureUnit::Kelvin, ..Units::METRIC };
+
+    /// The quick choices offered next to the location.
+    #[cfg(feature = "gui")]
+    pub const PRESETS: [Units; 3] = [Units::IMPERIAL, Units::METRIC, Units::KELVIN];
+
+    /// E.g. "°C, mph, mm"; the precipitation unit is left out for the presets.
+    #[cfg(feature = "gui")]
+    pub fn label(self) -> String {
+        let label = format!("{}, {}", self.temperature_unit(), self.speed_unit());
+        if Units::PRESETS.contains(&self) {
+            label
+        } else {
+            format!("{}, {}", label, self.precipitation.symbol())
+        }
+    }
+
+    pub fn temperature(self, fahrenheit: f64) -> f64 {
+        match self.temperature {
+            TemperatureUnit::Fahrenheit => fahrenheit,
+            TemperatureUnit::Celsius => (fahrenheit - 32.0) * 5.0 / 9.0,
+            TemperatureUnit::Kelvin => (fahrenheit - 32.0) * 5.0 / 9.0 + 273.15,
+        }
+    }
+
+    /// Converts a temperature difference, which unlike a reading has no offset.
+    #[cfg(feature = "gui")]
+    pub fn temperature_delta(self, fahrenheit: f64) -> f64 {
+        match self.temperature {
+            TemperatureUnit::Fahrenheit => fahrenheit,
+            TemperatureUnit::Celsius | TemperatureUnit::Kelvin => fahrenheit * 5.0 / 9.0,
+        }
+    }
+
+    /// Inverse of [`Units::temperature`], for values the user enters.
+    #[cfg(feature = "gui")]
+    pub fn to_fahrenheit(self, temperature: f64) -> f64 {
+        match self.temperature {
+            TemperatureUnit::Fahrenheit => temperature,
+            TemperatureUnit::Celsius => temperature * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => (temperature - 273.15) * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    pub fn temperature_unit(self) -> &'static str {
+        self.temperature.symbol()
+    }
+
+    pub fn speed(self, mph: f64) -> f64 {
+        self.speed.convert(mph)
+    }
+
+    /// Inverse of [`Units::speed`], for values the user enters.
+    #[cfg(feature = "gui")]
+    pub fn to_mph(self, speed: f64) -> f64 {
+        speed / self.speed.per_mph()
+    }
+
+    pub fn speed_unit(self) -> &'static str {
+        self.speed.symbol()
+    }
+
+    /// Converts a precipitation amount given in mm.
+    #[cfg(feature = "gui")]
+    pub fn precipitation(self, mm: f64) -> f64 {
+        match self.precipitation {
+            PrecipitationUnit::Inches => mm / MM_PER_INCH,
+            PrecipitationUnit::Mm => mm,
+        }
+    }
+
+    /// E.g. "72.4°F" or "22.4°C", to `precision` decimals.
+    pub fn format_temperature(self, fahrenheit: f64, precision: usize) -> String {
+        format!("{}{}", i18n::decimal(self.temperature(fahrenheit), precision), self.temperature_unit())
+    }
+
+    pub fn format_speed(self, mph: f64, precision: usize) -> String {
+        format!("{} {}", i18n::decimal(self.speed(mph), precision), self.speed_unit())
+    }
+
+    /// Miles alongside inches of rain, kilometres otherwise.
+    pub fn format_distance(self, metres: f64) -> String {
+        match self.precipitation {
+            PrecipitationUnit::Inches => format!("{} mi", i18n::decimal(metres / METRES_PER_MILE, 1)),
+            PrecipitationUnit::Mm => format!("{} km", i18n::decimal(metres / 1000.0, 1)),
+        }
+    }
+
+    /// "0.12 in" or "3.1 mm"; inches get the extra decimal they need.
+    #[cfg(feature = "gui")]
+    pub fn format_precipitation(self, mm: f64) -> String {
+        let precision = match self.precipitation {
+            PrecipitationUnit::Inches => 2,
+            PrecipitationUnit::Mm => 1,
+        };
+        format!("{} {}", i18n::decimal(self.precipitation(mm), precision), self.precipitation.symbol())
+    }
+}
+
+/// Pressure display unit, chosen separately from [`Units`] since all three
+/// are in everyday use regardless of the temperature scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureUnit {
+    #[default]
+    Hpa,
+    Inhg,
+    Mmhg,
+}
+
+impl PressureUnit {
+    #[cfg(feature = "gui")]
+    pub const ALL: [PressureUnit; 3] = [PressureUnit::Hpa, PressureUnit::Inhg, PressureUnit::Mmhg];
+
+    #[cfg(feature = "gui")]
+    pub fn label(self) -> &'static str {
+        match self {
+            PressureUnit::Hpa => "hPa",
+            PressureUnit::Inhg => "inHg",
+            PressureUnit::Mmhg => "mmHg",
+        }
+    }
+
+    /// E.g. "1013 hPa" or "29.92 inHg".
+    pub fn format(self, hpa: f64) -> String {
+        match self {
+            PressureUnit::Hpa => format!("{:.0} hPa", hpa),
+            PressureUnit::Inhg => format!("{} inHg", i18n::decimal(hpa * 0.029_53, 2)),
+            PressureUnit::Mmhg => format!("{:.0} mmHg", hpa * 0.750_06),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Notices when the forecast on screen has stopped updating, e.g. because
+//! the machine slept or the network is down, so an old forecast isn't
+//! mistaken for the current one while alerts are being issued.
+
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use eframe::egui;
+use crate::config::Config;
+use crate::app::format_duration;
+
+const DEFAULT_MISSED_REFRESHES: u32 = 3;
+// What a refresh is expected every when automatic refreshes are off.
+const FALLBACK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const WARNING_COLOR: egui::Color32 = egui::Color32::from_rgb(190, 110, 0);
+
+pub struct Watchdog {
+    missed_refreshes: u32,
+}
+
+impl Watchdog {
+    /// `STALE_AFTER_REFRESHES` sets how many refresh intervals may pass
+    /// without a successful fetch; `0` turns the check off.
+    pub fn new(config: &Config) -> Self {
+        Self { missed_refreshes: config.stale_after_refreshes().unwrap_or(DEFAULT_MISSED_REFRESHES) }
+    }
+
+    /// How old the data is, once that is too old.
+    pub fn stale_age(&self, fetched_at: DateTime<Local>, interval: Option<Duration>) -> Option<Duration> {
+        if self.missed_refreshes == 0 {
+            return None;
+        }
+        let age = (Local::now() - fetched_at).to_std().ok()?;
+        let limit = interval.unwrap_or(FALLBACK_INTERVAL).saturating_mul(self.missed_refreshes);
+        (age >= limit).then_some(age)
+    }
+}
+
+/// Full-width warning with the reason the last refresh failed, if known.
+pub fn show(ui: &mut egui::Ui, age: Duration, error: Option<&str>) {
+    egui::Frame::group(ui.style()).fill(WARNING_COLOR).show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.colored_label(
+            egui::Color32::WHITE,
+            egui::RichText::new(format!(
+                "Weather data is {} old — alerts issued since then are not shown",
+                format_duration(age)
+            ))
+            .strong(),
+        );
+        if let Some(e) = error {
+            ui.colored_label(egui::Color32::WHITE, format!("Last refresh failed: {}", e));
+        }
+    });
+}
@@ -0,0 +1,67 @@
+use eframe::egui::ColorImage;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::error::WeatherError;
+
+const WEBCAMS_URL: &str = "https://api.windy.com/webcams/api/v3/webcams";
+const SEARCH_RADIUS_KM: u32 = 50;
+
+#[derive(Debug, Deserialize)]
+struct WebcamsResponse {
+    #[serde(default)]
+    webcams: Vec<WebcamEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebcamEntry {
+    title: String,
+    images: Option<Images>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Images {
+    current: ImageUrls,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageUrls {
+    preview: String,
+}
+
+pub struct Webcam {
+    pub title: String,
+    pub image: ColorImage,
+}
+
+/// The nearest Windy webcam with its current preview image, if any is in range.
+pub async fn get_nearest_webcam(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    api_key: &str,
+) -> Result<Option<Webcam>, WeatherError> {
+    let nearby = format!("{:.4},{:.4},{}", lat, lon, SEARCH_RADIUS_KM);
+    let res = client
+        .get(WEBCAMS_URL)
+        .header("x-windy-api-key", api_key)
+        .query(&[("nearby", nearby.as_str()), ("include", "images"), ("limit", "1")])
+        .send()
+        .await?
+        .error_for_status()?;
+    let response: WebcamsResponse = res.json().await?;
+
+    let Some((title, image_url)) = response
+        .webcams
+        .into_iter()
+        .find_map(|cam| Some((cam.title, cam.images?.current.preview)))
+    else {
+        return Ok(None);
+    };
+
+    let bytes = client.get(&image_url).send().await?.error_for_status()?.bytes().await?;
+    let decoded = image::load_from_memory(&bytes)?.to_rgba8();
+    let size = [decoded.width() as usize, decoded.height() as usize];
+    let image = ColorImage::from_rgba_unmultiplied(size, decoded.as_flat_samples().as_slice());
+
+    Ok(Some(Webcam { title, image }))
+}
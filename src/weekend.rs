@@ -0,0 +1,88 @@
+//! The coming Saturday and Sunday in a few lines: what each day looks like,
+//! which is the better one, and when rain is likely.
+
+use chrono::{DateTime, Datelike, Local, TimeZone, Weekday};
+use crate::{i18n, time_format};
+use crate::units::Units;
+use crate::{capitalize_first_letter, Daily, Hourly, WeatherResponse};
+
+// Hourly chance of rain from which an hour counts towards a wet spell.
+const WET_POP: f64 = 0.5;
+// °F a day's high is compared against when both days are equally dry.
+const COMFORTABLE_F: f64 = 72.0;
+
+fn local(dt: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_opt(dt, 0).single()
+}
+
+fn is_weekend(day: &Daily) -> bool {
+    local(day.dt).is_some_and(|t| matches!(t.weekday(), Weekday::Sat | Weekday::Sun))
+}
+
+/// Lower is better: mostly the chance of rain, then distance from a mild high.
+fn score(day: &Daily) -> f64 {
+    day.pop.min(1.0) * 100.0 + (day.temp.max - COMFORTABLE_F).abs() / 2.0
+}
+
+/// Runs of likely-wet hours on the same local date as `day`, as start and
+/// end timestamps. Empty once the day is past the hourly forecast.
+fn wet_spells(day: &Daily, hourly: &[Hourly]) -> Vec<(i64, i64)> {
+    let Some(date) = local(day.dt).map(|t| t.date_naive()) else { return Vec::new() };
+    let mut spells: Vec<(i64, i64)> = Vec::new();
+    for hour in hourly.iter().filter(|h| h.pop >= WET_POP && local(h.dt).is_some_and(|t| t.date_naive() == date)) {
+        match spells.last_mut() {
+            Some((_, end)) if *end == hour.dt => *end = hour.dt + 3600,
+            _ => spells.push((hour.dt, hour.dt + 3600)),
+        }
+    }
+    spells
+}
+
+/// One line per weekend day, then the best day and any wet spells; `None`
+/// when the daily forecast doesn't reach the weekend.
+pub fn summarize(weather: &WeatherResponse, units: Units) -> Option<Vec<String>> {
+    let start = weather.daily.iter().position(is_weekend)?;
+    let days: Vec<&Daily> = weather.daily[start..].iter().take(2).take_while(|day| is_weekend(day)).collect();
+    let name = |day: &Daily| local(day.dt).map(|t| i18n::format_datetime(&t, "%A")).unwrap_or_default();
+
+    let mut lines: Vec<String> = days
+        .iter()
+        .map(|day| {
+            let description = day.weather.first().map_or(String::new(), |w| capitalize_first_letter(&w.description) + ", ");
+            format!(
+                "{}: {}{} / {}, {:.0}% chance of rain",
+                name(day),
+                description,
+                units.format_temperature(day.temp.max, 0),
+                units.format_temperature(day.temp.min, 0),
+                (day.pop.min(1.0) * 100.0).round()
+            )
+        })
+        .collect();
+    if let [first, second] = days[..] {
+        let best = if score(second) < score(first) { second } else { first };
+        lines.push(format!("Best day: {}", name(best)));
+    }
+    for day in &days {
+        for (start, end) in wet_spells(day, &weather.hourly) {
+            lines.push(format!(
+                "Rain likely {} {}–{}",
+                name(day),
+                time_format::local_time(start),
+                time_format::local_time(end)
+            ));
+        }
+    }
+    Some(lines)
+}
+
+/// A card that is left out when the forecast doesn't cover the weekend.
+#[cfg(feature = "gui")]
+pub fn show(ui: &mut eframe::egui::Ui, weather: &WeatherResponse, units: Units) {
+    let Some(lines) = summarize(weather, units) else { return };
+    crate::session::card(ui, "This weekend", true, |ui| {
+        for line in lines {
+            ui.label(line);
+        }
+    });
+}
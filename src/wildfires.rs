@@ -0,0 +1,75 @@
+use reqwest::Client;
+use crate::error::WeatherError;
+use crate::geo::distance_km;
+
+const FIRMS_AREA_URL: &str = "https://firms.modaps.eosdis.nasa.gov/api/area/csv";
+const FIRMS_SOURCE: &str = "VIIRS_SNPP_NRT";
+// FIRMS accepts 1-10 days; two covers a full overpass cycle.
+const LOOKBACK_DAYS: u32 = 2;
+pub const SEARCH_RADIUS_KM: f64 = 100.0;
+// Degrees of latitude per kilometer, used to size the query box.
+const DEG_PER_KM: f64 = 1.0 / 111.0;
+
+#[derive(Debug)]
+pub struct Hotspot {
+    pub distance_km: f64,
+    pub acquired: String,
+    // Fire radiative power in megawatts.
+    pub frp: f64,
+}
+
+/// NASA FIRMS satellite fire detections within `SEARCH_RADIUS_KM`, nearest first.
+pub async fn get_hotspots(
+    client: &Client,
+    lat: f64,
+    lon: f64,
+    map_key: &str,
+) -> Result<Vec<Hotspot>, WeatherError> {
+    let lat_span = SEARCH_RADIUS_KM * DEG_PER_KM;
+    let lon_span = lat_span / lat.to_radians().cos().max(0.1);
+    let area = format!(
+        "{:.3},{:.3},{:.3},{:.3}",
+        lon - lon_span,
+        lat - lat_span,
+        lon + lon_span,
+        lat + lat_span
+    );
+    let url = format!("{}/{}/{}/{}/{}", FIRMS_AREA_URL, map_key, FIRMS_SOURCE, area, LOOKBACK_DAYS);
+    let csv = client.get(&url).send().await?.error_for_status()?.text().await?;
+
+    let mut lines = csv.lines();
+    let header: Vec<&str> = lines.next().unwrap_or_default().split(',').collect();
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (Some(lat_col), Some(lon_col), Some(date_col), Some(time_col), Some(frp_col)) = (
+        column("latitude"),
+        column("longitude"),
+        column("acq_date"),
+        column("acq_time"),
+        column("frp"),
+    ) else {
+        // FIRMS reports bad keys and quota problems as a bare text line.
+        return Err(WeatherError::Service(format!("Unexpected FIRMS response: {}", csv.trim())));
+    };
+
+    let mut hotspots: Vec<Hotspot> = lines
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            let hot_lat: f64 = fields.get(lat_col)?.parse().ok()?;
+            let hot_lon: f64 = fields.get(lon_col)?.parse().ok()?;
+            // acq_time is HHMM in UTC.
+            let time = fields.get(time_col)?;
+            let time = match time.len() {
+                4 => format!("{}:{}", &time[..2], &time[2..]),
+                _ => time.to_string(),
+            };
+            Some(Hotspot {
+                distance_km: distance_km(lat, lon, hot_lat, hot_lon),
+                acquired: format!("{} {} UTC", fields.get(date_col)?, time),
+                frp: fields.get(frp_col)?.parse().unwrap_or_default(),
+            })
+        })
+        .filter(|h| h.distance_km <= SEARCH_RADIUS_KM)
+        .collect();
+    hotspots.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    Ok(hotspots)
+}
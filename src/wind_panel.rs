@@ -0,0 +1,83 @@
+use eframe::egui;
+use crate::config::Config;
+use crate::i18n;
+use crate::session;
+use crate::time_format;
+use crate::units::Units;
+use crate::{degrees_to_cardinal, Hourly};
+
+const DEFAULT_FLYABLE_WIND_MPH: f64 = 15.0;
+const HOURS_SHOWN: usize = 24;
+const FLYABLE_COLOR: egui::Color32 = egui::Color32::from_rgb(40, 110, 60);
+
+/// Hour-by-hour wind strip for drone and kite pilots.
+pub struct WindPanel {
+    // Hours where both sustained wind and gusts stay at or below this are highlighted.
+    flyable_threshold_mph: f64,
+}
+
+impl WindPanel {
+    pub fn new(config: &Config) -> Self {
+        Self { flyable_threshold_mph: config.flyable_wind_mph().unwrap_or(DEFAULT_FLYABLE_WIND_MPH) }
+    }
+
+    /// Returns the new threshold, in mph, when it was changed.
+    pub fn show(&mut self, ui: &mut egui::Ui, hourly: &[Hourly], units: Units) -> Option<f64> {
+        let mut changed = None;
+        session::card(ui, "Hourly wind", false, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(i18n::tr("Flyable up to"));
+                // The threshold is kept in mph, like the forecast itself.
+                let mut threshold = units.speed(self.flyable_threshold_mph);
+                let response = ui.add(
+                    egui::DragValue::new(&mut threshold)
+                        .clamp_range(0.0..=units.speed(60.0))
+                        .suffix(format!(" {}", units.speed_unit())),
+                );
+                if response.changed() {
+                    self.flyable_threshold_mph = units.to_mph(threshold);
+                    changed = Some(self.flyable_threshold_mph);
+                }
+            });
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for hour in hourly.iter().take(HOURS_SHOWN) {
+                        self.show_hour(ui, hour, units);
+                    }
+                });
+            });
+        });
+        changed
+    }
+
+    fn show_hour(&self, ui: &mut egui::Ui, hour: &Hourly, units: Units) {
+        let gust = hour.wind_gust.unwrap_or(hour.wind_speed);
+        let flyable = hour.wind_speed <= self.flyable_threshold_mph && gust <= self.flyable_threshold_mph;
+
+        let mut frame = egui::Frame::group(ui.style());
+        if flyable {
+            frame = frame.fill(FLYABLE_COLOR);
+        }
+        frame.show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.set_width(56.0);
+                ui.label(time_format::local_hour(hour.dt));
+                wind_arrow(ui, hour.wind_deg);
+                ui.label(units.format_speed(hour.wind_speed, 0));
+                ui.small(format!("G {:.0} {}", units.speed(gust), units.speed.symbol()));
+                ui.small(degrees_to_cardinal(hour.wind_deg));
+            });
+        });
+    }
+}
+
+// Draws an arrow pointing the way the wind blows (meteorological degrees give
+// the direction it comes from).
+fn wind_arrow(ui: &mut egui::Ui, wind_deg: u16) {
+    let size = 24.0;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let heading = (wind_deg as f32 + 180.0).to_radians();
+    let direction = egui::vec2(heading.sin(), -heading.cos()) * (size * 0.8);
+    let stroke = egui::Stroke::new(2.0, ui.visuals().text_color());
+    ui.painter().arrow(rect.center() - direction * 0.5, direction, stroke);
+}